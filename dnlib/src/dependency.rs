@@ -0,0 +1,213 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::proof::Proof;
+use crate::record::Statement;
+
+/// The records a proof could do without.
+#[derive(Debug, Default, PartialEq)]
+pub struct DeadRecords {
+    /// The records the last one does not depend on
+    pub steps: Vec<usize>,
+    /// The hypotheses the record ending their box does not depend on
+    pub hypotheses: Vec<usize>,
+}
+
+impl DeadRecords {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty() && self.hypotheses.is_empty()
+    }
+}
+
+impl Proof {
+    /// Returns the records the record `id` transitively depends on, through the
+    /// [`premises`](Proof::premises) of each record.
+    pub fn dependencies(&self, id: usize) -> BTreeSet<usize> {
+        let mut dependencies = BTreeSet::new();
+        let mut pending = self.premises(id);
+        while let Some(premise) = pending.pop() {
+            if dependencies.insert(premise) {
+                pending.extend(self.premises(premise));
+            }
+        }
+        dependencies
+    }
+
+    /// Returns the dependency graph of the proof, as the premises of each record: the edges go
+    /// from a record to later ones, so the graph is acyclic.
+    pub fn dependency_graph(&self) -> Vec<Vec<usize>> {
+        (0..self.records().len())
+            .map(|id| self.premises(id))
+            .collect()
+    }
+
+    /// Finds the steps the conclusion, the last record, does not depend on, and the hypotheses
+    /// assumed without being used to reach the end of their box. The hypotheses still open at
+    /// the end stand for the premises of the proof: they are not dead steps, but are unused if
+    /// the conclusion does not depend on them.
+    pub fn dead_records(&self) -> DeadRecords {
+        let Some(last) = self.records().last() else {
+            return DeadRecords::default();
+        };
+        let mut live = self.dependencies(last.id);
+        live.insert(last.id);
+        live.extend(last.ctxt.iter().copied());
+        let steps = (0..self.records().len())
+            .filter(|id| !live.contains(id))
+            .collect();
+        // The record ending the box of each hypothesis. A context may point past the records
+        // of a proof that was not checked, such boxes are left out.
+        let mut ends: Vec<Option<usize>> = vec![None; self.records().len()];
+        for hyp in &last.ctxt {
+            if let Some(end) = ends.get_mut(*hyp) {
+                *end = Some(last.id);
+            }
+        }
+        for (id, rec) in self.records().iter().enumerate().skip(1) {
+            if matches!(rec.stmt, Statement::Donc(_)) {
+                if let Some(end) = self.records()[id - 1]
+                    .ctxt
+                    .last()
+                    .and_then(|hyp| ends.get_mut(*hyp))
+                {
+                    *end = Some(id - 1);
+                }
+            }
+        }
+        let hypotheses = self
+            .records()
+            .iter()
+            .filter(|rec| matches!(rec.stmt, Statement::Supposons(_)))
+            .filter(|rec| match ends.get(rec.id).copied().flatten() {
+                Some(end) => end != rec.id && !self.dependencies(end).contains(&rec.id),
+                None => false,
+            })
+            .map(|rec| rec.id)
+            .collect();
+        DeadRecords { steps, hypotheses }
+    }
+
+    /// Writes the dependency graph in the DOT language of Graphviz: each record is a node,
+    /// with an edge from each of its premises, and each box is a cluster. The dead steps are
+    /// dashed and the unused hypotheses are red.
+    pub fn to_dot(&self) -> String {
+        let dead = self.dead_records();
+        let mut dot = String::from("digraph proof {\n  node [shape=box];\n");
+        let mut open: Vec<usize> = Vec::new();
+        for rec in self.records() {
+            let common = open
+                .iter()
+                .zip(&rec.ctxt)
+                .take_while(|(a, b)| a == b)
+                .count();
+            while open.len() > common {
+                open.pop();
+                writeln!(dot, "{}}}", "  ".repeat(open.len() + 1)).unwrap();
+            }
+            for hyp in &rec.ctxt[common..] {
+                writeln!(
+                    dot,
+                    "{}subgraph cluster_{} {{",
+                    "  ".repeat(open.len() + 1),
+                    hyp
+                )
+                .unwrap();
+                open.push(*hyp);
+            }
+            let mut attributes = format!(
+                "label=\"{}: {}\\n{}\"",
+                rec.id,
+                escape(&rec.stmt.to_string()),
+                escape(&rec.justif.to_string())
+            );
+            if dead.steps.contains(&rec.id) {
+                attributes.push_str(", style=dashed");
+            }
+            if dead.hypotheses.contains(&rec.id) {
+                attributes.push_str(", color=red");
+            }
+            writeln!(
+                dot,
+                "{}{} [{}];",
+                "  ".repeat(open.len() + 1),
+                rec.id,
+                attributes
+            )
+            .unwrap();
+        }
+        while !open.is_empty() {
+            open.pop();
+            writeln!(dot, "{}}}", "  ".repeat(open.len() + 1)).unwrap();
+        }
+        for (id, premises) in self.dependency_graph().iter().enumerate() {
+            for premise in premises {
+                writeln!(dot, "  {} -> {};", premise, id).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Escapes a DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "0;0;Supposons a;Hyp\n\
+                         1;0,1;Supposons b;Hyp\n\
+                         2;0,1;a;Rwrt 0\n\
+                         3;0;Donc b⇒a;IImpl\n\
+                         4;0;a∧a;IAnd 0 0\n\
+                         5;0;b⇒a;Rwrt 3\n\
+                         6;;Donc a⇒b⇒a;IImpl\n";
+
+    #[test]
+    fn dependencies() {
+        let proof = Proof::read_proof(INPUT).unwrap();
+        let deps: Vec<usize> = proof.dependencies(6).into_iter().collect();
+        assert_eq!(deps, vec![0, 1, 2, 3, 5]);
+        assert_eq!(proof.dependency_graph()[4], vec![0]);
+        assert_eq!(
+            proof.dead_records(),
+            DeadRecords {
+                steps: vec![4],
+                hypotheses: vec![1],
+            }
+        );
+        // The premises left open are not dead steps, but may be unused.
+        let open =
+            Proof::read_proof("0;0;Supposons a;Hyp\n1;0,1;Supposons b;Hyp\n2;0,1;a;Rwrt 0\n")
+                .unwrap();
+        assert_eq!(open.dead_records().hypotheses, vec![1]);
+        assert!(open.dead_records().steps.is_empty());
+    }
+
+    #[test]
+    fn malformed() {
+        // The contexts and ids point past the records, the proof not being checked
+        let proof = Proof::read_proof(
+            "0;0,5;Supposons a;Hyp\n1;0,7;a;Rwrt 0\n2;0,9;a;Rwrt 1\n3;0;Donc a⇒a;IImpl\n8;0,5;a;Rwrt 0\n",
+        )
+        .unwrap();
+        assert!(!proof.dead_records().is_empty());
+        let dot = proof.to_dot();
+        assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+    }
+
+    #[test]
+    fn dot() {
+        let dot = Proof::read_proof(INPUT).unwrap().to_dot();
+        assert!(dot.starts_with("digraph proof {\n"));
+        assert!(dot.contains(
+            "    subgraph cluster_1 {\n      1 [label=\"1: Supposons b\\nHyp\", color=red];\n"
+        ));
+        assert!(dot.contains("  4 [label=\"4: a∧a\\nIAnd 0 0\", style=dashed];"));
+        assert!(dot.contains("  0 -> 4;\n"));
+        assert_eq!(dot.matches('{').count(), dot.matches('}').count());
+    }
+}
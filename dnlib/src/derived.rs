@@ -0,0 +1,338 @@
+use std::collections::BTreeSet;
+
+use thiserror::Error;
+
+use crate::formula::Formula;
+use crate::justif::Jusitification;
+use crate::proof::{CheckUpResult, Logic, Proof, ReadError, SemanticError};
+use crate::justif::ReadError as JustifReadError;
+use crate::record::{Record, Statement};
+use crate::rule::{cited_formulas, read_named, Rule};
+use crate::sequent::{RecordError, Sequent};
+use crate::subst::Substitution;
+
+#[derive(Error, Debug)]
+pub enum DerivedRuleError {
+    /// The declaration has no `:` after the name
+    #[error("A derived rule is declared as Name: premise, … ⊢ conclusion")]
+    MissingName,
+    /// Records could not cite the rule by this name
+    #[error("{0} is not a valid rule name: a capital letter followed by letters and digits, other than a primitive rule")]
+    InvalidName(String),
+    /// The schema is not a sequent
+    #[error("Error reading the schema of the rule: {0}")]
+    InvalidSchema(RecordError),
+    /// The schema concludes no formula, or several
+    #[error("The schema should have exactly one conclusion")]
+    NotOneConclusion,
+    /// The proof of the rule could not be read
+    #[error("Error reading the proof of the rule: {0}")]
+    InvalidProofSyntax(ReadError),
+    /// The proof is not valid, even in classical logic
+    #[error("The proof of the rule is not valid, the first error being at record {first_error}")]
+    InvalidProof {
+        first_error: usize,
+        errors: Vec<(usize, SemanticError)>,
+    },
+    /// The premises of a rule are assumed, the proof may not state premises of its own
+    #[error("The proof of a rule assumes its premises in Supposons boxes, it may not declare a theorem or premises")]
+    Premise,
+    /// The proof does not prove the schema
+    #[error("The proof should assume the premises in order, in boxes it leaves open, and end with the conclusion")]
+    NotMatchingSchema,
+}
+
+/// A rule derived from the primitive ones, such as modus tollens `MT: A⇒B, ¬B ⊢ ¬A`.
+///
+/// Its proof is checked once, when the rule is declared. Records of a proof the rule is added to
+/// then cite it as `MT 4 7`, each variable of the schema standing for any formula.
+#[derive(Clone, Debug)]
+pub struct DerivedRule {
+    name: String,
+    premises: Vec<Formula>,
+    conclusion: Formula,
+    logic: Logic,
+    /// The variables introduced by `Soit` in the proof
+    eigenvariables: BTreeSet<char>,
+}
+
+impl DerivedRule {
+    /// Declares the rule `declaration`, such as `MT: A⇒B, ¬B ⊢ ¬A`, proved by `proof`. As the
+    /// proofs of [`crate::prover::prove`], the proof assumes each premise in a box it leaves
+    /// open, in order, and its last record states the conclusion.
+    ///
+    /// The rule holds in the weakest logic its proof is valid in: it is classical when its proof
+    /// needs `Raa`. The proof may itself use the derived rules added to it.
+    pub fn new(declaration: &str, mut proof: Proof) -> Result<Self, DerivedRuleError> {
+        let (name, schema) = declaration
+            .split_once(':')
+            .ok_or(DerivedRuleError::MissingName)?;
+        let name = name.trim();
+        if !matches!(
+            Jusitification::read(name),
+            Ok(Jusitification::Named(_, _))
+        ) {
+            return Err(DerivedRuleError::InvalidName(name.to_string()));
+        }
+        let schema = Sequent::read(schema).map_err(DerivedRuleError::InvalidSchema)?;
+        let [conclusion] = &schema.succedent[..] else {
+            return Err(DerivedRuleError::NotOneConclusion);
+        };
+        let premise = proof
+            .records()
+            .iter()
+            .any(|rec| matches!(rec.stmt, Statement::Premise(_)));
+        if premise || proof.theorem().is_some() {
+            return Err(DerivedRuleError::Premise);
+        }
+        let logic = [Logic::Minimal, Logic::Intuitionistic, Logic::Classical]
+            .into_iter()
+            .find(|logic| {
+                proof.set_logic(*logic);
+                proof.check();
+                matches!(proof.state(), CheckUpResult::Valid)
+            });
+        let Some(logic) = logic else {
+            return Err(match proof.into_state() {
+                CheckUpResult::SemanticErrors {
+                    first_error,
+                    errors,
+                } => DerivedRuleError::InvalidProof {
+                    first_error,
+                    errors,
+                },
+                _ => DerivedRuleError::InvalidProof {
+                    first_error: 0,
+                    errors: Vec::new(),
+                },
+            });
+        };
+        let last = proof
+            .records()
+            .last()
+            .ok_or(DerivedRuleError::NotMatchingSchema)?;
+        let assumed: Vec<&Formula> = last
+            .ctxt
+            .iter()
+            .filter_map(|b| match &proof.records()[*b].stmt {
+                Statement::Supposons(h) => Some(h),
+                _ => None,
+            })
+            .collect();
+        let proves_schema = assumed.len() == last.ctxt.len()
+            && assumed.into_iter().eq(schema.antecedent.iter())
+            && last.stmt.get_formula() == conclusion;
+        if !proves_schema {
+            return Err(DerivedRuleError::NotMatchingSchema);
+        }
+        let eigenvariables = proof
+            .records()
+            .iter()
+            .filter_map(|rec| match rec.stmt {
+                Statement::Soit(x) => Some(x),
+                _ => None,
+            })
+            .collect();
+        Ok(Self {
+            name: name.to_string(),
+            conclusion: conclusion.clone(),
+            premises: schema.antecedent,
+            logic,
+            eigenvariables,
+        })
+    }
+
+    /// Reads a rule from a file whose first line is its declaration, the proof following. The
+    /// proof may use `rules`.
+    pub fn read(input: &str, rules: &[DerivedRule]) -> Result<Self, DerivedRuleError> {
+        let (declaration, proof) = input.split_once('\n').unwrap_or((input, ""));
+        // The blank line keeps the positions of reading errors those of the file.
+        let mut proof = Proof::read_proof(&format!("\n{}", proof))
+            .map_err(DerivedRuleError::InvalidProofSyntax)?;
+        for rule in rules {
+            proof.add_rule(rule.clone());
+        }
+        Self::new(declaration, proof)
+    }
+
+    pub fn premises(&self) -> &[Formula] {
+        &self.premises
+    }
+
+    pub fn conclusion(&self) -> &Formula {
+        &self.conclusion
+    }
+
+    /// Tells whether the rule may be used in a proof checked in `logic`.
+    pub fn holds_in(&self, logic: Logic) -> bool {
+        self.logic.holds_in(logic)
+    }
+
+    /// Tells whether one substitution turns the premises of the schema into `premises` and its
+    /// conclusion into `conclusion`. The variables introduced by `Soit` in the proof of the rule
+    /// have to stay fresh: no formula the substitution replaces a variable with has them free.
+    pub fn matches(&self, premises: &[&Formula], conclusion: &Formula) -> bool {
+        let mut subst = Substitution::new();
+        self.premises.len() == premises.len()
+            && self
+                .premises
+                .iter()
+                .zip(premises)
+                .all(|(pattern, premise)| pattern.match_pattern_into(premise, &mut subst))
+            && self.conclusion.match_pattern_into(conclusion, &mut subst)
+            && subst.iter().all(|(_, f)| {
+                f.free_term_variables()
+                    .is_disjoint(&self.eigenvariables)
+            })
+    }
+}
+
+impl Rule for DerivedRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, JustifReadError> {
+        read_named(&self.name, args)
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        if !self.holds_in(proof.logic()) {
+            return Err(SemanticError::DerivedNotInLogic);
+        }
+        let formulas = cited_formulas(proof, rec, self.premises.len())?;
+        if self.matches(&formulas, rec.stmt.get_formula()) {
+            Ok(())
+        } else {
+            Err(SemanticError::DerivedNotMatching)
+        }
+    }
+
+    /// Returns the weakest logic the rule holds in.
+    fn logic(&self) -> Logic {
+        self.logic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MT: &str = "0;0;Supposons A⇒B;Hyp\n\
+                      1;0,1;Supposons ¬B;Hyp\n\
+                      2;0,1,2;Supposons A;Hyp\n\
+                      3;0,1,2;B;EImpl 2 0\n\
+                      4;0,1,2;B⇒⊥;Rwrt 1\n\
+                      5;0,1,2;⊥;EImpl 3 4\n\
+                      6;0,1;Donc A⇒⊥;IImpl\n\
+                      7;0,1;¬A;Rwrt 6\n";
+
+    fn rule(declaration: &str, proof: &str) -> Result<DerivedRule, DerivedRuleError> {
+        DerivedRule::new(declaration, Proof::read_proof(proof).unwrap())
+    }
+
+    #[test]
+    fn declarations() {
+        let mt = rule("MT: A⇒B, ¬B ⊢ ¬A", MT).unwrap();
+        assert_eq!(mt.logic(), Logic::Minimal);
+        assert!(mt.holds_in(Logic::Minimal));
+        let raa = "0;0;Supposons ¬¬A;Hyp\n1;0;A;Raa 0\n";
+        let dne = rule("DNE: ¬¬A ⊢ A", raa).unwrap();
+        assert_eq!(dne.logic(), Logic::Classical);
+        assert!(!dne.holds_in(Logic::Intuitionistic));
+        assert!(matches!(
+            rule("MT: ¬B, A⇒B ⊢ ¬A", MT),
+            Err(DerivedRuleError::NotMatchingSchema)
+        ));
+        assert!(matches!(
+            rule("EImpl: A⇒B, ¬B ⊢ ¬A", MT),
+            Err(DerivedRuleError::InvalidName(_))
+        ));
+        assert!(matches!(
+            rule(
+                "MT: A⇒B, ¬B ⊢ ¬A",
+                "0;0;Supposons A⇒B;Hyp\n1;0;¬A;EAndL 0\n"
+            ),
+            Err(DerivedRuleError::InvalidProof { first_error: 1, .. })
+        ));
+        // The proof would derive anything from nothing
+        assert!(matches!(
+            rule("Bad: ⊢ A", "0;;Prémisse A;Hyp\n"),
+            Err(DerivedRuleError::Premise)
+        ));
+        assert!(matches!(
+            rule("Bad: ⊢ A", "Théorème A ⊢ A\n0;;Prémisse A;Hyp\n1;;A;Rwrt 0\n"),
+            Err(DerivedRuleError::Premise)
+        ));
+        assert!(matches!(
+            rule("Top: ⊢ ⊤", "Théorème ⊢ ⊤\n0;;⊤;ITop\n"),
+            Err(DerivedRuleError::Premise)
+        ));
+    }
+
+    #[test]
+    fn citations() {
+        let mut proof = Proof::read_proof(
+            "0;0;Supposons p∧q⇒r;Hyp\n\
+             1;0,1;Supposons ¬r;Hyp\n\
+             2;0,1;¬(p∧q);MT 0 1\n\
+             3;0;Donc ¬r⇒¬(p∧q);IImpl\n",
+        )
+        .unwrap();
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 2, .. }
+        ));
+        proof.add_rule(rule("MT: A⇒B, ¬B ⊢ ¬A", MT).unwrap());
+        proof.set_logic(Logic::Minimal);
+        proof.check();
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+
+        let mut proof = Proof::read_proof("0;0;Supposons ¬¬p;Hyp\n1;0;p;DNE 0\n").unwrap();
+        let raa = "0;0;Supposons ¬¬A;Hyp\n1;0;A;Raa 0\n";
+        proof.add_rule(rule("DNE: ¬¬A ⊢ A", raa).unwrap());
+        proof.check();
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        assert_eq!(proof.classical_dependencies()[1].len(), 1);
+        proof.set_logic(Logic::Intuitionistic);
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn capture() {
+        let generalization = "0;0;Supposons A;Hyp\n\
+                              1;0,1;Soit y;Hyp\n\
+                              2;0,1;A;Rwrt 0\n\
+                              3;0;Donc ∀yA;IForall\n";
+        let q = rule("Q: A ⊢ ∀yA", generalization).unwrap();
+        let f = |input: &str| Formula::read(input).unwrap();
+        assert!(q.matches(&[&f("P(x)")], &f("∀yP(x)")));
+        assert!(!q.matches(&[&f("P(y)")], &f("∀yP(y)")));
+        // The eigenvariable y stays fresh, although the schema does not bind it.
+        let detour = "0;0;Supposons A;Hyp\n\
+                      1;0,1;Soit y;Hyp\n\
+                      2;0,1;A;Rwrt 0\n\
+                      3;0;Donc ∀yA;IForall\n\
+                      4;0;A;EForall 3 y\n";
+        let r = rule("R: A ⊢ A", detour).unwrap();
+        assert!(r.matches(&[&f("P(x)")], &f("P(x)")));
+        assert!(!r.matches(&[&f("P(y)")], &f("P(y)")));
+        let mut proof = Proof::read_proof(
+            "0;0;Supposons P(y);Hyp\n\
+             1;0;∀yP(y);Q 0\n\
+             2;;Donc P(y)⇒∀yP(y);IImpl\n",
+        )
+        .unwrap();
+        proof.add_rule(q);
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::mem::discriminant;
+
+use crate::proof::Proof;
+use crate::record::Record;
+
+/// What became of a record from a version of a proof to another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The record is the same
+    Unchanged { old: usize, new: usize },
+    /// The record is the same, but its id or its references shifted with the renumbering
+    Renumbered { old: usize, new: usize },
+    /// The record states the same, but is justified differently or in another box
+    Rejustified { old: usize, new: usize },
+    /// The record is of the same kind and rule, but states something else
+    Reformulated { old: usize, new: usize },
+    /// The record states the same by the same rule, but among other records
+    Moved { old: usize, new: usize },
+    /// The record is only in the old version
+    Removed(usize),
+    /// The record is only in the new version
+    Added(usize),
+}
+
+/// The changes from a version of a proof to another, in the order of the records.
+#[derive(Clone, Debug)]
+pub struct ProofDiff {
+    pub old: Vec<Record>,
+    pub new: Vec<Record>,
+    pub changes: Vec<Change>,
+}
+
+impl ProofDiff {
+    /// Tells whether both versions have the same records.
+    pub fn is_empty(&self) -> bool {
+        self.changes
+            .iter()
+            .all(|change| matches!(change, Change::Unchanged { .. }))
+    }
+}
+
+/// How much aligning `old` with `new` is worth: most if they state the same by the same rule,
+/// nothing if they are of different kinds or state and justify both differently.
+fn affinity(old: &Record, new: &Record) -> usize {
+    let same_statement = old.stmt.to_string() == new.stmt.to_string();
+    let same_rule = old.justif.name() == new.justif.name();
+    match (same_statement, same_rule) {
+        (true, true) => 3,
+        (true, false) => 2,
+        (false, true) if discriminant(&old.stmt) == discriminant(&new.stmt) => 1,
+        _ => 0,
+    }
+}
+
+impl Proof {
+    /// Compares the records of this proof with those of `other`, its new version. The records
+    /// are aligned keeping their order, preferably those stating the same formula, then
+    /// those justified by the same rule; a record left out and stated by the same rule elsewhere
+    /// is moved. A record aligned with another is unchanged if it cites the records aligned
+    /// with those the other one cites.
+    pub fn diff(&self, other: &Proof) -> ProofDiff {
+        let (old, new) = (self.records(), other.records());
+        // best[i][j] is the affinity of the best alignment of old[i..] and new[j..].
+        let mut best = vec![vec![0; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                let aligned = match affinity(&old[i], &new[j]) {
+                    0 => 0,
+                    a => a + best[i + 1][j + 1],
+                };
+                best[i][j] = aligned.max(best[i + 1][j]).max(best[i][j + 1]);
+            }
+        }
+        let mut pairs = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() {
+                let a = affinity(&old[i], &new[j]);
+                if a > 0 && best[i][j] == a + best[i + 1][j + 1] {
+                    pairs.push((Some(i), Some(j)));
+                    i += 1;
+                    j += 1;
+                    continue;
+                }
+            }
+            if i < old.len() && (j == new.len() || best[i][j] == best[i + 1][j]) {
+                pairs.push((Some(i), None));
+                i += 1;
+            } else {
+                pairs.push((None, Some(j)));
+                j += 1;
+            }
+        }
+        // A record removed then added elsewhere is moved.
+        let only = |pair: &(Option<usize>, Option<usize>), old: bool| match *pair {
+            (Some(i), None) if old => Some(i),
+            (None, Some(j)) if !old => Some(j),
+            _ => None,
+        };
+        let mut moved = vec![None; old.len()];
+        for i in pairs.iter().filter_map(|pair| only(pair, true)) {
+            moved[i] = pairs
+                .iter()
+                .filter_map(|pair| only(pair, false))
+                .filter(|j| !moved.contains(&Some(*j)))
+                .find(|j| affinity(&old[i], &new[*j]) == 3);
+        }
+        let mut position = vec![usize::MAX; old.len()];
+        for pair in &pairs {
+            if let (Some(i), Some(j)) = pair {
+                position[*i] = *j;
+            }
+        }
+        for (i, j) in moved.iter().enumerate() {
+            if let Some(j) = j {
+                position[i] = *j;
+            }
+        }
+        let map = |i: usize| position.get(i).copied().unwrap_or(usize::MAX);
+        let changes = pairs
+            .into_iter()
+            .filter(|pair| !matches!(pair, (Some(i), None) if moved[*i].is_some()))
+            .map(|pair| match pair {
+                (Some(i), Some(j)) => {
+                    let (o, n) = (&old[i], &new[j]);
+                    let mut renumbered = o.clone();
+                    renumbered.renumber(map);
+                    if o.stmt.to_string() != n.stmt.to_string() {
+                        Change::Reformulated { old: i, new: j }
+                    } else if renumbered.to_string() != n.to_string() {
+                        Change::Rejustified { old: i, new: j }
+                    } else if o.to_string() != n.to_string() {
+                        Change::Renumbered { old: i, new: j }
+                    } else {
+                        Change::Unchanged { old: i, new: j }
+                    }
+                }
+                (Some(i), None) => Change::Removed(i),
+                (None, Some(j)) => match moved.iter().position(|m| *m == Some(j)) {
+                    Some(i) => Change::Moved { old: i, new: j },
+                    None => Change::Added(j),
+                },
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        ProofDiff {
+            old: old.to_vec(),
+            new: new.to_vec(),
+            changes,
+        }
+    }
+}
+
+impl fmt::Display for ProofDiff {
+    /// Prints the changes, one per line: `-` a removed record, `+` an added one, `~` a changed
+    /// one, `>` a moved one and `=` one only renumbered, with its former version.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            match *change {
+                Change::Unchanged { .. } => (),
+                Change::Renumbered { old, new } => {
+                    writeln!(f, "= {} (renumbered, was {})", self.new[new], self.old[old])?
+                }
+                Change::Rejustified { old, new } => writeln!(
+                    f,
+                    "~ {} (re-justified, was {})",
+                    self.new[new], self.old[old]
+                )?,
+                Change::Reformulated { old, new } => writeln!(
+                    f,
+                    "~ {} (reformulated, was {})",
+                    self.new[new], self.old[old]
+                )?,
+                Change::Moved { old, new } => {
+                    writeln!(f, "> {} (moved, was {})", self.new[new], self.old[old])?
+                }
+                Change::Removed(old) => writeln!(f, "- {}", self.old[old])?,
+                Change::Added(new) => writeln!(f, "+ {}", self.new[new])?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        let old = Proof::read_proof(
+            "0;0;Supposons a∧b;Hyp\n\
+             1;0;a;EAndL 0\n\
+             2;0;b;EAndR 0\n\
+             3;0;b∧a;IAnd 2 1\n\
+             4;;Donc a∧b⇒b∧a;IImpl\n",
+        )
+        .unwrap();
+        let new = Proof::read_proof(
+            "0;0;Supposons a∧b;Hyp\n\
+             1;0;b;EAndR 0\n\
+             2;0;a;EAndL 0\n\
+             3;0;a∧b;Rwrt 0\n\
+             4;0;b∧a;IAnd 1 2\n\
+             5;;Donc a∧b⇒b∧a;IImpl\n",
+        )
+        .unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.changes,
+            vec![
+                Change::Unchanged { old: 0, new: 0 },
+                Change::Renumbered { old: 2, new: 1 },
+                Change::Moved { old: 1, new: 2 },
+                Change::Added(3),
+                Change::Renumbered { old: 3, new: 4 },
+                Change::Renumbered { old: 4, new: 5 },
+            ]
+        );
+        assert!(diff.to_string().starts_with(
+            "= 1;0;b;EAndR 0 (renumbered, was 2;0;b;EAndR 0)\n> 2;0;a;EAndL 0 (moved, was 1;0;a;EAndL 0)\n+ 3;0;a∧b;Rwrt 0\n"
+        ));
+        assert!(old.diff(&old).is_empty());
+        let new = Proof::read_proof(&old.to_string().replace("IAnd 2 1", "IAnd 2 2")).unwrap();
+        assert_eq!(
+            old.diff(&new).changes[3],
+            Change::Rejustified { old: 3, new: 3 }
+        );
+
+        let new = Proof::read_proof(
+            "0;0;Supposons a∧b;Hyp\n\
+             1;0;a;EAndL 0\n\
+             2;0;b;EAndR 0\n\
+             3;0;a∧b;IAnd 1 2\n\
+             4;;Donc a∧b⇒a∧b;IImpl\n",
+        )
+        .unwrap();
+        let changes = old.diff(&new).changes;
+        assert_eq!(changes[3], Change::Reformulated { old: 3, new: 3 });
+        assert_eq!(changes[4], Change::Reformulated { old: 4, new: 4 });
+    }
+}
@@ -0,0 +1,315 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::proof::Proof;
+use crate::record::Record;
+
+#[derive(Error, Debug)]
+pub enum EditError {
+    /// There is no record at this position
+    #[error("There is no record {0}")]
+    NoRecord(usize),
+    /// A record cannot be removed while a later one cites it or is in its box
+    #[error("Record {record} is used by record {by}")]
+    Used { record: usize, by: usize },
+    /// A block cannot be moved inside itself
+    #[error("Records {}..{} cannot be moved before record {to}", block.start, block.end)]
+    MoveInside { block: Range<usize>, to: usize },
+}
+
+/// The records an edit replaced, from the position `at` on, and the number of records
+/// replacing them.
+#[derive(Clone, Debug)]
+struct Step {
+    at: usize,
+    replaced: Vec<Record>,
+    by: usize,
+}
+
+impl Step {
+    /// Puts the replaced records back in `records`, returning the step putting back those
+    /// replacing them.
+    fn revert(self, records: &mut Vec<Record>) -> Self {
+        let by = self.replaced.len();
+        let replaced = records
+            .splice(self.at..self.at + self.by, self.replaced)
+            .collect();
+        Self {
+            at: self.at,
+            replaced,
+            by,
+        }
+    }
+}
+
+/// The steps undoing the edits of a proof, and those redoing the undone edits. Only the
+/// records an edit changed are kept.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+impl History {
+    fn push(&mut self, step: Step) {
+        self.undo.push(step);
+        self.redo.clear();
+    }
+
+    /// Keeps what an edit changed from the `former` records to `records`. The edit cannot be
+    /// redone any more.
+    pub(crate) fn done(&mut self, mut former: Vec<Record>, records: &[Record]) {
+        let at = former
+            .iter()
+            .zip(records)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let kept = former[at..]
+            .iter()
+            .rev()
+            .zip(records[at..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        former.truncate(former.len() - kept);
+        self.push(Step {
+            at,
+            replaced: former.split_off(at),
+            by: records.len() - kept - at,
+        });
+    }
+
+    /// Keeps that a record was added at the end of the `len` former ones.
+    pub(crate) fn appended(&mut self, len: usize) {
+        self.push(Step {
+            at: len,
+            replaced: Vec::new(),
+            by: 1,
+        });
+    }
+
+    /// Gives `records` back their state before the last edit, telling whether there was one.
+    pub(crate) fn undo(&mut self, records: &mut Vec<Record>) -> bool {
+        match self.undo.pop() {
+            Some(step) => {
+                self.redo.push(step.revert(records));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gives `records` back their state before the last undo, telling whether there was one.
+    pub(crate) fn redo(&mut self, records: &mut Vec<Record>) -> bool {
+        match self.redo.pop() {
+            Some(step) => {
+                self.undo.push(step.revert(records));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Proof {
+    /// Inserts `record` at the position `at`, which may be the end of the proof. Its id is
+    /// set to `at`, its context and references being those it has once inserted. The records
+    /// after it, and the references to them, are renumbered.
+    pub fn insert_record(&mut self, at: usize, mut record: Record) -> Result<(), EditError> {
+        if at > self.records().len() {
+            return Err(EditError::NoRecord(at));
+        }
+        let map = |i: usize| if i < at { i } else { i + 1 };
+        record.id = at;
+        let mut records = self.records()[..at].to_vec();
+        records.push(record);
+        records.extend(self.records()[at..].iter().map(|rec| {
+            let mut rec = rec.clone();
+            rec.renumber(map);
+            rec
+        }));
+        self.edit(records);
+        Ok(())
+    }
+
+    /// Removes the record `id`, which no later record may cite or be in the box of. The
+    /// records after it, and the references to them, are renumbered.
+    pub fn remove_record(&mut self, id: usize) -> Result<Record, EditError> {
+        let removed = self
+            .records()
+            .get(id)
+            .cloned()
+            .ok_or(EditError::NoRecord(id))?;
+        let user = self.records()[id + 1..]
+            .iter()
+            .find(|rec| rec.ctxt.contains(&id) || rec.justif.references().contains(&id));
+        if let Some(user) = user {
+            return Err(EditError::Used {
+                record: id,
+                by: user.id,
+            });
+        }
+        let map = |i: usize| if i < id { i } else { i - 1 };
+        let mut records = self.records()[..id].to_vec();
+        records.extend(self.records()[id + 1..].iter().map(|rec| {
+            let mut rec = rec.clone();
+            rec.renumber(map);
+            rec
+        }));
+        self.edit(records);
+        Ok(removed)
+    }
+
+    /// Replaces the record `id` by `record`, whose id is set to `id`, returning the former
+    /// one. The references to it are kept.
+    pub fn replace_record(&mut self, id: usize, mut record: Record) -> Result<Record, EditError> {
+        if id >= self.records().len() {
+            return Err(EditError::NoRecord(id));
+        }
+        record.id = id;
+        let mut records = self.records().to_vec();
+        let former = std::mem::replace(&mut records[id], record);
+        self.edit(records);
+        Ok(former)
+    }
+
+    /// Moves the records of `block` before the record `to`, or at the end of the proof if `to`
+    /// is its length. Every record is renumbered, references following the records they cite.
+    pub fn move_block(&mut self, block: Range<usize>, to: usize) -> Result<(), EditError> {
+        let len = self.records().len();
+        if block.start >= block.end || block.end > len {
+            return Err(EditError::NoRecord(block.end.max(block.start)));
+        }
+        if to > len {
+            return Err(EditError::NoRecord(to));
+        }
+        if block.start < to && to < block.end {
+            return Err(EditError::MoveInside { block, to });
+        }
+        // The former ids of the records, in their new order.
+        let order: Vec<usize> = if to <= block.start {
+            (0..to)
+                .chain(block.clone())
+                .chain(to..block.start)
+                .chain(block.end..len)
+                .collect()
+        } else {
+            (0..block.start)
+                .chain(block.end..to)
+                .chain(block.clone())
+                .chain(to..len)
+                .collect()
+        };
+        let mut position = vec![0; len];
+        for (new, old) in order.iter().enumerate() {
+            position[*old] = new;
+        }
+        let map = |i: usize| position.get(i).copied().unwrap_or(i);
+        let records = order
+            .iter()
+            .map(|old| {
+                let mut rec = self.records()[*old].clone();
+                rec.renumber(map);
+                rec
+            })
+            .collect();
+        self.edit(records);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::CheckUpResult;
+
+    fn record(input: &str) -> Record {
+        Record::read_record(input).unwrap()
+    }
+
+    fn is_valid(proof: &mut Proof) -> bool {
+        proof.check();
+        matches!(proof.state(), CheckUpResult::Valid)
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let input = "0;0;Supposons a;Hyp\n\
+                     1;0;a∧a;IAnd 0 0\n\
+                     2;;Donc a⇒a∧a;IImpl\n";
+        let mut proof = Proof::read_proof(input).unwrap();
+        proof.insert_record(1, record("1;0;a;Rwrt 0")).unwrap();
+        assert_eq!(
+            proof.to_string(),
+            "0;0;Supposons a;Hyp\n1;0;a;Rwrt 0\n2;0;a∧a;IAnd 0 0\n3;;Donc a⇒a∧a;IImpl\n"
+        );
+        proof.replace_record(2, record("2;0;a∧a;IAnd 1 0")).unwrap();
+        assert!(is_valid(&mut proof));
+        assert!(matches!(
+            proof.remove_record(1),
+            Err(EditError::Used { record: 1, by: 2 })
+        ));
+        assert!(matches!(
+            proof.remove_record(0),
+            Err(EditError::Used { record: 0, by: 1 })
+        ));
+        proof.replace_record(2, record("2;0;a∧a;IAnd 0 0")).unwrap();
+        proof.remove_record(1).unwrap();
+        assert_eq!(proof.to_string(), input);
+        assert!(matches!(
+            proof.remove_record(3),
+            Err(EditError::NoRecord(3))
+        ));
+    }
+
+    #[test]
+    fn move_block() {
+        let input = "0;;Prémisse a;Hyp\n\
+                     1;;Prémisse b;Hyp\n\
+                     2;;b∧a;IAnd 1 0\n\
+                     3;;a∧b;IAnd 0 1\n";
+        let mut proof = Proof::read_proof(input).unwrap();
+        proof.move_block(1..2, 0).unwrap();
+        assert_eq!(
+            proof.to_string(),
+            "0;;Prémisse b;Hyp\n1;;Prémisse a;Hyp\n2;;b∧a;IAnd 0 1\n3;;a∧b;IAnd 1 0\n"
+        );
+        proof.move_block(2..3, 4).unwrap();
+        assert_eq!(proof.records()[3].to_string(), "3;;b∧a;IAnd 0 1");
+        assert!(is_valid(&mut proof));
+        assert!(matches!(
+            proof.move_block(0..3, 1),
+            Err(EditError::MoveInside { .. })
+        ));
+    }
+
+    #[test]
+    fn undo_redo() {
+        let input = "0;;Prémisse a;Hyp\n1;;a;Rwrt 0\n";
+        let mut proof = Proof::read_proof(input).unwrap();
+        assert!(!proof.undo());
+        proof.remove_record(1).unwrap();
+        proof.add_record(record("1;;a∧a;IAnd 0 0"));
+        assert!(proof.undo());
+        assert!(proof.undo());
+        assert_eq!(proof.to_string(), input);
+        assert!(proof.redo());
+        assert_eq!(proof.records().len(), 1);
+        proof.insert_record(0, record("0;;Prémisse b;Hyp")).unwrap();
+        assert!(!proof.redo());
+        assert!(proof.undo());
+        assert!(proof.undo());
+        assert_eq!(proof.to_string(), input);
+        assert!(is_valid(&mut proof));
+        proof.add_record(record("2;;a∧a;IAnd 0 1"));
+        proof.move_block(0..1, 3).unwrap();
+        proof.replace_record(0, record("0;;a;Rwrt 2")).unwrap();
+        let edited = proof.to_string();
+        assert!(proof.undo());
+        assert!(proof.undo());
+        assert!(proof.undo());
+        assert_eq!(proof.to_string(), input);
+        while proof.redo() {}
+        assert_eq!(proof.to_string(), edited);
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::BTreeSet;
+
 use thiserror::Error;
 
-#[derive(Clone, Debug, PartialEq)]
+use crate::term::{Chars, Term};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Formula {
     Top,
     Bottom,
@@ -11,6 +15,18 @@ pub enum Formula {
     Implies(Box<Formula>, Box<Formula>),
     RLImplies(Box<Formula>, Box<Formula>),
     Equiv(Box<Formula>, Box<Formula>),
+    /// Predicate applied to terms, such as `P(x,f(y))`
+    Predicate(char, Vec<Term>),
+    /// Equality of two terms
+    Equal(Term, Term),
+    /// Universal quantification of a term variable
+    Forall(char, Box<Formula>),
+    /// Existential quantification of a term variable
+    Exists(char, Box<Formula>),
+    /// Modal necessity, `□`
+    Necessarily(Box<Formula>),
+    /// Modal possibility, `◇`
+    Possibly(Box<Formula>),
 }
 
 #[derive(Clone, PartialEq)]
@@ -20,8 +36,18 @@ pub enum NormalizedFormula {
     Or(Box<NormalizedFormula>, Box<NormalizedFormula>),
     And(Box<NormalizedFormula>, Box<NormalizedFormula>),
     Implies(Box<NormalizedFormula>, Box<NormalizedFormula>),
+    Predicate(char, Vec<Term>),
+    Equal(Term, Term),
+    Forall(char, Box<NormalizedFormula>),
+    Exists(char, Box<NormalizedFormula>),
+    Necessarily(Box<NormalizedFormula>),
 }
 
+/// A propositional tool was given a first-order formula.
+#[derive(Error, Debug, PartialEq)]
+#[error("The formula is not propositional")]
+pub struct NotPropositional;
+
 impl Formula {
     pub fn equiv(&self, other: &Self) -> bool {
         match self {
@@ -67,6 +93,23 @@ impl Formula {
                 },
                 Formula::Bottom => *other == Formula::Bottom,
                 Formula::Variable(v) => *other == Formula::Variable(*v),
+                Formula::Predicate(_, _) | Formula::Equal(_, _) => self == other,
+                Formula::Forall(x, f) => match other {
+                    Formula::Forall(y, g) => x == y && f.equiv(g),
+                    _ => false,
+                },
+                Formula::Exists(x, f) => match other {
+                    Formula::Exists(y, g) => x == y && f.equiv(g),
+                    _ => false,
+                },
+                Formula::Necessarily(f) => match other {
+                    Formula::Necessarily(g) => f.equiv(g),
+                    _ => false,
+                },
+                Formula::Possibly(f) => match other {
+                    Formula::Possibly(g) => f.equiv(g),
+                    _ => false,
+                },
             }
     }
     pub fn normalize(self) -> NormalizedFormula {
@@ -101,6 +144,218 @@ impl Formula {
             }
             Formula::Bottom => NormalizedFormula::Bottom,
             Formula::Variable(v) => NormalizedFormula::Variable(v),
+            Formula::Predicate(p, args) => NormalizedFormula::Predicate(p, args),
+            Formula::Equal(l, r) => NormalizedFormula::Equal(l, r),
+            Formula::Forall(x, f) => NormalizedFormula::Forall(x, Box::new(f.normalize())),
+            Formula::Exists(x, f) => NormalizedFormula::Exists(x, Box::new(f.normalize())),
+            Formula::Necessarily(f) => NormalizedFormula::Necessarily(Box::new(f.normalize())),
+            // ◇A is ¬□¬A
+            Formula::Possibly(f) => NormalizedFormula::Implies(
+                Box::new(NormalizedFormula::Necessarily(Box::new(NormalizedFormula::Implies(
+                    Box::new(f.normalize()),
+                    Box::new(NormalizedFormula::Bottom),
+                )))),
+                Box::new(NormalizedFormula::Bottom),
+            ),
+        }
+    }
+
+    /// Tells whether the formula has neither quantifiers, predicates, equalities nor modalities.
+    pub fn is_propositional(&self) -> bool {
+        match self {
+            Formula::Top | Formula::Bottom | Formula::Variable(_) => true,
+            Formula::Not(f) => f.is_propositional(),
+            Formula::Or(a, b)
+            | Formula::And(a, b)
+            | Formula::Implies(a, b)
+            | Formula::RLImplies(a, b)
+            | Formula::Equiv(a, b) => a.is_propositional() && b.is_propositional(),
+            Formula::Predicate(_, _)
+            | Formula::Equal(_, _)
+            | Formula::Forall(_, _)
+            | Formula::Exists(_, _)
+            | Formula::Necessarily(_)
+            | Formula::Possibly(_) => false,
+        }
+    }
+
+    /// Returns the set of the propositional variables appearing in the formula.
+    pub fn variables(&self) -> BTreeSet<char> {
+        let mut vars = BTreeSet::new();
+        self.collect_variables(&mut vars);
+        vars
+    }
+
+    /// Returns the number of symbols of the formula, counting each atom, connective, modality
+    /// and quantifier once.
+    pub fn size(&self) -> usize {
+        match self {
+            Formula::Top
+            | Formula::Bottom
+            | Formula::Variable(_)
+            | Formula::Predicate(_, _)
+            | Formula::Equal(_, _) => 1,
+            Formula::Not(f)
+            | Formula::Forall(_, f)
+            | Formula::Exists(_, f)
+            | Formula::Necessarily(f)
+            | Formula::Possibly(f) => 1 + f.size(),
+            Formula::Or(a, b)
+            | Formula::And(a, b)
+            | Formula::Implies(a, b)
+            | Formula::RLImplies(a, b)
+            | Formula::Equiv(a, b) => 1 + a.size() + b.size(),
+        }
+    }
+
+    fn collect_variables(&self, vars: &mut BTreeSet<char>) {
+        match self {
+            Formula::Top | Formula::Bottom => (),
+            Formula::Variable(v) => {
+                vars.insert(*v);
+            }
+            Formula::Not(f)
+            | Formula::Forall(_, f)
+            | Formula::Exists(_, f)
+            | Formula::Necessarily(f)
+            | Formula::Possibly(f) => f.collect_variables(vars),
+            Formula::Predicate(_, _) | Formula::Equal(_, _) => (),
+            Formula::Or(a, b)
+            | Formula::And(a, b)
+            | Formula::Implies(a, b)
+            | Formula::RLImplies(a, b)
+            | Formula::Equiv(a, b) => {
+                a.collect_variables(vars);
+                b.collect_variables(vars);
+            }
+        }
+    }
+
+    /// Evaluates the formula under the classical valuation `valuation`, if it is
+    /// propositional, see [`Formula::is_propositional`].
+    pub fn eval<F: Fn(char) -> bool>(&self, valuation: &F) -> Result<bool, NotPropositional> {
+        if !self.is_propositional() {
+            return Err(NotPropositional);
+        }
+        Ok(self.value(valuation))
+    }
+
+    /// Evaluates the propositional formula under `valuation`.
+    fn value<F: Fn(char) -> bool>(&self, valuation: &F) -> bool {
+        match self {
+            Formula::Top => true,
+            Formula::Bottom => false,
+            Formula::Variable(v) => valuation(*v),
+            Formula::Not(f) => !f.value(valuation),
+            Formula::Or(a, b) => a.value(valuation) || b.value(valuation),
+            Formula::And(a, b) => a.value(valuation) && b.value(valuation),
+            Formula::Implies(a, b) => !a.value(valuation) || b.value(valuation),
+            Formula::RLImplies(a, b) => a.value(valuation) || !b.value(valuation),
+            Formula::Equiv(a, b) => a.value(valuation) == b.value(valuation),
+            Formula::Predicate(_, _)
+            | Formula::Equal(_, _)
+            | Formula::Forall(_, _)
+            | Formula::Exists(_, _)
+            | Formula::Necessarily(_)
+            | Formula::Possibly(_) => unreachable!("{}", NotPropositional),
+        }
+    }
+
+    /// Tells whether the propositional variable `v` appears in the formula.
+    pub fn occurs(&self, v: char) -> bool {
+        match self {
+            Formula::Top | Formula::Bottom => false,
+            Formula::Variable(w) => *w == v,
+            Formula::Not(f)
+            | Formula::Forall(_, f)
+            | Formula::Exists(_, f)
+            | Formula::Necessarily(f)
+            | Formula::Possibly(f) => f.occurs(v),
+            Formula::Predicate(_, _) | Formula::Equal(_, _) => false,
+            Formula::Or(a, b)
+            | Formula::And(a, b)
+            | Formula::Implies(a, b)
+            | Formula::RLImplies(a, b)
+            | Formula::Equiv(a, b) => a.occurs(v) || b.occurs(v),
+        }
+    }
+}
+
+/// Binding strength of the main connective of a formula, used for printing.
+fn precedence(f: &Formula) -> u8 {
+    match f {
+        Formula::Top
+        | Formula::Bottom
+        | Formula::Variable(_)
+        | Formula::Not(_)
+        | Formula::Predicate(_, _)
+        | Formula::Equal(_, _)
+        | Formula::Forall(_, _)
+        | Formula::Exists(_, _)
+        | Formula::Necessarily(_)
+        | Formula::Possibly(_) => 5,
+        Formula::And(_, _) => 4,
+        Formula::Or(_, _) => 3,
+        Formula::Implies(_, _) | Formula::RLImplies(_, _) => 2,
+        Formula::Equiv(_, _) => 1,
+    }
+}
+
+impl std::fmt::Display for Formula {
+    /// Prints the formula in the syntax accepted by [`Formula::read`], with as few
+    /// parentheses as possible.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (symbol, left, right) = match self {
+            Formula::Top => return write!(f, "⊤"),
+            Formula::Bottom => return write!(f, "⊥"),
+            Formula::Variable(v) => return write!(f, "{}", v),
+            Formula::Predicate(p, args) => {
+                let args: Vec<String> = args.iter().map(|t| t.to_string()).collect();
+                return write!(f, "{}({})", p, args.join(","));
+            }
+            Formula::Equal(l, r) => return write!(f, "{}={}", l, r),
+            Formula::Not(g)
+            | Formula::Forall(_, g)
+            | Formula::Exists(_, g)
+            | Formula::Necessarily(g)
+            | Formula::Possibly(g) => {
+                match self {
+                    Formula::Forall(x, _) => write!(f, "∀{}", x)?,
+                    Formula::Exists(x, _) => write!(f, "∃{}", x)?,
+                    Formula::Necessarily(_) => write!(f, "□")?,
+                    Formula::Possibly(_) => write!(f, "◇")?,
+                    _ => write!(f, "¬")?,
+                }
+                return if precedence(g) < 5 {
+                    write!(f, "({})", g)
+                } else {
+                    write!(f, "{}", g)
+                };
+            }
+            Formula::And(a, b) => ('∧', a, b),
+            Formula::Or(a, b) => ('∨', a, b),
+            Formula::Implies(a, b) => ('⇒', a, b),
+            Formula::RLImplies(a, b) => ('⇐', a, b),
+            Formula::Equiv(a, b) => ('⇔', a, b),
+        };
+        let prec = precedence(self);
+        // ∧, ∨, ⇐ and ⇔ are left-associative, ⇒ is right-associative. Mixing ⇒ and ⇐ is
+        // always parenthesized.
+        let same_op = |g: &Formula| std::mem::discriminant(g) == std::mem::discriminant(self);
+        let left_bare = precedence(left) > prec
+            || (same_op(left) && !matches!(self, Formula::Implies(_, _)));
+        let right_bare = precedence(right) > prec
+            || (same_op(right) && matches!(self, Formula::Implies(_, _)));
+        if left_bare {
+            write!(f, "{}", left)?;
+        } else {
+            write!(f, "({})", left)?;
+        }
+        write!(f, "{}", symbol)?;
+        if right_bare {
+            write!(f, "{}", right)
+        } else {
+            write!(f, "({})", right)
         }
     }
 }
@@ -109,7 +364,13 @@ enum Lexemes {
     Top,
     Bottom,
     Variable(char),
+    /// Predicate application or equality
+    Atom(Formula),
     Not,
+    Forall(char),
+    Exists(char),
+    Necessarily,
+    Possibly,
     Or,
     And,
     Implies,
@@ -122,6 +383,10 @@ enum Lexemes {
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum Operators {
     Not,
+    Forall(char),
+    Exists(char),
+    Necessarily,
+    Possibly,
     Or,
     And,
     Implies,
@@ -149,7 +414,7 @@ enum Priority {
 
 impl Operators {
     /// # Priorities
-    /// ¬,∧,∨,⇒,⇐,⇔
+    /// ¬∀∃□◇,∧,∨,⇒,⇐,⇔
     ///
     /// # Contraintes
     ///
@@ -158,22 +423,28 @@ impl Operators {
     /// - cmp(z,u)=Less
     /// - cmp(u,a)=More
     fn cmp(left: &Self, right: &Self) -> Priority {
+        if right.is_unary() {
+            return Priority::Less;
+        }
         match (left, right) {
-            (Operators::Not, Operators::Not) => Priority::Less,
-            (Operators::Not, _) => Priority::More,
-            (Operators::And, Operators::Not) => Priority::Less,
+            (
+                Operators::Not
+                | Operators::Forall(_)
+                | Operators::Exists(_)
+                | Operators::Necessarily
+                | Operators::Possibly,
+                _,
+            ) => Priority::More,
             (Operators::And, _) => Priority::More, // And is left-associative
-            (Operators::Or, Operators::Not | Operators::And) => Priority::Less,
+            (Operators::Or, Operators::And) => Priority::Less,
             (Operators::Or, _) => Priority::More, // Or is left-associative
-            (
-                Operators::Implies,
-                Operators::Not | Operators::And | Operators::Or | Operators::Implies,
-            ) => Priority::Less, // Implies is right-associative
+            (Operators::Implies, Operators::And | Operators::Or | Operators::Implies) => {
+                Priority::Less // Implies is right-associative
+            }
             (Operators::Implies, _) => Priority::More,
-            (
-                Operators::RLImplies,
-                Operators::Not | Operators::And | Operators::Or | Operators::Implies,
-            ) => Priority::Less,
+            (Operators::RLImplies, Operators::And | Operators::Or | Operators::Implies) => {
+                Priority::Less
+            }
             (Operators::RLImplies, _) => Priority::More, // RLImplies is left-associative
             (Operators::Equiv, Operators::Equiv) => Priority::More, // Equiv is left-associative
             (Operators::Equiv, _) => Priority::Less,
@@ -181,10 +452,10 @@ impl Operators {
     }
 
     fn is_unary(&self) -> bool {
-        match self {
-            Self::Not => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            Self::Not | Self::Forall(_) | Self::Exists(_) | Self::Necessarily | Self::Possibly
+        )
     }
 }
 
@@ -212,6 +483,12 @@ pub enum TokenizationError {
     OperatorWithoutRightHandside,
     #[error("A binary operator was not provided a right operand.")]
     BinaryOperatorWithoutRightHandside,
+    #[error("Invalid term at character {0}")]
+    InvalidTerm(usize),
+    #[error("Quantifier at character {0} is not followed by a variable")]
+    MissingQuantifiedVariable(usize),
+    #[error("A term is missing")]
+    MissingTerm,
 }
 
 #[derive(Debug, PartialEq)]
@@ -222,6 +499,7 @@ enum Regime {
     EndEat,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 enum ZOT {
     Zero,
     One(Operators),
@@ -230,14 +508,26 @@ enum ZOT {
 
 impl Formula {
     fn tokenize(input: &str) -> Result<Vec<Lexemes>, TokenizationError> {
-        let mut iter = input.chars().enumerate();
+        let mut chars = input.chars().enumerate().peekable();
         let mut flow = Vec::new();
-        while let Some((pos, c)) = iter.next() {
+        while let Some((pos, c)) = chars.next() {
             flow.push(match c {
                 '⊤' => Lexemes::Top,
                 '⊥' => Lexemes::Bottom,
-                'a'..='z' | 'A'..='Z' => Lexemes::Variable(c),
+                'a'..='z' | 'A'..='Z' => Self::read_atom(c, &mut chars)?,
                 '¬' => Lexemes::Not,
+                '□' => Lexemes::Necessarily,
+                '◇' => Lexemes::Possibly,
+                '∀' | '∃' => match chars.next() {
+                    Some((_, v)) if v.is_ascii_alphabetic() => {
+                        if c == '∀' {
+                            Lexemes::Forall(v)
+                        } else {
+                            Lexemes::Exists(v)
+                        }
+                    }
+                    _ => return Err(TokenizationError::MissingQuantifiedVariable(pos)),
+                },
                 '∨' => Lexemes::Or,
                 '∧' => Lexemes::And,
                 '⇒' => Lexemes::Implies,
@@ -250,6 +540,31 @@ impl Formula {
         }
         Ok(flow)
     }
+
+    /// Reads what starts with the letter `c`: a propositional variable, a predicate applied to
+    /// terms, or an equality between terms.
+    fn read_atom(c: char, chars: &mut Chars) -> Result<Lexemes, TokenizationError> {
+        let args = match chars.peek() {
+            Some((_, '(')) => {
+                chars.next();
+                Some(Term::read_arguments(chars)?)
+            }
+            _ => None,
+        };
+        if let Some((_, '=')) = chars.peek() {
+            chars.next();
+            let left = match args {
+                Some(args) => Term::Function(c, args),
+                None => Term::Variable(c),
+            };
+            let right = Term::read_from(chars)?;
+            return Ok(Lexemes::Atom(Formula::Equal(left, right)));
+        }
+        Ok(match args {
+            Some(args) => Lexemes::Atom(Formula::Predicate(c, args)),
+            None => Lexemes::Variable(c),
+        })
+    }
     /// Reads a formula from a string
     pub fn read(input: &str) -> Result<Self, TokenizationError> {
         let lexemes: Vec<Lexemes> = Self::tokenize(input)?;
@@ -265,7 +580,7 @@ impl Formula {
         }
         regime = Regime::EndEat;
         Self::eat(&mut stack, &mut state, &mut regime, &mut formulas)?;
-        return Ok(formulas.pop().unwrap());
+        Ok(formulas.pop().unwrap())
     }
 
     fn read_lexeme(
@@ -285,6 +600,10 @@ impl Formula {
                 formulas.push(Formula::Variable(v));
                 *state = ParseState::Constant
             }
+            Lexemes::Atom(f) => {
+                formulas.push(f);
+                *state = ParseState::Constant
+            }
             Lexemes::Top => {
                 formulas.push(Formula::Top);
                 *regime = Regime::Eat;
@@ -310,6 +629,22 @@ impl Formula {
                 stack.push(ParseStackItem::Operator(Operators::Not));
                 *state = ParseState::Operator
             }
+            Lexemes::Forall(v) => {
+                stack.push(ParseStackItem::Operator(Operators::Forall(v)));
+                *state = ParseState::Operator
+            }
+            Lexemes::Exists(v) => {
+                stack.push(ParseStackItem::Operator(Operators::Exists(v)));
+                *state = ParseState::Operator
+            }
+            Lexemes::Necessarily => {
+                stack.push(ParseStackItem::Operator(Operators::Necessarily));
+                *state = ParseState::Operator
+            }
+            Lexemes::Possibly => {
+                stack.push(ParseStackItem::Operator(Operators::Possibly));
+                *state = ParseState::Operator
+            }
             Lexemes::Implies => {
                 stack.push(ParseStackItem::Operator(Operators::Implies));
                 *state = ParseState::Operator
@@ -326,7 +661,7 @@ impl Formula {
         Ok(())
     }
 
-    fn get_last_two_ops_from_stack(stack: &mut Vec<ParseStackItem>) -> ZOT {
+    fn get_last_two_ops_from_stack(stack: &mut [ParseStackItem]) -> ZOT {
         match stack.split_last() {
             None => ZOT::Zero,
             Some((right, tail)) => match tail.split_last() {
@@ -404,7 +739,7 @@ impl Formula {
                             return Err(TokenizationError::InternalError(1));
                         }
                         ParseState::Constant => match Operators::cmp(&left, &right) {
-                            Priority::Less => {
+                            Priority::Less if right.is_unary() => {
                                 // On est dans la situation ?L?Ra. On mange ?Ra.
                                 debug_assert_eq!(
                                     Some(ParseStackItem::Operator(right)),
@@ -412,6 +747,11 @@ impl Formula {
                                 );
                                 Self::build_from_operator(right, formulas)?;
                             }
+                            Priority::Less => {
+                                // On est dans la situation ?L?Ra avec R binaire : l'opérateur
+                                // suivant peut encore s'appliquer à a, on attend de le lire.
+                                *regime = Regime::Stop;
+                            }
                             Priority::More => {
                                 debug_assert!(!right.is_unary());
                                 // Comme right n'est pas unaire, on est dans la situation:
@@ -510,7 +850,7 @@ impl Formula {
                                     // On est dans la situation ?(?L?Ra). On mange ?Ra.
                                     assert_eq!(Some(ParseStackItem::Operator(right)), stack.pop());
                                     Self::build_from_operator(right, formulas)?;
-                                    *regime = Regime::Eat;
+                                    *regime = Regime::ForceEat; // On doit tout manger
                                 }
                                 Priority::More => {
                                     // right n'est pas unaire
@@ -615,52 +955,68 @@ impl Formula {
             Operators::Not => {
                 let f = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 formulas.push(Formula::Not(Box::new(f)));
             }
+            Operators::Forall(v) => {
+                let f = formulas.pop().ok_or(TokenizationError::AFormulaIsMissing)?;
+                formulas.push(Formula::Forall(v, Box::new(f)));
+            }
+            Operators::Exists(v) => {
+                let f = formulas.pop().ok_or(TokenizationError::AFormulaIsMissing)?;
+                formulas.push(Formula::Exists(v, Box::new(f)));
+            }
+            Operators::Necessarily => {
+                let f = formulas.pop().ok_or(TokenizationError::AFormulaIsMissing)?;
+                formulas.push(Formula::Necessarily(Box::new(f)));
+            }
+            Operators::Possibly => {
+                let f = formulas.pop().ok_or(TokenizationError::AFormulaIsMissing)?;
+                formulas.push(Formula::Possibly(Box::new(f)));
+            }
             Operators::And => {
                 let right = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 let left = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 formulas.push(Formula::And(Box::new(left), Box::new(right)));
             }
             Operators::Or => {
                 let right = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 let left = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 formulas.push(Formula::Or(Box::new(left), Box::new(right)));
             }
             Operators::Implies => {
                 let right = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 let left = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 formulas.push(Formula::Implies(Box::new(left), Box::new(right)));
             }
             Operators::RLImplies => {
                 let right = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 let left = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 formulas.push(Formula::RLImplies(Box::new(left), Box::new(right)));
             }
             Operators::Equiv => {
                 let right = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 let left = formulas
                     .pop()
-                    .ok_or_else(|| TokenizationError::AFormulaIsMissing)?;
+                    .ok_or(TokenizationError::AFormulaIsMissing)?;
                 formulas.push(Formula::Equiv(Box::new(left), Box::new(right)));
             }
         };
@@ -189,3 +189,33 @@ fn parsing_priorities() {
         f
     );
 }
+
+#[test]
+fn display_roundtrip() {
+    for input in [
+        "c∧(a∨b)",
+        "¬a⇒a∨b⇒(¬q∨¬r∧s⇔t⇔d)",
+        "(a⇒b)⇒c",
+        "a⇐b⇐c",
+        "a⇐(b⇐c)",
+        "(a⇒b)⇐c",
+        "¬¬(a∧⊤)∨⊥",
+        "a⇔(b⇔c)",
+    ] {
+        let f = Formula::read(input).unwrap();
+        let printed = f.to_string();
+        assert_eq!(printed, input);
+        assert_eq!(Formula::read(&printed).unwrap(), f);
+    }
+}
+
+#[test]
+fn evaluation() {
+    let valuation = |v: char| v == 'a';
+    let eval = |input: &str| Formula::read(input).unwrap().eval(&valuation);
+    assert_eq!(eval("a∧¬b⇒a⇔⊤"), Ok(true));
+    assert_eq!(eval("a⇐b∨⊥"), Ok(true));
+    assert_eq!(eval("b∨¬a"), Ok(false));
+    assert_eq!(eval("⊤∨∀xP(x)"), Err(NotPropositional));
+    assert_eq!(eval("□a"), Err(NotPropositional));
+}
@@ -0,0 +1,531 @@
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::SplitWhitespace;
+
+use thiserror::Error;
+
+use crate::formula::{Formula, TokenizationError};
+use crate::subst::Substitution;
+
+#[derive(Error, Debug)]
+pub enum RecordError {
+    /// A field is missing
+    #[error("A field is missing")]
+    MissingField,
+    /// The id field is invalid
+    #[error("The id field is invalid")]
+    InvalidId,
+    /// There are too many fields
+    #[error("There are too many fields")]
+    TooMuch,
+    /// Error parsing the formula
+    #[error("Error parsing the formula: {0}")]
+    InvalidFormula(TokenizationError),
+    /// The justification is missing
+    #[error("The justification is missing")]
+    MissingJustification,
+    /// The justification is neither Hyp, Ax nor MP
+    #[error("The justification is neither Hyp, Ax nor MP")]
+    UnknownJustification,
+    /// Ax is missing the name of the schema
+    #[error("Ax is missing the name of the schema")]
+    MissingSchema,
+    /// The axiom schema is unknown
+    #[error("The axiom schema is unknown")]
+    UnknownSchema,
+    /// A binding should be written v:=formula
+    #[error("A binding should be written v:=formula")]
+    InvalidBinding,
+    /// The formula of a binding is invalid
+    #[error("The formula of a binding is invalid: {0}")]
+    InvalidBindingFormula(TokenizationError),
+    /// MP is missing the position of a premise
+    #[error("MP is missing the position of a premise")]
+    MissingReference,
+    /// The position of a premise is not a valid number
+    #[error("The position of a premise is not a valid number: {0}")]
+    InvalidReference(ParseIntError),
+    /// The justification is given too many arguments
+    #[error("The justification is given too many arguments")]
+    TooManyArguments,
+}
+
+#[derive(Error, Debug)]
+#[error("At {line}: {content}")]
+pub struct ReadError {
+    pub line: usize,
+    #[source]
+    pub content: RecordError,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum HilbertError {
+    /// The id of the record is not its position
+    #[error("The id of the record is not its position")]
+    IncorrectId,
+    /// A premise has to be proved before the record
+    #[error("Premise {0} has to be proved before the record")]
+    PremiseNotBefore(usize),
+    /// The formula is not an instance of the schema
+    #[error("The formula is not an instance of schema {0}")]
+    NotAnInstance(Schema),
+    /// The substitution does not turn the schema into the formula
+    #[error("The given substitution does not turn schema {0} into the formula")]
+    WrongSubstitution(Schema),
+    /// The second premise of MP should be an implication
+    #[error("The second premise of MP should be an implication")]
+    MPNotAnImplication,
+    /// The first premise of MP should be the hypothesis of the implication
+    #[error("The first premise of MP should be the hypothesis of the implication")]
+    MPHypNotMatching,
+    /// The formula should be the consequence of the implication
+    #[error("The formula should be the consequence of the implication")]
+    MPConsNotMatching,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DeductionError {
+    /// The proof has errors
+    #[error("The proof has errors")]
+    InvalidProof,
+    /// The proof is empty
+    #[error("The proof is empty")]
+    EmptyProof,
+}
+
+/// An axiom schema. Its variables `A`, `B` and `C` stand for any formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Schema {
+    /// A⇒(B⇒A)
+    K,
+    /// (A⇒(B⇒C))⇒((A⇒B)⇒(A⇒C))
+    S,
+    /// A∧B⇒A
+    AndE1,
+    /// A∧B⇒B
+    AndE2,
+    /// A⇒(B⇒A∧B)
+    AndI,
+    /// A⇒A∨B
+    OrI1,
+    /// B⇒A∨B
+    OrI2,
+    /// (A⇒C)⇒((B⇒C)⇒(A∨B⇒C))
+    OrE,
+    /// (A⇒B)⇒((A⇒¬B)⇒¬A)
+    NotI,
+    /// ¬¬A⇒A
+    NotE,
+}
+
+impl Schema {
+    pub const ALL: [Schema; 10] = [
+        Schema::K,
+        Schema::S,
+        Schema::AndE1,
+        Schema::AndE2,
+        Schema::AndI,
+        Schema::OrI1,
+        Schema::OrI2,
+        Schema::OrE,
+        Schema::NotI,
+        Schema::NotE,
+    ];
+
+    /// Returns the name of the schema, as written in a proof.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Schema::K => "K",
+            Schema::S => "S",
+            Schema::AndE1 => "AndE1",
+            Schema::AndE2 => "AndE2",
+            Schema::AndI => "AndI",
+            Schema::OrI1 => "OrI1",
+            Schema::OrI2 => "OrI2",
+            Schema::OrE => "OrE",
+            Schema::NotI => "NotI",
+            Schema::NotE => "NotE",
+        }
+    }
+
+    /// Returns the schema as a formula.
+    pub fn formula(&self) -> Formula {
+        let schema = match self {
+            Schema::K => "A⇒(B⇒A)",
+            Schema::S => "(A⇒(B⇒C))⇒((A⇒B)⇒(A⇒C))",
+            Schema::AndE1 => "A∧B⇒A",
+            Schema::AndE2 => "A∧B⇒B",
+            Schema::AndI => "A⇒(B⇒A∧B)",
+            Schema::OrI1 => "A⇒A∨B",
+            Schema::OrI2 => "B⇒A∨B",
+            Schema::OrE => "(A⇒C)⇒((B⇒C)⇒(A∨B⇒C))",
+            Schema::NotI => "(A⇒B)⇒((A⇒¬B)⇒¬A)",
+            Schema::NotE => "¬¬A⇒A",
+        };
+        Formula::read(schema).expect("axiom schemas are valid formulas")
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HilbertJustification {
+    /// The formula is a hypothesis
+    Hyp,
+    /// The formula is an instance of the schema. Bindings that are not given are inferred.
+    Axiom(Schema, Substitution),
+    /// Modus ponens, from A and A⇒B
+    MP { hyp: usize, implication: usize },
+}
+
+fn read_reference(s: &mut SplitWhitespace) -> Result<usize, RecordError> {
+    s.next()
+        .ok_or(RecordError::MissingReference)?
+        .parse::<usize>()
+        .map_err(RecordError::InvalidReference)
+}
+
+impl HilbertJustification {
+    /// Reads a justification: `Hyp`, `Ax K A:=p B:=q∧r` (bindings are optional) or `MP 1 3`.
+    /// Formulas of bindings are written without spaces.
+    pub fn read(input: &str) -> Result<Self, RecordError> {
+        let s = &mut input.split_whitespace();
+        let justif = match s.next().ok_or(RecordError::MissingJustification)? {
+            "Hyp" => Self::Hyp,
+            "Ax" => {
+                let name = s.next().ok_or(RecordError::MissingSchema)?;
+                let schema = Schema::ALL
+                    .into_iter()
+                    .find(|schema| schema.name() == name)
+                    .ok_or(RecordError::UnknownSchema)?;
+                let mut subst = Substitution::new();
+                for binding in s.by_ref() {
+                    let (v, f) = binding
+                        .split_once(":=")
+                        .ok_or(RecordError::InvalidBinding)?;
+                    let mut v = v.chars();
+                    let v = match (v.next(), v.next()) {
+                        (Some(v), None) => v,
+                        _ => return Err(RecordError::InvalidBinding),
+                    };
+                    let f = Formula::read(f).map_err(RecordError::InvalidBindingFormula)?;
+                    subst.insert(v, f);
+                }
+                Self::Axiom(schema, subst)
+            }
+            "MP" => Self::MP {
+                hyp: read_reference(s)?,
+                implication: read_reference(s)?,
+            },
+            _ => return Err(RecordError::UnknownJustification),
+        };
+        match s.next() {
+            Some(_) => Err(RecordError::TooManyArguments),
+            None => Ok(justif),
+        }
+    }
+}
+
+impl fmt::Display for HilbertJustification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hyp => write!(f, "Hyp"),
+            Self::Axiom(schema, subst) => {
+                write!(f, "Ax {}", schema)?;
+                for (v, formula) in subst.iter() {
+                    write!(f, " {}:={}", v, formula)?;
+                }
+                Ok(())
+            }
+            Self::MP { hyp, implication } => write!(f, "MP {} {}", hyp, implication),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HilbertRecord {
+    pub id: usize,
+    pub formula: Formula,
+    pub justif: HilbertJustification,
+}
+
+impl HilbertRecord {
+    /// Reads a record `id;formula;justification`.
+    pub fn read_record(input: &str) -> Result<Self, RecordError> {
+        let mut input = input.split(';');
+        let id = input
+            .next()
+            .ok_or(RecordError::MissingField)?
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| RecordError::InvalidId)?;
+        let formula = Formula::read(input.next().ok_or(RecordError::MissingField)?.trim())
+            .map_err(RecordError::InvalidFormula)?;
+        let justif = HilbertJustification::read(input.next().ok_or(RecordError::MissingField)?)?;
+        match input.next() {
+            Some(_) => Err(RecordError::TooMuch),
+            None => Ok(Self {
+                id,
+                formula,
+                justif,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for HilbertRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};{};{}", self.id, self.formula, self.justif)
+    }
+}
+
+/// A proof in the Hilbert system: axiom instances, hypotheses and modus ponens.
+#[derive(Debug, Default)]
+pub struct HilbertProof {
+    records: Vec<HilbertRecord>,
+}
+
+impl HilbertProof {
+    /// Reads a proof, one record per line. Blank lines are ignored.
+    pub fn read_proof(input: &str) -> Result<Self, ReadError> {
+        let mut records = Vec::new();
+        for (line, record) in input.split('\n').enumerate() {
+            if record.trim().is_empty() {
+                continue;
+            }
+            match HilbertRecord::read_record(record) {
+                Ok(r) => records.push(r),
+                Err(content) => return Err(ReadError { line, content }),
+            }
+        }
+        Ok(Self { records })
+    }
+
+    pub fn records(&self) -> &[HilbertRecord] {
+        &self.records
+    }
+
+    /// Returns the formulas the proof assumes, in order of first use.
+    pub fn hypotheses(&self) -> Vec<Formula> {
+        let mut hypotheses: Vec<Formula> = Vec::new();
+        for rec in &self.records {
+            if rec.justif == HilbertJustification::Hyp && !hypotheses.contains(&rec.formula) {
+                hypotheses.push(rec.formula.clone());
+            }
+        }
+        hypotheses
+    }
+
+    /// Checks every record, returning the errors found.
+    pub fn check(&self) -> Result<(), Vec<(usize, HilbertError)>> {
+        let errors: Vec<(usize, HilbertError)> = (0..self.records.len())
+            .filter_map(|id| self.check_record(id).err().map(|e| (id, e)))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_record(&self, id: usize) -> Result<(), HilbertError> {
+        let rec = &self.records[id];
+        if rec.id != id {
+            return Err(HilbertError::IncorrectId);
+        }
+        match &rec.justif {
+            HilbertJustification::Hyp => Ok(()),
+            HilbertJustification::Axiom(schema, given) => {
+                let mut subst = given.clone();
+                if schema
+                    .formula()
+                    .match_pattern_into(&rec.formula, &mut subst)
+                {
+                    Ok(())
+                } else if given.is_empty() {
+                    Err(HilbertError::NotAnInstance(*schema))
+                } else {
+                    Err(HilbertError::WrongSubstitution(*schema))
+                }
+            }
+            HilbertJustification::MP { hyp, implication } => {
+                for premise in [*hyp, *implication] {
+                    if premise >= id {
+                        return Err(HilbertError::PremiseNotBefore(premise));
+                    }
+                }
+                match &self.records[*implication].formula {
+                    Formula::Implies(a, b) => {
+                        if **a != self.records[*hyp].formula {
+                            Err(HilbertError::MPHypNotMatching)
+                        } else if **b != rec.formula {
+                            Err(HilbertError::MPConsNotMatching)
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    _ => Err(HilbertError::MPNotAnImplication),
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, formula: Formula, justif: HilbertJustification) -> usize {
+        let id = self.records.len();
+        self.records.push(HilbertRecord {
+            id,
+            formula,
+            justif,
+        });
+        id
+    }
+
+    /// Pushes the instance of `schema` by the bindings of `A`, `B` and `C`, in that order.
+    fn push_axiom(&mut self, schema: Schema, bindings: &[&Formula]) -> usize {
+        let subst: Substitution = ['A', 'B', 'C']
+            .into_iter()
+            .zip(bindings.iter().map(|f| (*f).clone()))
+            .collect();
+        let formula = subst.apply(&schema.formula());
+        self.push(formula, HilbertJustification::Axiom(schema, subst))
+    }
+
+    fn push_mp(&mut self, hyp: usize, implication: usize) -> usize {
+        let formula = match &self.records[implication].formula {
+            Formula::Implies(_, b) => (**b).clone(),
+            _ => unreachable!("the implication of a generated MP is built by the deduction"),
+        };
+        self.push(formula, HilbertJustification::MP { hyp, implication })
+    }
+
+    /// The deduction theorem: turns this proof of `φ` under the hypothesis `h` into a proof of
+    /// `h⇒φ` that does not assume `h`, `φ` being the last formula.
+    ///
+    /// Every record `ψ` becomes a few records ending with `h⇒ψ`: `h⇒h` is proved from `S` and
+    /// `K`, other hypotheses and axioms are weakened by `K`, and modus ponens is distributed
+    /// over `h` by `S`. Axiom instances are given with their full substitution.
+    pub fn deduction(&self, h: &Formula) -> Result<HilbertProof, DeductionError> {
+        if self.records.is_empty() {
+            return Err(DeductionError::EmptyProof);
+        }
+        if self.check().is_err() {
+            return Err(DeductionError::InvalidProof);
+        }
+        let implies =
+            |a: &Formula, b: &Formula| Formula::Implies(Box::new(a.clone()), Box::new(b.clone()));
+        let mut proof = HilbertProof::default();
+        let mut discharged: Vec<usize> = Vec::with_capacity(self.records.len());
+        for rec in &self.records {
+            let phi = &rec.formula;
+            let id = match &rec.justif {
+                _ if phi == h => {
+                    let h_h = implies(h, h);
+                    let s = proof.push_axiom(Schema::S, &[h, &h_h, h]);
+                    let k = proof.push_axiom(Schema::K, &[h, &h_h]);
+                    let mp = proof.push_mp(k, s);
+                    let k = proof.push_axiom(Schema::K, &[h, h]);
+                    proof.push_mp(k, mp)
+                }
+                HilbertJustification::Hyp | HilbertJustification::Axiom(_, _) => {
+                    let justif = match &rec.justif {
+                        HilbertJustification::Axiom(schema, given) => {
+                            let mut subst = given.clone();
+                            schema.formula().match_pattern_into(phi, &mut subst);
+                            HilbertJustification::Axiom(*schema, subst)
+                        }
+                        justif => justif.clone(),
+                    };
+                    let copy = proof.push(phi.clone(), justif);
+                    let k = proof.push_axiom(Schema::K, &[phi, h]);
+                    proof.push_mp(copy, k)
+                }
+                HilbertJustification::MP { hyp, implication } => {
+                    let a = &self.records[*hyp].formula;
+                    let s = proof.push_axiom(Schema::S, &[h, a, phi]);
+                    let mp = proof.push_mp(discharged[*implication], s);
+                    proof.push_mp(discharged[*hyp], mp)
+                }
+            };
+            discharged.push(id);
+        }
+        Ok(proof)
+    }
+}
+
+impl fmt::Display for HilbertProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    #[test]
+    fn schemas() {
+        // Implication is right-associative, even after a parenthesized formula.
+        assert_eq!(Schema::S.formula(), f("(A⇒B⇒C)⇒(A⇒B)⇒A⇒C"));
+        for schema in Schema::ALL {
+            assert_eq!(
+                Formula::read(&schema.formula().to_string()),
+                Ok(schema.formula())
+            );
+        }
+    }
+
+    #[test]
+    fn check() {
+        let input =
+            "0;A;Hyp\n1;A⇒B;Hyp\n2;B;MP 0 1\n3;B⇒A⇒B∧A;Ax AndI\n4;A⇒B∧A;MP 2 3\n5;B∧A;MP 0 4";
+        let proof = HilbertProof::read_proof(input).unwrap();
+        assert_eq!(proof.check(), Ok(()));
+        assert_eq!(proof.hypotheses(), vec![f("A"), f("A⇒B")]);
+        let input = "0;A⇒B⇒A;Ax K A:=A B:=B\n1;A⇒B⇒A;Ax K B:=C\n2;A∨B⇒A;Ax AndE1\n3;A;MP 0 1";
+        let proof = HilbertProof::read_proof(input).unwrap();
+        assert_eq!(
+            proof.check(),
+            Err(vec![
+                (1, HilbertError::WrongSubstitution(Schema::K)),
+                (2, HilbertError::NotAnInstance(Schema::AndE1)),
+                (3, HilbertError::MPHypNotMatching),
+            ])
+        );
+        assert!(matches!(
+            HilbertRecord::read_record("0;A;Ax Z"),
+            Err(RecordError::UnknownSchema)
+        ));
+    }
+
+    #[test]
+    fn deduction() {
+        let input =
+            "0;A;Hyp\n1;A⇒B;Hyp\n2;B;MP 0 1\n3;B⇒A⇒B∧A;Ax AndI\n4;A⇒B∧A;MP 2 3\n5;B∧A;MP 0 4";
+        let proof = HilbertProof::read_proof(input).unwrap();
+        let discharged = proof.deduction(&f("A")).unwrap();
+        assert_eq!(discharged.check(), Ok(()));
+        assert_eq!(discharged.hypotheses(), vec![f("A⇒B")]);
+        assert_eq!(discharged.records().last().unwrap().formula, f("A⇒B∧A"));
+        let discharged = discharged.deduction(&f("A⇒B")).unwrap();
+        assert_eq!(discharged.check(), Ok(()));
+        assert!(discharged.hypotheses().is_empty());
+        assert_eq!(
+            discharged.records().last().unwrap().formula,
+            f("(A⇒B)⇒A⇒B∧A")
+        );
+        // The printed proof reads back the same.
+        let reread = HilbertProof::read_proof(&discharged.to_string()).unwrap();
+        assert_eq!(reread.records(), discharged.records());
+    }
+}
@@ -0,0 +1,623 @@
+use std::fmt;
+
+use crate::formula::Formula;
+use crate::justif::Jusitification;
+use crate::proof::Proof;
+use crate::record::{Record, Statement};
+use crate::rule::Rule;
+use crate::ruleset::SchemaRule;
+use crate::subst::Substitution;
+use crate::term::Term;
+
+/// How much a hint moves toward the goal, from the most to the least.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Progress {
+    /// The record states the formula to prove in its box
+    Reaches,
+    /// The record opens the box proving the formula, or states a part of it
+    Builds,
+    /// The formula to prove is a part of the record's formula
+    Narrows,
+    /// The record is not related to the formula to prove, or there is no goal
+    Unrelated,
+}
+
+/// A rule application available at the end of a proof.
+#[derive(Debug)]
+pub struct Hint {
+    /// The record that would follow the last one
+    pub record: Record,
+    /// For a record opening a box, the formula the box is meant to prove
+    pub aim: Option<Formula>,
+    pub progress: Progress,
+}
+
+impl fmt::Display for Hint {
+    /// Describes the hint, as in "EAndL 3 gives b" or "you could assume a to prove a⇒c".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let aim = self.aim.as_ref().unwrap_or(&Formula::Top);
+        match &self.record.stmt {
+            Statement::Supposons(hyp) => write!(f, "you could assume {} to prove {}", hyp, aim),
+            Statement::Soit(x) => write!(f, "you could introduce {} to prove {}", x, aim),
+            Statement::Monde => write!(f, "you could open a world to prove {}", aim),
+            Statement::Donc(formula) => {
+                write!(
+                    f,
+                    "{} closes the box and gives {}",
+                    self.record.justif, formula
+                )
+            }
+            Statement::Simple(formula) => write!(f, "{} gives {}", self.record.justif, formula),
+            Statement::Premise(formula) => write!(f, "you could state the premise {}", formula),
+        }
+    }
+}
+
+/// Tells whether `part` occurs in `formula`.
+fn contains(formula: &Formula, part: &Formula) -> bool {
+    formula == part
+        || match formula {
+            Formula::Not(a)
+            | Formula::Forall(_, a)
+            | Formula::Exists(_, a)
+            | Formula::Necessarily(a)
+            | Formula::Possibly(a) => contains(a, part),
+            Formula::Or(a, b)
+            | Formula::And(a, b)
+            | Formula::Implies(a, b)
+            | Formula::RLImplies(a, b)
+            | Formula::Equiv(a, b) => contains(a, part) || contains(b, part),
+            _ => false,
+        }
+}
+
+fn progress(formula: &Formula, target: Option<&Formula>) -> Progress {
+    match target {
+        Some(target) if target == formula => Progress::Reaches,
+        Some(target) if contains(target, formula) => Progress::Builds,
+        Some(target) if contains(formula, target) => Progress::Narrows,
+        _ => Progress::Unrelated,
+    }
+}
+
+/// Matches the `premises` of a schema in order against the `known` formulas, extending
+/// `subst`, and calls `found` with the records cited and the substitution of each match.
+fn match_premises(
+    premises: &[Formula],
+    known: &[(usize, &Formula)],
+    subst: &Substitution,
+    cited: &mut Vec<usize>,
+    found: &mut dyn FnMut(&[usize], &Substitution),
+) {
+    let Some((premise, rest)) = premises.split_first() else {
+        found(cited, subst);
+        return;
+    };
+    for (i, formula) in known {
+        let mut extended = subst.clone();
+        if premise.match_pattern_into(formula, &mut extended) {
+            cited.push(*i);
+            match_premises(rest, known, &extended, cited, found);
+            cited.pop();
+        }
+    }
+}
+
+/// Returns the formula `pattern` stands for, if `subst` binds each of its variables.
+fn instance(pattern: &Formula, subst: &Substitution) -> Option<Formula> {
+    let bound = pattern.variables().iter().all(|v| subst.get(*v).is_some());
+    bound.then(|| subst.apply(pattern))
+}
+
+/// Returns the justification citing the records `cited` by `rule`.
+fn citing(rule: &dyn Rule, cited: &[usize]) -> Option<Jusitification> {
+    let args: Vec<String> = cited.iter().map(|i| i.to_string()).collect();
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    rule.read(&args).ok()
+}
+
+/// Returns the formula ending the box assuming `hyp`, from which a rule of `schemas` concludes
+/// `target`.
+fn box_end(
+    hyp: &Formula,
+    target: &Formula,
+    schemas: &[(&dyn Rule, &SchemaRule)],
+) -> Option<Formula> {
+    schemas.iter().find_map(|(_, schema)| {
+        let mut subst = schema.conclusion().match_pattern(target)?;
+        if !schema.discharged()?.match_pattern_into(hyp, &mut subst) {
+            return None;
+        }
+        instance(schema.premises().first()?, &subst)
+    })
+}
+
+impl Proof {
+    /// Lists the records that could follow the last one, the ones moving the most toward
+    /// `goal` first. Within each box, the formula to prove is deduced from `goal` and the
+    /// boxes opened to prove it, such as `c` in a box assuming `a` when the goal is `a⇒c`.
+    ///
+    /// The hints use the rules of the proof holding in its logic, and the formulas of the
+    /// records accessible from the end of the proof and its open boxes. A rule declared by a
+    /// schema applies to the formulas matching its premises, toward the formula to prove only
+    /// if it concludes a larger formula than each of its premises. The proof is not checked.
+    pub fn hints(&self, goal: Option<&Formula>) -> Vec<Hint> {
+        let records = self.records();
+        let next = records.len();
+        let ctxt: Vec<usize> = records.last().map(|r| r.ctxt.clone()).unwrap_or_default();
+        let rules: Vec<&dyn Rule> = self
+            .rules()
+            .rules()
+            .filter(|rule| rule.logic().holds_in(self.logic()))
+            .collect();
+        let schemas: Vec<(&dyn Rule, &SchemaRule)> = rules
+            .iter()
+            .filter_map(|rule| Some((*rule, rule.schema()?)))
+            .collect();
+        // The primitive rules are known by name, whatever their spelling
+        let primitive = |name: &str| {
+            rules
+                .iter()
+                .any(|rule| rule.schema().is_none() && rule.name() == name)
+        };
+        // The opening record of a box, if the context points to one
+        let box_opening = |b: &usize| records.get(*b).map(|r| &r.stmt);
+        // The formula to prove in each open box, the first one being the goal
+        let mut targets = vec![goal.cloned()];
+        for b in &ctxt {
+            let target = match (box_opening(b), targets.last().unwrap()) {
+                (Some(Statement::Supposons(h)), Some(target)) => box_end(h, target, &schemas)
+                    .or_else(|| match target {
+                        Formula::Implies(a, c) if **a == *h && primitive("IImpl") => {
+                            Some(*c.clone())
+                        }
+                        Formula::Not(a) if **a == *h && primitive("INot") => Some(Formula::Bottom),
+                        _ => None,
+                    }),
+                (Some(Statement::Soit(x)), Some(Formula::Forall(y, body))) => {
+                    Some(body.substitute_term(*y, &Term::Variable(*x)))
+                }
+                (Some(Statement::Monde), Some(Formula::Necessarily(a))) => Some(*a.clone()),
+                _ => None,
+            };
+            targets.push(target);
+        }
+        let target = targets.last().unwrap().as_ref();
+        let known: Vec<(usize, &Formula)> = records
+            .iter()
+            .filter(|r| {
+                !matches!(r.stmt, Statement::Soit(_) | Statement::Monde)
+                    && r.justif != Jusitification::Gap
+                    && self.accessible(&ctxt, r)
+            })
+            .map(|r| (r.id, r.stmt.get_formula()))
+            .collect();
+        let find = |formula: &Formula| known.iter().find(|(_, f)| *f == formula).map(|(i, _)| *i);
+
+        let mut derivations: Vec<(Formula, Jusitification)> = Vec::new();
+        for (rule, schema) in &schemas {
+            if schema.discharged().is_some() {
+                continue;
+            }
+            let conclusion = schema.conclusion();
+            let mut seeds = Vec::new();
+            if schema
+                .premises()
+                .iter()
+                .any(|p| p.size() >= conclusion.size())
+            {
+                seeds.push(Substitution::new());
+            }
+            if let Some(subst) = target.and_then(|t| conclusion.match_pattern(t)) {
+                seeds.push(subst);
+            }
+            for seed in seeds {
+                let mut found = |cited: &[usize], subst: &Substitution| {
+                    if let (Some(formula), Some(justif)) =
+                        (instance(conclusion, subst), citing(*rule, cited))
+                    {
+                        derivations.push((formula, justif));
+                    }
+                };
+                match_premises(
+                    schema.premises(),
+                    &known,
+                    &seed,
+                    &mut Vec::new(),
+                    &mut found,
+                );
+            }
+        }
+        for (i, formula) in &known {
+            let i = *i;
+            match formula {
+                Formula::And(a, b) => {
+                    if primitive("EAndL") {
+                        derivations.push((*a.clone(), Jusitification::EAndL(i)));
+                    }
+                    if primitive("EAndR") {
+                        derivations.push((*b.clone(), Jusitification::EAndR(i)));
+                    }
+                }
+                Formula::Implies(a, b) if primitive("EImpl") => {
+                    if let Some(hyp) = find(a) {
+                        let justif = Jusitification::EImpl {
+                            hyp,
+                            implication: i,
+                        };
+                        derivations.push((*b.clone(), justif));
+                    }
+                }
+                Formula::Or(a, b) if primitive("EOr") => {
+                    for (a_to_c, left) in &known {
+                        let Formula::Implies(a2, c) = left else {
+                            continue;
+                        };
+                        if a2 != a {
+                            continue;
+                        }
+                        if let Some(b_to_c) = find(&Formula::Implies(b.clone(), c.clone())) {
+                            let justif = Jusitification::EOr {
+                                a_to_c: *a_to_c,
+                                b_to_c,
+                                a_or_b: i,
+                            };
+                            derivations.push((*c.clone(), justif));
+                        }
+                    }
+                }
+                Formula::Bottom if primitive("Efq") => {
+                    if let Some(target) = target {
+                        derivations.push((target.clone(), Jusitification::Efq(i)));
+                    }
+                }
+                Formula::Not(a) => {
+                    if let Some(hyp) = find(a).filter(|_| primitive("ENot")) {
+                        let justif = Jusitification::ENot { hyp, negation: i };
+                        derivations.push((Formula::Bottom, justif));
+                    }
+                    if let (Formula::Not(a), true) = (a.as_ref(), primitive("Raa")) {
+                        derivations.push((*a.clone(), Jusitification::Raa(i)));
+                    }
+                }
+                Formula::Equiv(a, b) => {
+                    if primitive("EEquivL") {
+                        let forward = Formula::Implies(a.clone(), b.clone());
+                        derivations.push((forward, Jusitification::EEquivL(i)));
+                    }
+                    if primitive("EEquivR") {
+                        let backward = Formula::Implies(b.clone(), a.clone());
+                        derivations.push((backward, Jusitification::EEquivR(i)));
+                    }
+                }
+                Formula::Forall(x, body) if primitive("EForall") => {
+                    for b in &ctxt {
+                        if let Some(Statement::Soit(y)) = box_opening(b) {
+                            let instance = body.substitute_term(*x, &Term::Variable(*y));
+                            derivations
+                                .push((instance, Jusitification::EForall(i, Term::Variable(*y))));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut hints = Vec::new();
+        // Assuming a hypothesis foreign to the formula to prove, as in reductio ad absurdum
+        let mut last_resorts = Vec::new();
+        let simple = |formula: Formula, justif: Jusitification| Hint {
+            progress: progress(&formula, target),
+            record: Record {
+                id: next,
+                ctxt: ctxt.clone(),
+                stmt: Statement::Simple(formula),
+                justif,
+            },
+            aim: None,
+        };
+        let opening = |stmt: Statement, aim: &Formula| Hint {
+            progress: Progress::Builds,
+            record: Record {
+                id: next,
+                ctxt: ctxt.iter().copied().chain([next]).collect(),
+                stmt,
+                justif: Jusitification::Hyp,
+            },
+            aim: Some(aim.clone()),
+        };
+        if let Some(target) = target {
+            // A box may not end with its hypothesis
+            let last_in_box = records.last().filter(|r| Some(&r.id) != ctxt.last());
+            let stated = last_in_box.is_some_and(|r| r.stmt.get_formula() == target);
+            if !stated && primitive("Rwrt") {
+                let normal = target.clone().normalize();
+                for (i, formula) in &known {
+                    if (*formula).clone().normalize() == normal {
+                        hints.push(simple(target.clone(), Jusitification::Rwrt(*i)));
+                        break;
+                    }
+                }
+            }
+            match target {
+                // Already known, at most restated by Rwrt
+                _ if find(target).is_some() => (),
+                Formula::And(a, b) if primitive("IAnd") => {
+                    if let (Some(left), Some(right)) = (find(a), find(b)) {
+                        hints.push(simple(target.clone(), Jusitification::IAnd { left, right }));
+                    }
+                }
+                Formula::Or(a, b) => {
+                    if let Some(i) = find(a).filter(|_| primitive("IOrR")) {
+                        hints.push(simple(target.clone(), Jusitification::IOrR(i, *b.clone())));
+                    }
+                    if let Some(i) = find(b).filter(|_| primitive("IOrL")) {
+                        hints.push(simple(target.clone(), Jusitification::IOrL(i, *a.clone())));
+                    }
+                }
+                Formula::Equiv(a, b) if primitive("IEquiv") => {
+                    let forward = find(&Formula::Implies(a.clone(), b.clone()));
+                    let backward = find(&Formula::Implies(b.clone(), a.clone()));
+                    if let (Some(forward), Some(backward)) = (forward, backward) {
+                        let justif = Jusitification::IEquiv { forward, backward };
+                        hints.push(simple(target.clone(), justif));
+                    }
+                }
+                Formula::Top if primitive("ITop") => {
+                    hints.push(simple(Formula::Top, Jusitification::ITop))
+                }
+                Formula::Implies(a, _) if primitive("IImpl") => {
+                    hints.push(opening(Statement::Supposons(*a.clone()), target));
+                }
+                Formula::Not(a) if primitive("INot") => {
+                    hints.push(opening(Statement::Supposons(*a.clone()), target));
+                }
+                Formula::Forall(x, _) if primitive("IForall") => {
+                    hints.push(opening(Statement::Soit(*x), target))
+                }
+                Formula::Necessarily(_) if primitive("INec") => {
+                    hints.push(opening(Statement::Monde, target))
+                }
+                _ => (),
+            }
+            if find(target).is_none() {
+                for (_, schema) in &schemas {
+                    let Some(discharged) = schema.discharged() else {
+                        continue;
+                    };
+                    let hyp = schema
+                        .conclusion()
+                        .match_pattern(target)
+                        .and_then(|subst| instance(discharged, &subst));
+                    let Some(hyp) = hyp else {
+                        continue;
+                    };
+                    let builds = contains(target, &hyp);
+                    let stmt = Statement::Supposons(hyp);
+                    if hints
+                        .iter()
+                        .chain(&last_resorts)
+                        .any(|h| h.record.stmt == stmt)
+                    {
+                        continue;
+                    }
+                    let mut hint = opening(stmt, target);
+                    if builds {
+                        hints.push(hint);
+                    } else {
+                        hint.progress = Progress::Unrelated;
+                        last_resorts.push(hint);
+                    }
+                }
+            }
+        }
+        for (formula, justif) in derivations {
+            let duplicate = hints
+                .iter()
+                .any(|h: &Hint| h.record.stmt.get_formula() == &formula);
+            if find(&formula).is_none() && !duplicate {
+                hints.push(simple(formula, justif));
+            }
+        }
+        hints.append(&mut last_resorts);
+        if let (Some(b), Some(last)) = (ctxt.last(), records.last()) {
+            if last.id != *b {
+                let consequence = last.stmt.get_formula().clone();
+                let outer = &ctxt[..ctxt.len() - 1];
+                let mut closed = Vec::new();
+                match box_opening(b) {
+                    Some(Statement::Supposons(h)) => {
+                        if consequence == Formula::Bottom && primitive("INot") {
+                            let negation = Formula::Not(Box::new(h.clone()));
+                            closed.push((negation, Jusitification::INot));
+                        }
+                        if primitive("IImpl") {
+                            let implication = Formula::Implies(
+                                Box::new(h.clone()),
+                                Box::new(consequence.clone()),
+                            );
+                            closed.push((implication, Jusitification::IImpl));
+                        }
+                        // The other premises of the rule are cited from out of the box
+                        let outer_known: Vec<(usize, &Formula)> = known
+                            .iter()
+                            .filter(|(i, _)| {
+                                records.get(*i).is_some_and(|r| self.accessible(outer, r))
+                            })
+                            .copied()
+                            .collect();
+                        for (rule, schema) in &schemas {
+                            let Some(mut subst) =
+                                schema.discharged().and_then(|d| d.match_pattern(h))
+                            else {
+                                continue;
+                            };
+                            let Some((end, premises)) = schema.premises().split_first() else {
+                                continue;
+                            };
+                            if !end.match_pattern_into(&consequence, &mut subst) {
+                                continue;
+                            }
+                            let mut found = |cited: &[usize], subst: &Substitution| {
+                                if let (Some(formula), Some(justif)) =
+                                    (instance(schema.conclusion(), subst), citing(*rule, cited))
+                                {
+                                    closed.push((formula, justif));
+                                }
+                            };
+                            match_premises(
+                                premises,
+                                &outer_known,
+                                &subst,
+                                &mut Vec::new(),
+                                &mut found,
+                            );
+                        }
+                    }
+                    Some(Statement::Soit(x)) if primitive("IForall") => closed.push((
+                        Formula::Forall(*x, Box::new(consequence)),
+                        Jusitification::IForall,
+                    )),
+                    Some(Statement::Monde) if primitive("INec") => closed.push((
+                        Formula::Necessarily(Box::new(consequence)),
+                        Jusitification::INec,
+                    )),
+                    _ => (),
+                };
+                for (formula, justif) in closed {
+                    let outer_target = targets[targets.len() - 2].as_ref();
+                    hints.push(Hint {
+                        progress: progress(&formula, outer_target),
+                        record: Record {
+                            id: next,
+                            ctxt: outer.to_vec(),
+                            stmt: Statement::Donc(formula),
+                            justif,
+                        },
+                        aim: None,
+                    });
+                }
+            }
+        }
+        hints.sort_by_key(|h| h.progress);
+        hints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{CheckUpResult, Logic};
+    use crate::rule::RuleRegistry;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    #[test]
+    fn descriptions() {
+        let proof = Proof::read_proof("0;0;Supposons a∧(a⇒b);Hyp\n1;0;a;EAndL 0\n").unwrap();
+        let hints: Vec<String> = proof
+            .hints(Some(&f("a∧(a⇒b)⇒b∨c")))
+            .iter()
+            .map(|h| h.to_string())
+            .collect();
+        assert_eq!(
+            hints,
+            [
+                "EAndR 0 gives a⇒b",
+                "IImpl closes the box and gives a∧(a⇒b)⇒a",
+            ]
+        );
+        let proof = Proof::read_proof("").unwrap();
+        let hints = proof.hints(Some(&f("a⇒c")));
+        assert_eq!(hints[0].to_string(), "you could assume a to prove a⇒c");
+        assert!(proof.hints(None).is_empty());
+    }
+
+    #[test]
+    fn premises() {
+        let input = "0;;Prémisse a⇒b;Hyp\n1;1;Supposons a;Hyp\n";
+        let proof = Proof::read_proof(input).unwrap();
+        let hints: Vec<String> = proof.hints(None).iter().map(|h| h.to_string()).collect();
+        assert!(
+            hints.contains(&"EImpl 1 0 gives b".to_string()),
+            "{:?}",
+            hints
+        );
+        // The premises hold in the world of the goal only
+        let proof = Proof::read_proof(&format!("{}2;2;Monde;Hyp\n3;2;a;?\n", input)).unwrap();
+        let hints: Vec<String> = proof.hints(None).iter().map(|h| h.to_string()).collect();
+        assert!(!hints.iter().any(|h| h.contains("EImpl")), "{:?}", hints);
+    }
+
+    #[test]
+    fn malformed() {
+        // The context and the ids point past the records, the proof not being checked
+        for input in [
+            "0;0;Supposons a;Hyp\n1;0,7;a∧a;IAnd 0 0\n",
+            "0;0;Supposons a∧b;Hyp\n5;0;a;EAndL 0\n",
+            "0;0;Supposons ∀xP(x);Hyp\n1;0,9;P(x);?\n",
+        ] {
+            let proof = Proof::read_proof(input).unwrap();
+            proof.hints(Some(&f("a∧b⇒a")));
+            proof.hints(None);
+        }
+    }
+
+    #[test]
+    fn rule_set() {
+        let rules =
+            RuleRegistry::read_rule_set(include_str!("../rules/alternative.rules")).unwrap();
+        let describe = |input: &str, goal: &str| -> Vec<String> {
+            let proof = Proof::read_proof_with(input, rules.clone()).unwrap();
+            proof
+                .hints(Some(&f(goal)))
+                .iter()
+                .map(|h| h.to_string())
+                .collect()
+        };
+        let input = "0;0;Supposons a∧(a⇒b);Hyp\n";
+        assert_eq!(
+            describe(input, "a∧(a⇒b)⇒b∨c"),
+            [
+                "∧E1 0 gives a",
+                "∧E2 0 gives a⇒b",
+                "you could assume ¬(b∨c) to prove b∨c",
+            ]
+        );
+        let input = format!("{}1;0;a;∧E1 0\n2;0;a⇒b;∧E2 0\n", input);
+        let hints = describe(&input, "a∧(a⇒b)⇒b∨c");
+        assert_eq!(hints[0], "⇒E 2 1 gives b");
+        assert!(!hints.iter().any(|h| h.contains("EAnd")), "{:?}", hints);
+        let input = format!("{}3;0;b;⇒E 2 1\n", input);
+        assert_eq!(describe(&input, "a∧(a⇒b)⇒b∨c")[0], "∨I1 3 gives b∨c");
+        let input = format!("{}4;0;b∨c;∨I1 3\n", input);
+        assert_eq!(
+            describe(&input, "a∧(a⇒b)⇒b∨c")[0],
+            "⇒I closes the box and gives a∧(a⇒b)⇒b∨c"
+        );
+        assert_eq!(describe("", "¬a")[0], "you could assume a to prove ¬a");
+    }
+
+    #[test]
+    fn following_the_best_hint() {
+        for goal in ["(a∨b)∧(a⇒c)∧(b⇒c)⇒c∧(a∨b)", "a⇒¬¬a", "(a⇔b)⇒(b⇔a)"]
+        {
+            let goal = f(goal);
+            let mut proof = Proof::read_proof("").unwrap();
+            proof.set_logic(Logic::Intuitionistic);
+            for _ in 0..12 {
+                let last = proof.records().last();
+                if last.is_some_and(|r| r.ctxt.is_empty() && r.stmt.get_formula() == &goal) {
+                    break;
+                }
+                let hint = proof.hints(Some(&goal)).remove(0);
+                proof.add_record(hint.record);
+            }
+            proof.check();
+            assert!(matches!(proof.state(), CheckUpResult::Valid), "{}", proof);
+            assert_eq!(proof.records().last().unwrap().stmt.get_formula(), &goal);
+        }
+    }
+}
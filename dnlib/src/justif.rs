@@ -1,8 +1,11 @@
+use std::fmt;
 use std::num::ParseIntError;
 
 use thiserror::Error;
 
 use crate::formula::{Formula, TokenizationError};
+use crate::rule::RuleRegistry;
+use crate::term::Term;
 
 #[allow(non_camel_case_types)]
 #[derive(Error,Debug, PartialEq)]
@@ -77,9 +80,67 @@ pub enum ReadError {
     Missing_In_Rwrt_Reference,
     #[error("Rwrt is provided a position of the formula that is not a valid number: {0}")]
     Invalid_In_Rwrt_Reference(ParseIntError),
+    #[error("EForall is missing the position of the universal formula")]
+    Missing_In_EForall_Reference,
+    #[error("EForall is provided a position of the universal formula that is not a valid number: {0}")]
+    Invalid_In_EForall_Reference(ParseIntError),
+    #[error("EForall is missing the term to be substituted")]
+    Missing_In_EForall_Term,
+    #[error("EForall is provided a term to be substituted that is not a valid term: {0}")]
+    Invalid_In_EForall_Term(TokenizationError),
+    #[error("IExists is missing the position of the instance formula")]
+    Missing_In_IExists_Reference,
+    #[error("IExists is provided a position of the instance formula that is not a valid number: {0}")]
+    Invalid_In_IExists_Reference(ParseIntError),
+    #[error("IExists is missing the witness term")]
+    Missing_In_IExists_Term,
+    #[error("IExists is provided a witness that is not a valid term: {0}")]
+    Invalid_In_IExists_Term(TokenizationError),
+    #[error("EExists is missing the position of the existential formula")]
+    Missing_In_EExists_Exists,
+    #[error("EExists is provided a position of the existential formula that is not a valid number: {0}")]
+    Invalid_In_EExists_Exists(ParseIntError),
+    #[error("EExists is missing the position of the universal formula")]
+    Missing_In_EExists_Forall,
+    #[error("EExists is provided a position of the universal formula that is not a valid number: {0}")]
+    Invalid_In_EExists_Forall(ParseIntError),
+    #[error("ENec is missing the position of the necessary formula")]
+    Missing_In_ENec_Reference,
+    #[error("ENec is provided a position of the necessary formula that is not a valid number: {0}")]
+    Invalid_In_ENec_Reference(ParseIntError),
+    #[error("ENot is missing the position of the formula")]
+    Missing_In_ENot_Hyp,
+    #[error("ENot is provided a position of the formula that is not a valid number: {0}")]
+    Invalid_In_ENot_Hyp(ParseIntError),
+    #[error("ENot is missing the position of the negation")]
+    Missing_In_ENot_Negation,
+    #[error("ENot is provided a position of the negation that is not a valid number: {0}")]
+    Invalid_In_ENot_Negation(ParseIntError),
+    #[error("IEquiv is missing the position of the a->b formula")]
+    Missing_In_IEquiv_Forward,
+    #[error("IEquiv is provided a position of the a->b formula that is not a valid number: {0}")]
+    Invalid_In_IEquiv_Forward(ParseIntError),
+    #[error("IEquiv is missing the position of the b->a formula")]
+    Missing_In_IEquiv_Backward,
+    #[error("IEquiv is provided a position of the b->a formula that is not a valid number: {0}")]
+    Invalid_In_IEquiv_Backward(ParseIntError),
+    #[error("EEquivL is missing the position of the equivalence")]
+    Missing_In_EEquivL_Reference,
+    #[error("EEquivL is provided a position of the equivalence that is not a valid number: {0}")]
+    Invalid_In_EEquivL_Reference(ParseIntError),
+    #[error("EEquivR is missing the position of the equivalence")]
+    Missing_In_EEquivR_Reference,
+    #[error("EEquivR is provided a position of the equivalence that is not a valid number: {0}")]
+    Invalid_In_EEquivR_Reference(ParseIntError),
+    #[error("{0} is provided a position that is not a valid number: {1}")]
+    Invalid_In_Named_Reference(String, ParseIntError),
+    #[error("RPos is missing the position of the possible formula")]
+    Missing_In_RPos_Reference,
+    #[error("RPos is provided a position of the possible formula that is not a valid number: {0}")]
+    Invalid_In_RPos_Reference(ParseIntError),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Jusitification {
     /// Introduction of Or (new formula on left)
     IOrL(usize, Formula),
@@ -108,131 +169,187 @@ pub enum Jusitification {
     /// Reductio ad absorbum
     Raa(usize),
     /// Rewrite
-    Rwrt(usize)
+    Rwrt(usize),
+    /// Introduction of Forall, closing a `Soit` box
+    IForall,
+    /// Elimination of Forall, instantiating the bound variable with a term
+    EForall(usize, Term),
+    /// Introduction of Exists, the term being the witness
+    IExists(usize, Term),
+    /// Elimination of Exists, from ∃xφ and ∀y(φ[x:=y]⇒c)
+    EExists { exists: usize, forall: usize },
+    /// Introduction of Necessarily, closing a `Monde` box
+    INec,
+    /// Elimination of Necessarily, in an accessible world
+    ENec(usize),
+    /// Reiteration of a Possibly in an accessible world (S5)
+    RPos(usize),
+    /// Introduction of Not, closing a `Supposons` box ending with ⊥
+    INot,
+    /// Elimination of Not, from a formula and its negation
+    ENot { hyp: usize, negation: usize },
+    /// Introduction of Equiv, from both implications
+    IEquiv { forward: usize, backward: usize },
+    /// Elimination of Equiv (getting the left to right implication)
+    EEquivL(usize),
+    /// Elimination of Equiv (getting the right to left implication)
+    EEquivR(usize),
+    /// Introduction of Top
+    ITop,
+    /// Placeholder, `?`: the formula is to be derived from earlier records
+    Gap,
+    /// Application of a rule citing records only, such as a derived rule, by its name
+    Named(String, Vec<usize>),
 }
 
 impl Jusitification {
-    pub fn read(input: &str) -> Result<Self, ReadError> {
-        let mut s = input.split(' ');
-        let rule = s.next().ok_or(ReadError::InputEmpty)?;
-        let r = match rule {
-            "IOrL" => {
-                let left_pos = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_IOrL_LeftPos)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_IOrL_LeftPos(err))?;
-                let right_formula =
-                    Formula::read(s.next().ok_or(ReadError::Missing_In_IOrL_Formula)?)
-                        .map_err(|err| ReadError::Invalid_In_IOrL_Formula(err))?;
-                Ok(Self::IOrL(left_pos, right_formula))
-            }
-            "IOrR" => {
-                let right_pos = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_IOrR_RightPos)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_IOrR_RightPos(err))?;
-                let left_formula =
-                    Formula::read(s.next().ok_or(ReadError::Missing_In_IOrR_Formula)?)
-                        .map_err(|err| ReadError::Invalid_In_IOrR_Formula(err))?;
-                Ok(Self::IOrR(right_pos, left_formula))
-            }
-            "EOr" => {
-                let a_to_c = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EOr_A_to_C)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EOr_A_to_C(err))?;
-                let b_to_c = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EOr_B_to_C)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EOr_B_to_C(err))?;
-                let a_or_b = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EOr_A_or_B)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EOr_A_or_B(err))?;
-                Ok(Self::EOr {
-                    a_to_c,
-                    b_to_c,
-                    a_or_b,
-                })
-            }
-            "IAnd" => {
-                let left = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_IAnd_Left)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_IAnd_Left(err))?;
-                let right = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_IAnd_Right)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_IAnd_Right(err))?;
-                Ok(Self::IAnd { left, right })
+    /// Returns the records referenced by the justification.
+    pub fn references(&self) -> Vec<usize> {
+        match self {
+            Self::IOrL(pos, _) | Self::IOrR(pos, _) => vec![*pos],
+            Self::EOr {
+                a_to_c,
+                b_to_c,
+                a_or_b,
+            } => vec![*a_to_c, *b_to_c, *a_or_b],
+            Self::IAnd { left, right } => vec![*left, *right],
+            Self::EAndL(pos) | Self::EAndR(pos) => vec![*pos],
+            Self::Hyp | Self::IImpl | Self::IForall | Self::INec => Vec::new(),
+            Self::INot | Self::ITop | Self::Gap => Vec::new(),
+            Self::ENot { hyp, negation } => vec![*hyp, *negation],
+            Self::IEquiv { forward, backward } => vec![*forward, *backward],
+            Self::EEquivL(pos) | Self::EEquivR(pos) => vec![*pos],
+            Self::EImpl { hyp, implication } => vec![*hyp, *implication],
+            Self::Efq(pos) | Self::Raa(pos) | Self::Rwrt(pos) => vec![*pos],
+            Self::EForall(pos, _) | Self::IExists(pos, _) => vec![*pos],
+            Self::EExists { exists, forall } => vec![*exists, *forall],
+            Self::ENec(pos) | Self::RPos(pos) => vec![*pos],
+            Self::Named(_, premises) => premises.clone(),
+        }
+    }
+
+    /// Replaces every referenced record `i` by `map(i)`.
+    pub(crate) fn renumber(&mut self, map: impl Fn(usize) -> usize) {
+        match self {
+            Self::IOrL(pos, _) | Self::IOrR(pos, _) => *pos = map(*pos),
+            Self::EOr {
+                a_to_c,
+                b_to_c,
+                a_or_b,
+            } => {
+                *a_to_c = map(*a_to_c);
+                *b_to_c = map(*b_to_c);
+                *a_or_b = map(*a_or_b);
             }
-            "EAndL" => {
-                let reference = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EAndL_Reference)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EAndL_Reference(err))?;
-                Ok(Self::EAndL(reference))
+            Self::IAnd { left, right } => {
+                *left = map(*left);
+                *right = map(*right);
             }
-            "EAndR" => {
-                let reference = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EAndR_Reference)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EAndR_Reference(err))?;
-                Ok(Self::EAndR(reference))
+            Self::EAndL(pos) | Self::EAndR(pos) => *pos = map(*pos),
+            Self::Hyp | Self::IImpl | Self::IForall | Self::INec => (),
+            Self::INot | Self::ITop | Self::Gap => (),
+            Self::ENot { hyp, negation } => {
+                *hyp = map(*hyp);
+                *negation = map(*negation);
             }
-            "Hyp" => Ok(Self::Hyp),
-            "IImpl" => Ok(Self::IImpl),
-            "EImpl" => {
-                let hyp = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EImpl_Hyp)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EImpl_Hyp(err))?;
-                let implication = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_EImpl_Implication)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_EImpl_Implication(err))?;
-                Ok(Self::EImpl { hyp, implication })
+            Self::IEquiv { forward, backward } => {
+                *forward = map(*forward);
+                *backward = map(*backward);
             }
-            "Efq" => {
-                let reference = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_Efq_Reference)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_Efq_Reference(err))?;
-                Ok(Self::Efq(reference))
+            Self::EEquivL(pos) | Self::EEquivR(pos) => *pos = map(*pos),
+            Self::EImpl { hyp, implication } => {
+                *hyp = map(*hyp);
+                *implication = map(*implication);
             }
-            "Raa" => {
-                let reference = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_Raa_Reference)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_Raa_Reference(err))?;
-                Ok(Self::Raa(reference))
+            Self::Efq(pos) | Self::Raa(pos) | Self::Rwrt(pos) => *pos = map(*pos),
+            Self::EForall(pos, _) | Self::IExists(pos, _) => *pos = map(*pos),
+            Self::EExists { exists, forall } => {
+                *exists = map(*exists);
+                *forall = map(*forall);
             }
-            "Rwrt" => {
-                let reference = s
-                    .next()
-                    .ok_or(ReadError::Missing_In_Rwrt_Reference)?
-                    .parse::<usize>()
-                    .map_err(|err| ReadError::Invalid_In_Rwrt_Reference(err))?;
-                Ok(Self::Rwrt(reference))
+            Self::ENec(pos) | Self::RPos(pos) => *pos = map(*pos),
+            Self::Named(_, premises) => premises.iter_mut().for_each(|p| *p = map(*p)),
+        }
+    }
+
+    /// Reads a justification citing a primitive rule, or any other rule by name. The rules of
+    /// a [`RuleRegistry`] may be read with [`RuleRegistry::read`].
+    pub fn read(input: &str) -> Result<Self, ReadError> {
+        RuleRegistry::standard().read(input)
+    }
+
+    /// Returns the name of the rule the justification cites.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::IOrL(_, _) => "IOrL",
+            Self::IOrR(_, _) => "IOrR",
+            Self::EOr { .. } => "EOr",
+            Self::IAnd { .. } => "IAnd",
+            Self::EAndL(_) => "EAndL",
+            Self::EAndR(_) => "EAndR",
+            Self::Hyp => "Hyp",
+            Self::IImpl => "IImpl",
+            Self::EImpl { .. } => "EImpl",
+            Self::Efq(_) => "Efq",
+            Self::Raa(_) => "Raa",
+            Self::Rwrt(_) => "Rwrt",
+            Self::IForall => "IForall",
+            Self::EForall(_, _) => "EForall",
+            Self::IExists(_, _) => "IExists",
+            Self::EExists { .. } => "EExists",
+            Self::INec => "INec",
+            Self::ENec(_) => "ENec",
+            Self::RPos(_) => "RPos",
+            Self::INot => "INot",
+            Self::ENot { .. } => "ENot",
+            Self::IEquiv { .. } => "IEquiv",
+            Self::EEquivL(_) => "EEquivL",
+            Self::EEquivR(_) => "EEquivR",
+            Self::ITop => "ITop",
+            Self::Gap => "?",
+            Self::Named(name, _) => name,
+        }
+    }
+}
+
+impl fmt::Display for Jusitification {
+    /// Prints the justification in the syntax accepted by [`Jusitification::read`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOrL(pos, formula) => write!(f, "IOrL {} {}", pos, formula),
+            Self::IOrR(pos, formula) => write!(f, "IOrR {} {}", pos, formula),
+            Self::EOr {
+                a_to_c,
+                b_to_c,
+                a_or_b,
+            } => write!(f, "EOr {} {} {}", a_to_c, b_to_c, a_or_b),
+            Self::IAnd { left, right } => write!(f, "IAnd {} {}", left, right),
+            Self::EAndL(pos) => write!(f, "EAndL {}", pos),
+            Self::EAndR(pos) => write!(f, "EAndR {}", pos),
+            Self::Hyp => write!(f, "Hyp"),
+            Self::IImpl => write!(f, "IImpl"),
+            Self::EImpl { hyp, implication } => write!(f, "EImpl {} {}", hyp, implication),
+            Self::Efq(pos) => write!(f, "Efq {}", pos),
+            Self::Raa(pos) => write!(f, "Raa {}", pos),
+            Self::Rwrt(pos) => write!(f, "Rwrt {}", pos),
+            Self::IForall => write!(f, "IForall"),
+            Self::EForall(pos, term) => write!(f, "EForall {} {}", pos, term),
+            Self::IExists(pos, term) => write!(f, "IExists {} {}", pos, term),
+            Self::EExists { exists, forall } => write!(f, "EExists {} {}", exists, forall),
+            Self::INec => write!(f, "INec"),
+            Self::ENec(pos) => write!(f, "ENec {}", pos),
+            Self::RPos(pos) => write!(f, "RPos {}", pos),
+            Self::INot => write!(f, "INot"),
+            Self::ENot { hyp, negation } => write!(f, "ENot {} {}", hyp, negation),
+            Self::IEquiv { forward, backward } => write!(f, "IEquiv {} {}", forward, backward),
+            Self::EEquivL(pos) => write!(f, "EEquivL {}", pos),
+            Self::EEquivR(pos) => write!(f, "EEquivR {}", pos),
+            Self::ITop => write!(f, "ITop"),
+            Self::Gap => write!(f, "?"),
+            Self::Named(name, premises) => {
+                write!(f, "{}", name)?;
+                premises.iter().try_for_each(|p| write!(f, " {}", p))
             }
-            _ => Err(ReadError::UnknownRule),
-        }?;
-        match s.next() {
-            Some(_) => Err(ReadError::InputTooLarge),
-            None => Ok(r),
         }
     }
 }
@@ -263,4 +380,39 @@ mod tests {
         let r = Jusitification::read(input);
         matches!(r,Err(ReadError::Invalid_In_IOrL_LeftPos(_)));
     }
+
+    #[test]
+    fn EForall_legal() {
+        let input = "EForall 2 f(y)";
+        let r = Jusitification::read(input);
+        assert_eq!(r, Ok(Jusitification::EForall(2, Term::Function('f', vec![Term::Variable('y')]))));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for input in [
+            "IOrL 1 ¬x",
+            "EOr 1 2 3",
+            "EImpl 4 2",
+            "Hyp",
+            "EForall 2 f(y)",
+            "ENec 0",
+            "?",
+            "MT 4 7",
+            "ENot 1 3",
+            "IEquiv 2 5",
+            "EEquivR 4",
+            "INot",
+            "ITop",
+        ] {
+            assert_eq!(Jusitification::read(input).unwrap().to_string(), input);
+        }
+    }
+
+    #[test]
+    fn EForall_missing_term() {
+        let input = "EForall 2";
+        let r = Jusitification::read(input);
+        assert_eq!(r, Err(ReadError::Missing_In_EForall_Term));
+    }
 }
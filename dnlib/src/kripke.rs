@@ -0,0 +1,376 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::formula::{Formula, NotPropositional};
+
+/// A finite Kripke model: worlds, an accessibility relation between them and the variables
+/// forced at each world.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KripkeModel {
+    /// Variables forced at each world
+    valuation: Vec<BTreeSet<char>>,
+    /// Worlds accessible from each world
+    accessible: Vec<BTreeSet<usize>>,
+}
+
+impl KripkeModel {
+    /// Builds a model from the variables forced at each world and the worlds accessible from
+    /// each world. References to worlds that do not exist are dropped.
+    pub fn new(valuation: Vec<BTreeSet<char>>, mut accessible: Vec<BTreeSet<usize>>) -> Self {
+        let n = valuation.len();
+        accessible.resize(n, BTreeSet::new());
+        for successors in accessible.iter_mut() {
+            successors.retain(|w| *w < n);
+        }
+        Self {
+            valuation,
+            accessible,
+        }
+    }
+
+    /// Builds the intuitionistic model of a tree: the accessibility relation is the reflexive
+    /// and transitive closure of `edges`.
+    pub fn from_tree(valuation: Vec<BTreeSet<char>>, edges: &[(usize, usize)]) -> Self {
+        let n = valuation.len();
+        let mut accessible: Vec<BTreeSet<usize>> = (0..n).map(|w| BTreeSet::from([w])).collect();
+        // Floyd–Warshall like closure, models are small.
+        for (from, to) in edges.iter() {
+            accessible[*from].insert(*to);
+        }
+        for k in 0..n {
+            for i in 0..n {
+                if accessible[i].contains(&k) {
+                    let via_k = accessible[k].clone();
+                    accessible[i].extend(via_k);
+                }
+            }
+        }
+        Self::new(valuation, accessible)
+    }
+
+    pub fn worlds(&self) -> usize {
+        self.valuation.len()
+    }
+
+    /// Variables forced at world `w`.
+    pub fn valuation(&self, w: usize) -> &BTreeSet<char> {
+        &self.valuation[w]
+    }
+
+    /// Worlds accessible from world `w`.
+    pub fn accessible(&self, w: usize) -> &BTreeSet<usize> {
+        &self.accessible[w]
+    }
+
+    /// Tells whether the model is an intuitionistic one: its relation is a preorder and
+    /// forced variables stay forced in accessible worlds.
+    pub fn is_intuitionistic(&self) -> bool {
+        (0..self.worlds()).all(|w| {
+            self.accessible[w].contains(&w)
+                && self.accessible[w].iter().all(|v| {
+                    self.accessible[*v].is_subset(&self.accessible[w])
+                        && self.valuation[w].is_subset(&self.valuation[*v])
+                })
+        })
+    }
+
+    /// Intuitionistic forcing: tells whether world `w` forces `f`, if it is propositional.
+    pub fn forces(&self, w: usize, f: &Formula) -> Result<bool, NotPropositional> {
+        if !f.is_propositional() {
+            return Err(NotPropositional);
+        }
+        Ok(self.forces_propositional(w, f))
+    }
+
+    /// Tells whether world `w` forces the propositional formula `f`.
+    fn forces_propositional(&self, w: usize, f: &Formula) -> bool {
+        match f {
+            Formula::Top => true,
+            Formula::Bottom => false,
+            Formula::Variable(v) => self.valuation[w].contains(v),
+            Formula::And(a, b) => {
+                self.forces_propositional(w, a) && self.forces_propositional(w, b)
+            }
+            Formula::Or(a, b) => self.forces_propositional(w, a) || self.forces_propositional(w, b),
+            Formula::Not(a) => self.accessible[w]
+                .iter()
+                .all(|v| !self.forces_propositional(*v, a)),
+            Formula::Implies(a, b) | Formula::RLImplies(b, a) => self.accessible[w]
+                .iter()
+                .all(|v| !self.forces_propositional(*v, a) || self.forces_propositional(*v, b)),
+            Formula::Equiv(a, b) => self.accessible[w]
+                .iter()
+                .all(|v| self.forces_propositional(*v, a) == self.forces_propositional(*v, b)),
+            Formula::Predicate(_, _)
+            | Formula::Equal(_, _)
+            | Formula::Forall(_, _)
+            | Formula::Exists(_, _)
+            | Formula::Necessarily(_)
+            | Formula::Possibly(_) => unreachable!("{}", NotPropositional),
+        }
+    }
+}
+
+impl fmt::Display for KripkeModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let worlds: Vec<String> = (0..self.worlds()).map(|w| format!("w{}", w)).collect();
+        writeln!(f, "Worlds: {}", worlds.join(", "))?;
+        let pairs: Vec<String> = (0..self.worlds())
+            .flat_map(|w| {
+                self.accessible[w]
+                    .iter()
+                    .filter(move |v| **v != w)
+                    .map(move |v| format!("w{} ≤ w{}", w, v))
+            })
+            .collect();
+        if pairs.is_empty() {
+            writeln!(f, "Accessibility (reflexive): none other")?;
+        } else {
+            writeln!(f, "Accessibility (reflexive): {}", pairs.join(", "))?;
+        }
+        writeln!(f, "Forcing:")?;
+        for w in 0..self.worlds() {
+            if self.valuation[w].is_empty() {
+                writeln!(f, "  w{} forces no variable", w)?;
+            } else {
+                let vars: Vec<String> = self.valuation[w].iter().map(|v| v.to_string()).collect();
+                writeln!(f, "  w{} ⊩ {}", w, vars.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of the intuitionistic decision procedure.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    /// The formula is intuitionistically valid
+    Valid,
+    /// The root world `w0` of the model does not force the formula
+    Refuted(KripkeModel),
+}
+
+impl Formula {
+    /// Decides whether the formula is valid in propositional intuitionistic logic.
+    pub fn intuitionistic_validity(&self) -> Result<Verdict, NotPropositional> {
+        decide_sequent(&[], self)
+    }
+}
+
+/// Decides whether `goal` follows intuitionistically from `hypotheses`. When it does not, the
+/// root world `w0` of the returned model forces every hypothesis but not the goal.
+///
+/// The search follows the Kripke semantics: at each world the formulas to be forced and those
+/// not to be forced are decomposed, and a new world is created for each implication that
+/// must not be forced. Worlds only get created when they force strictly more formulas than
+/// their parent, so the search terminates.
+pub fn decide_sequent(
+    hypotheses: &[Formula],
+    goal: &Formula,
+) -> Result<Verdict, NotPropositional> {
+    if !goal.is_propositional() || !hypotheses.iter().all(Formula::is_propositional) {
+        return Err(NotPropositional);
+    }
+    let gamma = hypotheses.iter().map(canonical).collect();
+    let delta = BTreeSet::from([canonical(goal)]);
+    Ok(match refute(gamma, delta) {
+        None => Verdict::Valid,
+        Some(tree) => {
+            let mut valuation = Vec::new();
+            let mut edges = Vec::new();
+            flatten(&tree, &mut valuation, &mut edges);
+            Verdict::Refuted(KripkeModel::from_tree(valuation, &edges))
+        }
+    })
+}
+
+/// A world of the countermodel being built, with the formulas it forces.
+struct World {
+    forced: BTreeSet<Formula>,
+    successors: Vec<World>,
+}
+
+fn flatten(world: &World, valuation: &mut Vec<BTreeSet<char>>, edges: &mut Vec<(usize, usize)>) {
+    let id = valuation.len();
+    valuation.push(
+        world
+            .forced
+            .iter()
+            .filter_map(|f| match f {
+                Formula::Variable(v) => Some(*v),
+                _ => None,
+            })
+            .collect(),
+    );
+    for successor in world.successors.iter() {
+        edges.push((id, valuation.len()));
+        flatten(successor, valuation, edges);
+    }
+}
+
+/// Rewrites a formula with ⊥, variables, ∧, ∨ and ⇒ only.
+fn canonical(f: &Formula) -> Formula {
+    let implies = |a: Formula, b: Formula| Formula::Implies(Box::new(a), Box::new(b));
+    match f {
+        Formula::Top => implies(Formula::Bottom, Formula::Bottom),
+        Formula::Bottom => Formula::Bottom,
+        Formula::Variable(v) => Formula::Variable(*v),
+        Formula::Not(a) => implies(canonical(a), Formula::Bottom),
+        Formula::And(a, b) => Formula::And(Box::new(canonical(a)), Box::new(canonical(b))),
+        Formula::Or(a, b) => Formula::Or(Box::new(canonical(a)), Box::new(canonical(b))),
+        Formula::Implies(a, b) => implies(canonical(a), canonical(b)),
+        Formula::RLImplies(a, b) => implies(canonical(b), canonical(a)),
+        Formula::Equiv(a, b) => {
+            let (a, b) = (canonical(a), canonical(b));
+            Formula::And(
+                Box::new(implies(a.clone(), b.clone())),
+                Box::new(implies(b, a)),
+            )
+        }
+        Formula::Predicate(_, _)
+        | Formula::Equal(_, _)
+        | Formula::Forall(_, _)
+        | Formula::Exists(_, _)
+        | Formula::Necessarily(_)
+        | Formula::Possibly(_) => unreachable!("the formula was checked to be propositional"),
+    }
+}
+
+fn with(set: &BTreeSet<Formula>, formulas: &[&Formula]) -> BTreeSet<Formula> {
+    let mut set = set.clone();
+    set.extend(formulas.iter().map(|f| (*f).clone()));
+    set
+}
+
+/// Looks for a world forcing every formula of `gamma` and none of `delta`.
+fn refute(gamma: BTreeSet<Formula>, delta: BTreeSet<Formula>) -> Option<World> {
+    if gamma.contains(&Formula::Bottom) || gamma.intersection(&delta).next().is_some() {
+        return None;
+    }
+    for f in gamma.iter() {
+        match f {
+            Formula::And(a, b) if !gamma.contains(a) || !gamma.contains(b) => {
+                return refute(with(&gamma, &[a, b]), delta);
+            }
+            Formula::Or(a, b) if !gamma.contains(a) && !gamma.contains(b) => {
+                return refute(with(&gamma, &[a]), delta.clone())
+                    .or_else(|| refute(with(&gamma, &[b]), delta));
+            }
+            // The world either does not force a, or forces b.
+            Formula::Implies(a, b) if !delta.contains(a) && !gamma.contains(b) => {
+                return refute(gamma.clone(), with(&delta, &[a]))
+                    .or_else(|| refute(with(&gamma, &[b]), delta));
+            }
+            _ => (),
+        }
+    }
+    for f in delta.iter() {
+        match f {
+            Formula::And(a, b) if !delta.contains(a) && !delta.contains(b) => {
+                return refute(gamma.clone(), with(&delta, &[a]))
+                    .or_else(|| refute(gamma, with(&delta, &[b])));
+            }
+            Formula::Or(a, b) if !delta.contains(a) || !delta.contains(b) => {
+                return refute(gamma, with(&delta, &[a, b]));
+            }
+            // The world forces a, so it may only avoid forcing a⇒b by not forcing b.
+            Formula::Implies(a, b) if gamma.contains(a) && !delta.contains(b) => {
+                return refute(gamma, with(&delta, &[b]));
+            }
+            _ => (),
+        }
+    }
+    let mut successors = Vec::new();
+    for f in delta.iter() {
+        if let Formula::Implies(a, b) = f {
+            if !gamma.contains(a) {
+                successors.push(refute(with(&gamma, &[a]), BTreeSet::from([*b.clone()]))?);
+            }
+        }
+    }
+    Some(World {
+        forced: gamma,
+        successors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    fn assert_refuted(input: &str) -> KripkeModel {
+        let formula = f(input);
+        match formula.intuitionistic_validity().unwrap() {
+            Verdict::Valid => panic!("{} should not be valid", input),
+            Verdict::Refuted(model) => {
+                assert!(model.is_intuitionistic());
+                assert_eq!(model.forces(0, &formula), Ok(false));
+                model
+            }
+        }
+    }
+
+    #[test]
+    fn excluded_middle() {
+        let model = assert_refuted("A∨¬A");
+        assert_eq!(model.worlds(), 2);
+        assert!(model.valuation(0).is_empty());
+        assert_eq!(model.valuation(1), &BTreeSet::from(['A']));
+        assert!(model.accessible(0).contains(&1));
+    }
+
+    #[test]
+    fn classical_tautologies_refuted() {
+        assert_refuted("¬¬A⇒A");
+        assert_refuted("((A⇒B)⇒A)⇒A");
+        assert_refuted("(A⇒B)∨(B⇒A)");
+        assert_refuted("(A⇒B)⇒¬A∨B");
+        assert_refuted("¬(A∧B)⇒¬A∨¬B");
+    }
+
+    #[test]
+    fn intuitionistic_theorems() {
+        for input in [
+            "A⇒¬¬A",
+            "¬¬(A∨¬A)",
+            "(A⇒B)⇒¬B⇒¬A",
+            "¬¬¬A⇔¬A",
+            "¬(A∨B)⇔¬A∧¬B",
+            "A∨B⇒(A⇒C)⇒((B⇒C)⇒C)",
+            "⊤",
+            "⊥⇒A",
+            "(A⇐B)⇔(B⇒A)",
+        ] {
+            assert_eq!(f(input).intuitionistic_validity(), Ok(Verdict::Valid), "{}", input);
+        }
+    }
+
+    #[test]
+    fn sequents() {
+        assert_eq!(decide_sequent(&[f("A"), f("A⇒B")], &f("B")), Ok(Verdict::Valid));
+        match decide_sequent(&[f("¬¬A")], &f("A")).unwrap() {
+            Verdict::Valid => panic!("¬¬A does not entail A"),
+            Verdict::Refuted(model) => {
+                assert_eq!(model.forces(0, &f("¬¬A")), Ok(true));
+                assert_eq!(model.forces(0, &f("A")), Ok(false));
+            }
+        }
+        assert_eq!(decide_sequent(&[], &f("∀xP(x)")), Err(NotPropositional));
+        let model = KripkeModel::new(vec![BTreeSet::new()], vec![BTreeSet::from([0])]);
+        assert_eq!(model.forces(0, &f("∀xP(x)")), Err(NotPropositional));
+        assert_eq!(model.forces(0, &f("□A")), Err(NotPropositional));
+    }
+
+    #[test]
+    fn display() {
+        let model = assert_refuted("A∨¬A");
+        assert_eq!(
+            model.to_string(),
+            "Worlds: w0, w1\nAccessibility (reflexive): w0 ≤ w1\nForcing:\n  w0 forces no variable\n  w1 ⊩ A\n"
+        );
+    }
+}
@@ -0,0 +1,930 @@
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::formula::{Formula, TokenizationError};
+use crate::justif::Jusitification;
+use crate::proof::{CheckUpResult, Proof};
+use crate::record::{Record, Statement};
+
+/// A term of the simply typed λ-calculus with pairs, sums and `abort`, whose types are
+/// formulas: by the Curry–Howard correspondence, a term of type A is a proof of A.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lambda {
+    Variable(String),
+    /// `λx:A.t`, proving A⇒B (or ¬A) when t is of type B (or ⊥)
+    Abstraction(String, Formula, Box<Lambda>),
+    /// `t u`, t being of type A⇒B (or ¬A) and u of type A
+    Application(Box<Lambda>, Box<Lambda>),
+    /// `(t, u)`, proving A∧B (or A⇔B from both implications)
+    Pair(Box<Lambda>, Box<Lambda>),
+    /// `fst t`
+    First(Box<Lambda>),
+    /// `snd t`
+    Second(Box<Lambda>),
+    /// `inl t`, proving A∨B from A
+    Left(Box<Lambda>),
+    /// `inr t`, proving A∨B from B
+    Right(Box<Lambda>),
+    /// `case t of inl x ⇒ u | inr y ⇒ v`
+    Case {
+        sum: Box<Lambda>,
+        left: (String, Box<Lambda>),
+        right: (String, Box<Lambda>),
+    },
+    /// `abort t`, proving any formula from t of type ⊥
+    Abort(Box<Lambda>),
+    /// `()`, proving ⊤
+    Unit,
+    /// `(t : A)`, telling the type of a term whose type cannot be inferred
+    Ascription(Box<Lambda>, Formula),
+}
+
+#[derive(Error, Debug)]
+pub enum ReadError {
+    /// Something else was expected
+    #[error("Expected {expected} at character {pos}")]
+    Expected { expected: &'static str, pos: usize },
+    /// The term is followed by other characters
+    #[error("Unexpected characters after the term at character {0}")]
+    TooMuch(usize),
+    /// A type is not a formula
+    #[error("Invalid formula at character {0}: {1}")]
+    InvalidFormula(usize, TokenizationError),
+}
+
+#[derive(Error, Debug)]
+pub enum TypeError {
+    /// The variable is neither bound by a λ or a case, nor in the context
+    #[error("{0} is not bound")]
+    Unbound(String),
+    /// The term builds a proof of another connective
+    #[error("{term} cannot have the type {expected}")]
+    NotOfType {
+        term: Box<Lambda>,
+        expected: Formula,
+    },
+    /// The type of the term is not the one expected
+    #[error("{term} has the type {found}, not {expected}")]
+    Mismatch {
+        term: Box<Lambda>,
+        expected: Formula,
+        found: Box<Formula>,
+    },
+    /// The term is applied, projected or split, but its type does not allow it
+    #[error("{term} has the type {found}, which is not {expected}")]
+    NotA {
+        term: Box<Lambda>,
+        found: Formula,
+        expected: &'static str,
+    },
+    /// The term has many types, one of which has to be given
+    #[error("The type of {0} cannot be inferred, write it as ({0} : A)")]
+    CannotInfer(Box<Lambda>),
+}
+
+#[derive(Error, Debug)]
+pub enum ExtractionError {
+    /// There is no record to translate
+    #[error("The proof is empty")]
+    Empty,
+    /// Only a valid proof may be translated
+    #[error("The proof is not valid, the first error being at record {0}")]
+    NotValid(usize),
+    /// The proof is not intuitionistic
+    #[error("Record {0} uses Raa, classical proofs have no λ-term")]
+    Classical(usize),
+    /// The rule has no counterpart among the terms
+    #[error("Record {0} uses {1}, which has no λ-term")]
+    Unsupported(usize, String),
+}
+
+const KEYWORDS: [&str; 7] = ["case", "of", "inl", "inr", "fst", "snd", "abort"];
+
+impl Lambda {
+    /// Reads a term, such as `λx:a∧b.(snd x, fst x)`. `\` may be written for `λ` and `=>` for
+    /// `⇒`. Application is written by juxtaposition and associates to the left, the body of a
+    /// λ and the last branch of a case extending as far right as possible.
+    pub fn read(input: &str) -> Result<Self, ReadError> {
+        let mut reader = Reader {
+            chars: input.chars().collect(),
+            pos: 0,
+        };
+        let term = reader.term()?;
+        reader.skip_whitespace();
+        if reader.pos < reader.chars.len() {
+            return Err(ReadError::TooMuch(reader.pos));
+        }
+        Ok(term)
+    }
+
+    /// Type-checks the term against `formula`, its free variables having the types of
+    /// `context`, and writes the natural-deduction proof it stands for: the hypotheses of the
+    /// context are assumed in `Supposons` boxes left open, and the last record states
+    /// `formula`.
+    pub fn proof(
+        &self,
+        context: &[(String, Formula)],
+        formula: &Formula,
+    ) -> Result<Proof, TypeError> {
+        let mut writer = Writer::default();
+        for (name, hyp) in context {
+            writer.ctxt.push(writer.records.len());
+            let id = writer.push(Statement::Supposons(hyp.clone()), Jusitification::Hyp);
+            writer.env.push((name.clone(), id, hyp.clone()));
+        }
+        let id = writer.check(self, formula)?;
+        writer.conclude(id, context.len());
+        Ok(Proof::from_records(writer.records))
+    }
+
+    /// Tells whether the type of the term is inferred from the types of its variables.
+    fn synthesizes(&self) -> bool {
+        match self {
+            Lambda::Variable(_)
+            | Lambda::Application(_, _)
+            | Lambda::First(_)
+            | Lambda::Second(_)
+            | Lambda::Unit
+            | Lambda::Ascription(_, _) => true,
+            Lambda::Abstraction(_, _, body) => body.synthesizes(),
+            Lambda::Pair(t, u) => t.synthesizes() && u.synthesizes(),
+            Lambda::Left(_) | Lambda::Right(_) | Lambda::Case { .. } | Lambda::Abort(_) => false,
+        }
+    }
+
+    /// Tells whether the term is printed without parentheses as an argument.
+    fn is_atomic(&self) -> bool {
+        matches!(
+            self,
+            Lambda::Variable(_) | Lambda::Pair(_, _) | Lambda::Unit | Lambda::Ascription(_, _)
+        )
+    }
+
+    /// Tells whether the term extends as far right as possible.
+    fn is_open(&self) -> bool {
+        matches!(self, Lambda::Abstraction(_, _, _) | Lambda::Case { .. })
+    }
+}
+
+/// Prints `term` as an argument, between parentheses unless it is atomic.
+struct Atom<'a>(&'a Lambda);
+
+impl fmt::Display for Atom<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_atomic() {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "({})", self.0)
+        }
+    }
+}
+
+/// Prints `term` between parentheses if it extends as far right as possible.
+struct Closed<'a>(&'a Lambda);
+
+impl fmt::Display for Closed<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_open() {
+            write!(f, "({})", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl fmt::Display for Lambda {
+    /// Prints the term in the syntax accepted by [`Lambda::read`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lambda::Variable(x) => write!(f, "{}", x),
+            Lambda::Abstraction(x, a, body) => write!(f, "λ{}:{}.{}", x, a, body),
+            Lambda::Application(t, u) => write!(f, "{} {}", Closed(t), Atom(u)),
+            Lambda::Pair(t, u) => write!(f, "({}, {})", t, u),
+            Lambda::First(t) => write!(f, "fst {}", Atom(t)),
+            Lambda::Second(t) => write!(f, "snd {}", Atom(t)),
+            Lambda::Left(t) => write!(f, "inl {}", Atom(t)),
+            Lambda::Right(t) => write!(f, "inr {}", Atom(t)),
+            Lambda::Case { sum, left, right } => write!(
+                f,
+                "case {} of inl {} ⇒ {} | inr {} ⇒ {}",
+                Closed(sum),
+                left.0,
+                Closed(&left.1),
+                right.0,
+                right.1
+            ),
+            Lambda::Abort(t) => write!(f, "abort {}", Atom(t)),
+            Lambda::Unit => write!(f, "()"),
+            Lambda::Ascription(t, a) => write!(f, "({} : {})", t, a),
+        }
+    }
+}
+
+/// Reads a term character by character.
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Consumes `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let len = token.chars().count();
+        let found = self.chars.len() >= self.pos + len
+            && self.chars[self.pos..self.pos + len]
+                .iter()
+                .copied()
+                .eq(token.chars());
+        if found {
+            self.pos += len;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str, expected: &'static str) -> Result<(), ReadError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(ReadError::Expected {
+                expected,
+                pos: self.pos,
+            })
+        }
+    }
+
+    /// Returns the identifier coming next, without consuming it.
+    fn peek_identifier(&mut self) -> Option<String> {
+        self.skip_whitespace();
+        let start = self
+            .chars
+            .get(self.pos)
+            .filter(|c| c.is_ascii_alphabetic())?;
+        let mut name = start.to_string();
+        name.extend(
+            self.chars[self.pos + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == '_' || **c == '\''),
+        );
+        Some(name)
+    }
+
+    /// Consumes the keyword `keyword` if it comes next.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_identifier().as_deref() == Some(keyword) {
+            self.pos += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reads a variable, which is not a keyword.
+    fn variable(&mut self) -> Result<String, ReadError> {
+        match self.peek_identifier() {
+            Some(name) if !KEYWORDS.contains(&name.as_str()) => {
+                self.pos += name.chars().count();
+                Ok(name)
+            }
+            _ => Err(ReadError::Expected {
+                expected: "a variable",
+                pos: self.pos,
+            }),
+        }
+    }
+
+    /// Reads a formula up to `end`, not included, out of parentheses.
+    fn formula(&mut self, end: char) -> Result<Formula, ReadError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(c) = self.chars.get(self.pos) {
+            match c {
+                '(' => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                c if *c == end && depth == 0 => break,
+                _ => (),
+            }
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        Formula::read(text.trim()).map_err(|e| ReadError::InvalidFormula(start, e))
+    }
+
+    fn term(&mut self) -> Result<Lambda, ReadError> {
+        if self.eat("λ") || self.eat("\\") {
+            let x = self.variable()?;
+            self.expect(":", "':'")?;
+            let a = self.formula('.')?;
+            self.expect(".", "'.'")?;
+            Ok(Lambda::Abstraction(x, a, Box::new(self.term()?)))
+        } else if self.keyword("case") {
+            let sum = self.application()?;
+            if !self.keyword("of") {
+                return Err(ReadError::Expected {
+                    expected: "of",
+                    pos: self.pos,
+                });
+            }
+            let left = self.branch("inl")?;
+            self.expect("|", "'|'")?;
+            let right = self.branch("inr")?;
+            Ok(Lambda::Case {
+                sum: Box::new(sum),
+                left,
+                right,
+            })
+        } else {
+            self.application()
+        }
+    }
+
+    /// Reads `inl x ⇒ t` or `inr x ⇒ t`.
+    fn branch(&mut self, injection: &'static str) -> Result<(String, Box<Lambda>), ReadError> {
+        if !self.keyword(injection) {
+            return Err(ReadError::Expected {
+                expected: injection,
+                pos: self.pos,
+            });
+        }
+        let x = self.variable()?;
+        if !self.eat("⇒") && !self.eat("=>") {
+            return Err(ReadError::Expected {
+                expected: "'⇒'",
+                pos: self.pos,
+            });
+        }
+        Ok((x, Box::new(self.term()?)))
+    }
+
+    fn application(&mut self) -> Result<Lambda, ReadError> {
+        let mut term = self.prefixed()?;
+        while self.peek() == Some('(')
+            || self
+                .peek_identifier()
+                .is_some_and(|name| !KEYWORDS.contains(&name.as_str()))
+        {
+            term = Lambda::Application(Box::new(term), Box::new(self.atom()?));
+        }
+        Ok(term)
+    }
+
+    /// Reads an atom, possibly after `fst`, `snd`, `inl`, `inr` or `abort`.
+    fn prefixed(&mut self) -> Result<Lambda, ReadError> {
+        let name = self.peek_identifier().unwrap_or_default();
+        let constructor = match name.as_str() {
+            "fst" => Lambda::First,
+            "snd" => Lambda::Second,
+            "inl" => Lambda::Left,
+            "inr" => Lambda::Right,
+            "abort" => Lambda::Abort,
+            _ => return self.atom(),
+        };
+        self.pos += name.len();
+        Ok(constructor(Box::new(self.atom()?)))
+    }
+
+    fn atom(&mut self) -> Result<Lambda, ReadError> {
+        if !self.eat("(") {
+            return self.variable().map(Lambda::Variable);
+        }
+        if self.eat(")") {
+            return Ok(Lambda::Unit);
+        }
+        let term = self.term()?;
+        let term = if self.eat(",") {
+            Lambda::Pair(Box::new(term), Box::new(self.term()?))
+        } else if self.eat(":") {
+            Lambda::Ascription(Box::new(term), self.formula(')')?)
+        } else {
+            term
+        };
+        self.expect(")", "')'")?;
+        Ok(term)
+    }
+}
+
+/// Tells whether two formulas are the same type, `¬A` being `A⇒⊥`, `A⇔B` being
+/// `(A⇒B)∧(B⇒A)` and `⊤` being `⊥⇒⊥`, as for `Rwrt`.
+fn same(a: &Formula, b: &Formula) -> bool {
+    a.clone().normalize() == b.clone().normalize()
+}
+
+/// Unfolds `⇐` and `⇔` at the root of the formula.
+fn expose(formula: &Formula) -> Formula {
+    match formula {
+        Formula::RLImplies(a, b) => Formula::Implies(b.clone(), a.clone()),
+        Formula::Equiv(a, b) => Formula::And(
+            Box::new(Formula::Implies(a.clone(), b.clone())),
+            Box::new(Formula::Implies(b.clone(), a.clone())),
+        ),
+        f => f.clone(),
+    }
+}
+
+/// Writes the proof a term stands for while type-checking it.
+#[derive(Default)]
+struct Writer {
+    records: Vec<Record>,
+    /// The open boxes
+    ctxt: Vec<usize>,
+    /// The bound variables, with the record of their hypothesis and their type
+    env: Vec<(String, usize, Formula)>,
+}
+
+impl Writer {
+    fn push(&mut self, stmt: Statement, justif: Jusitification) -> usize {
+        let id = self.records.len();
+        self.records.push(Record {
+            id,
+            ctxt: self.ctxt.clone(),
+            stmt,
+            justif,
+        });
+        id
+    }
+
+    fn derive(&mut self, formula: Formula, justif: Jusitification) -> usize {
+        self.push(Statement::Simple(formula), justif)
+    }
+
+    /// States `formula`, the same type as the formula of `id`, with `Rwrt` if it is another one.
+    fn restate(&mut self, id: usize, formula: &Formula) -> usize {
+        if self.records[id].stmt.get_formula() == formula {
+            id
+        } else {
+            self.derive(formula.clone(), Jusitification::Rwrt(id))
+        }
+    }
+
+    /// Makes `id` the last record, written at `since` or later.
+    fn conclude(&mut self, id: usize, since: usize) {
+        if id + 1 != self.records.len() || id < since {
+            let formula = self.records[id].stmt.get_formula().clone();
+            self.derive(formula, Jusitification::Rwrt(id));
+        }
+    }
+
+    /// Opens a box assuming `hyp`, bound to `x`, in which `body` is written by `write`, then
+    /// closes it by `Donc formula`.
+    fn assume<F>(
+        &mut self,
+        x: &str,
+        hyp: &Formula,
+        formula: Formula,
+        justif: Jusitification,
+        write: F,
+    ) -> Result<usize, TypeError>
+    where
+        F: FnOnce(&mut Self) -> Result<usize, TypeError>,
+    {
+        self.ctxt.push(self.records.len());
+        let h = self.push(Statement::Supposons(hyp.clone()), Jusitification::Hyp);
+        self.env.push((x.to_string(), h, hyp.clone()));
+        let id = write(self)?;
+        self.conclude(id, h + 1);
+        self.env.pop();
+        self.ctxt.pop();
+        Ok(self.push(Statement::Donc(formula), justif))
+    }
+
+    /// Checks that `term` has the type `formula`, returning the record stating `formula`.
+    fn check(&mut self, term: &Lambda, formula: &Formula) -> Result<usize, TypeError> {
+        let shape = expose(formula);
+        let id = match (term, &shape) {
+            (Lambda::Abstraction(x, a, body), Formula::Implies(h, c)) if same(a, h) => self
+                .assume(x, h, shape.clone(), Jusitification::IImpl, |w| {
+                    w.check(body, c)
+                })?,
+            (Lambda::Abstraction(x, a, body), Formula::Not(h)) if same(a, h) => {
+                self.assume(x, h, shape.clone(), Jusitification::INot, |w| {
+                    w.check(body, &Formula::Bottom)
+                })?
+            }
+            (Lambda::Pair(t, u), Formula::And(a, b)) => {
+                let left = self.check(t, a)?;
+                let right = self.check(u, b)?;
+                self.derive(shape, Jusitification::IAnd { left, right })
+            }
+            (Lambda::Left(t), Formula::Or(a, b)) => {
+                let i = self.check(t, a)?;
+                self.derive(shape.clone(), Jusitification::IOrR(i, *b.clone()))
+            }
+            (Lambda::Right(t), Formula::Or(a, b)) => {
+                let i = self.check(t, b)?;
+                self.derive(shape.clone(), Jusitification::IOrL(i, *a.clone()))
+            }
+            (Lambda::Abort(t), _) => {
+                let i = self.check(t, &Formula::Bottom)?;
+                self.derive(shape, Jusitification::Efq(i))
+            }
+            (Lambda::Unit, Formula::Top) => self.derive(shape, Jusitification::ITop),
+            (Lambda::Case { sum, left, right }, _) => {
+                let (i, found) = self.synthesize(sum)?;
+                let Formula::Or(a, b) = expose(&found) else {
+                    return Err(TypeError::NotA {
+                        term: sum.clone(),
+                        found,
+                        expected: "a disjunction",
+                    });
+                };
+                let a_or_b = self.restate(i, &Formula::Or(a.clone(), b.clone()));
+                let a_to_c = Formula::Implies(a.clone(), Box::new(shape.clone()));
+                let a_to_c = self.assume(&left.0, &a, a_to_c, Jusitification::IImpl, |w| {
+                    w.check(&left.1, &shape)
+                })?;
+                let b_to_c = Formula::Implies(b.clone(), Box::new(shape.clone()));
+                let b_to_c = self.assume(&right.0, &b, b_to_c, Jusitification::IImpl, |w| {
+                    w.check(&right.1, &shape)
+                })?;
+                self.derive(
+                    shape,
+                    Jusitification::EOr {
+                        a_to_c,
+                        b_to_c,
+                        a_or_b,
+                    },
+                )
+            }
+            (
+                Lambda::Abstraction(_, _, _)
+                | Lambda::Pair(_, _)
+                | Lambda::Left(_)
+                | Lambda::Right(_)
+                | Lambda::Unit,
+                _,
+            ) => {
+                return Err(TypeError::NotOfType {
+                    term: Box::new(term.clone()),
+                    expected: formula.clone(),
+                })
+            }
+            _ => {
+                let (i, found) = self.synthesize(term)?;
+                if !same(&found, formula) {
+                    return Err(TypeError::Mismatch {
+                        term: Box::new(term.clone()),
+                        expected: formula.clone(),
+                        found: Box::new(found),
+                    });
+                }
+                i
+            }
+        };
+        Ok(self.restate(id, formula))
+    }
+
+    /// Infers the type of `term`, returning the record stating it and the type.
+    fn synthesize(&mut self, term: &Lambda) -> Result<(usize, Formula), TypeError> {
+        match term {
+            Lambda::Variable(x) => self
+                .env
+                .iter()
+                .rev()
+                .find(|(name, _, _)| name == x)
+                .map(|(_, id, formula)| (*id, formula.clone()))
+                .ok_or_else(|| TypeError::Unbound(x.clone())),
+            Lambda::Ascription(t, a) => Ok((self.check(t, a)?, a.clone())),
+            Lambda::Application(t, u) => {
+                let (i, found) = self.synthesize(t)?;
+                match expose(&found) {
+                    Formula::Implies(a, b) => {
+                        let implication = self.restate(i, &Formula::Implies(a.clone(), b.clone()));
+                        let hyp = self.check(u, &a)?;
+                        let id =
+                            self.derive(*b.clone(), Jusitification::EImpl { hyp, implication });
+                        Ok((id, *b))
+                    }
+                    Formula::Not(a) => {
+                        let hyp = self.check(u, &a)?;
+                        let id =
+                            self.derive(Formula::Bottom, Jusitification::ENot { hyp, negation: i });
+                        Ok((id, Formula::Bottom))
+                    }
+                    _ => Err(TypeError::NotA {
+                        term: t.clone(),
+                        found,
+                        expected: "an implication",
+                    }),
+                }
+            }
+            Lambda::First(t) | Lambda::Second(t) => {
+                let (i, found) = self.synthesize(t)?;
+                let Formula::And(a, b) = expose(&found) else {
+                    return Err(TypeError::NotA {
+                        term: t.clone(),
+                        found,
+                        expected: "a conjunction",
+                    });
+                };
+                let i = self.restate(i, &Formula::And(a.clone(), b.clone()));
+                Ok(match term {
+                    Lambda::First(_) => (self.derive(*a.clone(), Jusitification::EAndL(i)), *a),
+                    _ => (self.derive(*b.clone(), Jusitification::EAndR(i)), *b),
+                })
+            }
+            Lambda::Abstraction(x, a, body) if body.synthesizes() => {
+                let mut found = Formula::Bottom;
+                let id = self.assume(x, a, Formula::Bottom, Jusitification::IImpl, |w| {
+                    let (i, b) = w.synthesize(body)?;
+                    found = b;
+                    Ok(i)
+                })?;
+                let formula = Formula::Implies(Box::new(a.clone()), Box::new(found));
+                self.records[id].stmt = Statement::Donc(formula.clone());
+                Ok((id, formula))
+            }
+            Lambda::Pair(t, u) if term.synthesizes() => {
+                let (left, a) = self.synthesize(t)?;
+                let (right, b) = self.synthesize(u)?;
+                let formula = Formula::And(Box::new(a), Box::new(b));
+                Ok((
+                    self.derive(formula.clone(), Jusitification::IAnd { left, right }),
+                    formula,
+                ))
+            }
+            Lambda::Unit => Ok((
+                self.derive(Formula::Top, Jusitification::ITop),
+                Formula::Top,
+            )),
+            _ => Err(TypeError::CannotInfer(Box::new(term.clone()))),
+        }
+    }
+}
+
+/// A λ-term with the types of its free variables, the open hypotheses of the proof it comes
+/// from.
+pub struct Extraction {
+    pub context: Vec<(String, Formula)>,
+    pub term: Lambda,
+    pub formula: Formula,
+}
+
+impl fmt::Display for Extraction {
+    /// Prints the typing judgement, as in `x0 : a ⊢ inl x0 : a∨b`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let context: Vec<String> = self
+            .context
+            .iter()
+            .map(|(x, a)| format!("{} : {}", x, a))
+            .collect();
+        if !context.is_empty() {
+            write!(f, "{} ", context.join(", "))?;
+        }
+        write!(f, "⊢ {} : {}", self.term, self.formula)
+    }
+}
+
+impl Proof {
+    /// Translates the proof into the λ-term of its last record: the hypothesis or the premise
+    /// of the record `i` is the variable `xi`, and the premises and the open hypotheses are
+    /// free.
+    ///
+    /// The proof has to be valid and intuitionistic, using the propositional rules only.
+    pub fn lambda_term(&self) -> Result<Extraction, ExtractionError> {
+        let mut checked = self.with_records(self.records().to_vec());
+        let Some(last) = self.records().last() else {
+            return Err(ExtractionError::Empty);
+        };
+        checked.check();
+        if let CheckUpResult::SemanticErrors { first_error, .. } = checked.state() {
+            return Err(ExtractionError::NotValid(*first_error));
+        }
+        let premises = self
+            .records()
+            .iter()
+            .filter(|rec| matches!(rec.stmt, Statement::Premise(_)))
+            .map(|rec| rec.id);
+        let context = premises
+            .chain(last.ctxt.iter().copied())
+            .map(|h| (variable(h), self.records()[h].stmt.get_formula().clone()))
+            .collect();
+        Ok(Extraction {
+            context,
+            term: extract(self.records(), last.id)?,
+            formula: last.stmt.get_formula().clone(),
+        })
+    }
+}
+
+fn variable(hyp: usize) -> String {
+    format!("x{}", hyp)
+}
+
+/// Returns the term of the record `id`, with its type if it cannot be inferred.
+fn synthesized(records: &[Record], id: usize) -> Result<Lambda, ExtractionError> {
+    let term = extract(records, id)?;
+    Ok(if term.synthesizes() {
+        term
+    } else {
+        Lambda::Ascription(Box::new(term), records[id].stmt.get_formula().clone())
+    })
+}
+
+/// Returns the branch of a case from the term of the implication `id`, applied to `x` unless
+/// it is a λ.
+fn branch(
+    records: &[Record],
+    id: usize,
+    x: String,
+) -> Result<(String, Box<Lambda>), ExtractionError> {
+    Ok(match synthesized(records, id)? {
+        Lambda::Abstraction(y, _, body) => (y, body),
+        term => {
+            let applied =
+                Lambda::Application(Box::new(term), Box::new(Lambda::Variable(x.clone())));
+            (x, Box::new(applied))
+        }
+    })
+}
+
+fn extract(records: &[Record], id: usize) -> Result<Lambda, ExtractionError> {
+    let rec = &records[id];
+    let boxed = |t: Lambda| Box::new(t);
+    Ok(match (&rec.stmt, &rec.justif) {
+        (Statement::Supposons(_) | Statement::Premise(_), Jusitification::Hyp) => {
+            Lambda::Variable(variable(id))
+        }
+        (Statement::Donc(_), Jusitification::IImpl | Jusitification::INot) => {
+            let hyp = *records[id - 1]
+                .ctxt
+                .last()
+                .ok_or(ExtractionError::NotValid(id))?;
+            Lambda::Abstraction(
+                variable(hyp),
+                records[hyp].stmt.get_formula().clone(),
+                boxed(extract(records, id - 1)?),
+            )
+        }
+        (_, Jusitification::IAnd { left, right })
+        | (
+            _,
+            Jusitification::IEquiv {
+                forward: left,
+                backward: right,
+            },
+        ) => Lambda::Pair(
+            boxed(extract(records, *left)?),
+            boxed(extract(records, *right)?),
+        ),
+        (_, Jusitification::EAndL(i) | Jusitification::EEquivL(i)) => {
+            Lambda::First(boxed(synthesized(records, *i)?))
+        }
+        (_, Jusitification::EAndR(i) | Jusitification::EEquivR(i)) => {
+            Lambda::Second(boxed(synthesized(records, *i)?))
+        }
+        (_, Jusitification::IOrR(i, _)) => Lambda::Left(boxed(extract(records, *i)?)),
+        (_, Jusitification::IOrL(i, _)) => Lambda::Right(boxed(extract(records, *i)?)),
+        (
+            _,
+            Jusitification::EOr {
+                a_to_c,
+                b_to_c,
+                a_or_b,
+            },
+        ) => Lambda::Case {
+            sum: boxed(synthesized(records, *a_or_b)?),
+            left: branch(records, *a_to_c, format!("y{}", id))?,
+            right: branch(records, *b_to_c, format!("z{}", id))?,
+        },
+        (_, Jusitification::EImpl { hyp, implication })
+        | (
+            _,
+            Jusitification::ENot {
+                hyp,
+                negation: implication,
+            },
+        ) => Lambda::Application(
+            boxed(synthesized(records, *implication)?),
+            boxed(extract(records, *hyp)?),
+        ),
+        (_, Jusitification::Efq(i)) => Lambda::Abort(boxed(extract(records, *i)?)),
+        (_, Jusitification::ITop) => Lambda::Unit,
+        (_, Jusitification::Rwrt(i)) => extract(records, *i)?,
+        (_, Jusitification::Raa(_)) => return Err(ExtractionError::Classical(id)),
+        (_, justif) => return Err(ExtractionError::Unsupported(id, justif.name().to_string())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formula(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    fn valid(proof: &Proof) -> bool {
+        let mut proof = proof.with_records(proof.records().to_vec());
+        proof.check();
+        matches!(proof.state(), CheckUpResult::Valid)
+    }
+
+    #[test]
+    fn extraction() {
+        let input = "0;0;Supposons a∧b;Hyp\n\
+                     1;0;b;EAndR 0\n\
+                     2;0;a;EAndL 0\n\
+                     3;0;b∧a;IAnd 1 2\n\
+                     4;;Donc a∧b⇒b∧a;IImpl\n";
+        let proof = Proof::read_proof(input).unwrap();
+        let extraction = proof.lambda_term().unwrap();
+        assert_eq!(
+            extraction.to_string(),
+            "⊢ λx0:a∧b.(snd x0, fst x0) : a∧b⇒b∧a"
+        );
+        // The proof of the term is the one it comes from.
+        let back = extraction.term.proof(&[], &extraction.formula).unwrap();
+        assert_eq!(back.to_string(), proof.to_string());
+
+        let open = "0;0;Supposons a;Hyp\n1;0;a∨b;IOrR 0 b\n";
+        let extraction = Proof::read_proof(open).unwrap().lambda_term().unwrap();
+        assert_eq!(extraction.to_string(), "x0 : a ⊢ inl x0 : a∨b");
+
+        let classical = "0;0;Supposons ¬¬a;Hyp\n1;0;a;Raa 0\n";
+        assert!(matches!(
+            Proof::read_proof(classical).unwrap().lambda_term(),
+            Err(ExtractionError::Classical(1))
+        ));
+    }
+
+    #[test]
+    fn type_checking() {
+        let cases = [
+            ("λx:a∨b.case x of inl y ⇒ inr y | inr z ⇒ inl z", "a∨b⇒b∨a"),
+            ("λx:a.λy:¬a.abort (y x)", "a⇒¬a⇒b"),
+            ("λf:a⇒b.λg:b⇒c.λx:a.g (f x)", "(a⇒b)⇒(b⇒c)⇒a⇒c"),
+            ("(λx:a.x, λx:a.x)", "a⇔a"),
+            ("λx:¬a.(inl x : ¬a∨a)", "¬a⇒¬a∨a"),
+            ("λx:a.()", "a⇒⊤"),
+        ];
+        for (term, type_) in cases {
+            let term = Lambda::read(term).unwrap();
+            assert_eq!(Lambda::read(&term.to_string()).unwrap(), term);
+            let proof = term.proof(&[], &formula(type_)).unwrap();
+            assert!(valid(&proof), "{}", proof);
+            let extraction = proof.lambda_term().unwrap();
+            assert!(extraction.term.proof(&[], &formula(type_)).is_ok());
+        }
+        let context = [("p".to_string(), formula("a∧b"))];
+        let proof = Lambda::read("fst p")
+            .unwrap()
+            .proof(&context, &formula("a"))
+            .unwrap();
+        assert!(valid(&proof));
+
+        let error = |term: &str, type_: &str| {
+            Lambda::read(term)
+                .unwrap()
+                .proof(&[], &formula(type_))
+                .err()
+        };
+        assert!(matches!(
+            error("λx:a.x", "b⇒b"),
+            Some(TypeError::NotOfType { .. })
+        ));
+        assert!(matches!(
+            error("λx:a.y", "a⇒a"),
+            Some(TypeError::Unbound(_))
+        ));
+        assert!(matches!(
+            error("λx:a∧b.fst x", "a∧b⇒b"),
+            Some(TypeError::Mismatch { .. })
+        ));
+        assert!(matches!(
+            error("λx:a.x x", "a⇒a"),
+            Some(TypeError::NotA { .. })
+        ));
+        assert!(matches!(
+            error("λx:a.fst (inl x)", "a⇒a"),
+            Some(TypeError::CannotInfer(_))
+        ));
+    }
+
+    #[test]
+    fn reading() {
+        assert!(matches!(
+            Lambda::read("λx.x"),
+            Err(ReadError::Expected { pos: 2, .. })
+        ));
+        assert!(matches!(Lambda::read("x y)"), Err(ReadError::TooMuch(3))));
+        assert!(matches!(
+            Lambda::read("λx:a∧.x"),
+            Err(ReadError::InvalidFormula(3, _))
+        ));
+        let term = Lambda::read("\\x:a.case x of inl y => (f y) z | inr y => y").unwrap();
+        assert_eq!(term.to_string(), "λx:a.case x of inl y ⇒ f y z | inr y ⇒ y");
+    }
+}
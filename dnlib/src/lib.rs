@@ -1,4 +1,27 @@
-mod formula;
-mod justif;
+pub mod dependency;
+pub mod derived;
+pub mod diff;
+pub mod edit;
+pub mod formula;
+pub mod hint;
+pub mod hilbert;
+pub mod justif;
+pub mod kripke;
+pub mod lint;
+pub mod lambda;
+pub mod minimize;
+pub mod modal;
+pub mod normal;
 pub mod proof;
-mod record;
+pub mod prover;
+pub mod record;
+pub mod resolution;
+pub mod rule;
+pub mod ruleset;
+pub mod sequent;
+pub mod simplify;
+pub mod stats;
+pub mod subst;
+pub mod tableau;
+pub mod term;
+pub mod theorem;
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::formula::Formula;
+use crate::justif::Jusitification;
+use crate::kripke::{decide_sequent, Verdict};
+use crate::proof::Proof;
+use crate::record::Statement;
+
+/// A kind of warning, named by a stable code such as `unused-record`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    /// A record the conclusion does not depend on
+    UnusedRecord,
+    /// A hypothesis the end of its box does not depend on
+    UnusedHypothesis,
+    /// A `Rwrt` giving the formula it cites, which could be cited instead
+    TrivialRwrt,
+    /// A `Supposons` never closed by a `Donc`
+    UndischargedHypothesis,
+    /// A proof ending inside a box
+    OpenContext,
+    /// A formula already proved by a record that could be cited instead
+    DuplicateFormula,
+    /// A `Raa` concluding a formula with an intuitionistic proof
+    AvoidableRaa,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedRecord,
+        Lint::UnusedHypothesis,
+        Lint::TrivialRwrt,
+        Lint::UndischargedHypothesis,
+        Lint::OpenContext,
+        Lint::DuplicateFormula,
+        Lint::AvoidableRaa,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::UnusedRecord => "unused-record",
+            Lint::UnusedHypothesis => "unused-hypothesis",
+            Lint::TrivialRwrt => "trivial-rwrt",
+            Lint::UndischargedHypothesis => "undischarged-hypothesis",
+            Lint::OpenContext => "open-context",
+            Lint::DuplicateFormula => "duplicate-formula",
+            Lint::AvoidableRaa => "avoidable-raa",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lint| lint.code() == code)
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// How a warning is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// The warning is not reported
+    Allow,
+    /// The warning is reported
+    Warn,
+    /// The warning is reported as an error
+    Deny,
+}
+
+impl Level {
+    pub fn read(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LintError {
+    /// The code names no lint
+    #[error("{0} is not a lint, the lints are unused-record, unused-hypothesis, trivial-rwrt, undischarged-hypothesis, open-context, duplicate-formula and avoidable-raa")]
+    UnknownLint(String),
+}
+
+/// The level of each lint, every lint being warned about by default.
+#[derive(Clone, Debug)]
+pub struct LintLevels {
+    levels: BTreeMap<Lint, Level>,
+}
+
+impl Default for LintLevels {
+    fn default() -> Self {
+        Self {
+            levels: Lint::ALL
+                .into_iter()
+                .map(|lint| (lint, Level::Warn))
+                .collect(),
+        }
+    }
+}
+
+impl LintLevels {
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels[&lint]
+    }
+
+    pub fn set(&mut self, lint: Lint, level: Level) {
+        self.levels.insert(lint, level);
+    }
+
+    /// Sets the level of the lint named `code`.
+    pub fn set_code(&mut self, code: &str, level: Level) -> Result<(), LintError> {
+        let lint = Lint::from_code(code).ok_or_else(|| LintError::UnknownLint(code.to_string()))?;
+        self.set(lint, level);
+        Ok(())
+    }
+
+    /// Follows the directives of a proof file, comment lines such as
+    /// `(* allow: unused-record, trivial-rwrt *)`, `(* warn: … *)` or `(* deny: … *)`. Other
+    /// comments are ignored.
+    pub fn read_directives(&mut self, input: &str) -> Result<(), LintError> {
+        for line in input.split('\n') {
+            let Some(comment) = line.trim().strip_prefix("(*") else {
+                continue;
+            };
+            let comment = comment.trim_end().trim_end_matches("*)");
+            let Some((level, codes)) = comment.split_once(':') else {
+                continue;
+            };
+            let Some(level) = Level::read(level.trim()) else {
+                continue;
+            };
+            for code in codes.split(',') {
+                self.set_code(code.trim(), level)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A warning about a record of a proof.
+#[derive(Clone, Debug)]
+pub struct Warning {
+    pub lint: Lint,
+    pub level: Level,
+    pub record: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    /// Prints the warning, as in "warning[trivial-rwrt] at record 3: …".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.level {
+            Level::Deny => "error",
+            _ => "warning",
+        };
+        write!(
+            f,
+            "{}[{}] at record {}: {}",
+            level, self.lint, self.record, self.message
+        )
+    }
+}
+
+impl Proof {
+    /// Looks for the flaws of the proof that are not errors, reporting those whose lint is not
+    /// allowed by `levels`, in the order of the records.
+    pub fn lint(&self, levels: &LintLevels) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        let mut warn = |lint: Lint, record: usize, message: String| {
+            let level = levels.level(lint);
+            if level != Level::Allow {
+                warnings.push(Warning {
+                    lint,
+                    level,
+                    record,
+                    message,
+                });
+            }
+        };
+        let records = self.records();
+        let Some(last) = records.last() else {
+            return Vec::new();
+        };
+        let dead = self.dead_records();
+        for id in dead.steps {
+            warn(
+                Lint::UnusedRecord,
+                id,
+                "The conclusion does not depend on this record".to_string(),
+            );
+        }
+        for id in dead.hypotheses {
+            warn(
+                Lint::UnusedHypothesis,
+                id,
+                "The hypothesis is not used before the end of its box".to_string(),
+            );
+        }
+        for hyp in &last.ctxt {
+            if matches!(
+                records.get(*hyp).map(|r| &r.stmt),
+                Some(Statement::Supposons(_))
+            ) {
+                warn(
+                    Lint::UndischargedHypothesis,
+                    *hyp,
+                    "The hypothesis is never discharged by a Donc".to_string(),
+                );
+            }
+        }
+        if !last.ctxt.is_empty() {
+            let boxes: Vec<String> = last.ctxt.iter().map(|b| b.to_string()).collect();
+            warn(
+                Lint::OpenContext,
+                last.id,
+                format!(
+                    "The proof ends inside the box(es) opened at {}",
+                    boxes.join(", ")
+                ),
+            );
+        }
+        for (position, rec) in records.iter().enumerate() {
+            match (&rec.stmt, &rec.justif) {
+                (Statement::Simple(formula), Jusitification::Rwrt(orig)) => {
+                    // A copy may be needed to end a box or the proof.
+                    let needed = rec.id == last.id
+                        || matches!(
+                            records.get(rec.id + 1).map(|r| &r.stmt),
+                            Some(Statement::Donc(_))
+                        );
+                    if !needed
+                        && records
+                            .get(*orig)
+                            .is_some_and(|o| o.stmt.get_formula() == formula)
+                    {
+                        warn(
+                            Lint::TrivialRwrt,
+                            rec.id,
+                            format!("Rwrt copies record {} without changing it, which could be cited instead", orig),
+                        );
+                    }
+                }
+                (Statement::Simple(formula) | Statement::Donc(formula), justif) => {
+                    let earlier = records[..position].iter().find(|other| {
+                        matches!(
+                            other.stmt,
+                            Statement::Supposons(_) | Statement::Simple(_) | Statement::Donc(_)
+                        ) && other.stmt.get_formula() == formula
+                            && self.ctxt_compatible(&rec.ctxt, &other.ctxt)
+                    });
+                    if let Some(earlier) = earlier {
+                        warn(
+                            Lint::DuplicateFormula,
+                            rec.id,
+                            format!("{} was already proved at record {}", formula, earlier.id),
+                        );
+                    }
+                    if matches!(justif, Jusitification::Raa(_))
+                        && self.intuitionistic(&rec.ctxt, formula)
+                    {
+                        warn(
+                            Lint::AvoidableRaa,
+                            rec.id,
+                            format!("{} has an intuitionistic proof, Raa is not needed", formula),
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+        warnings.sort_by_key(|warning| warning.record);
+        warnings
+    }
+
+    /// Tells whether `formula` intuitionistically follows from the hypotheses of the boxes
+    /// `ctxt`, all of them being propositional.
+    fn intuitionistic(&self, ctxt: &[usize], formula: &Formula) -> bool {
+        let mut hypotheses = Vec::new();
+        for hyp in ctxt {
+            match self.records().get(*hyp).map(|r| &r.stmt) {
+                Some(Statement::Supposons(f)) => hypotheses.push(f.clone()),
+                _ => return false,
+            }
+        }
+        matches!(decide_sequent(&hypotheses, formula), Ok(Verdict::Valid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(input: &str, levels: &LintLevels) -> Vec<(usize, &'static str)> {
+        let proof = Proof::read_proof(input).unwrap();
+        proof
+            .lint(levels)
+            .iter()
+            .map(|warning| (warning.record, warning.lint.code()))
+            .collect()
+    }
+
+    #[test]
+    fn lints() {
+        let input = "0;0;Supposons a;Hyp\n\
+                     1;0,1;Supposons b;Hyp\n\
+                     2;0,1;a;Rwrt 0\n\
+                     3;0;Donc b⇒a;IImpl\n\
+                     4;0;a;Rwrt 0\n\
+                     5;0;a∧a;IAnd 0 4\n\
+                     6;0;a∧a;IAnd 0 0\n";
+        assert_eq!(
+            codes(input, &LintLevels::default()),
+            vec![
+                (0, "undischarged-hypothesis"),
+                (1, "unused-record"),
+                (1, "unused-hypothesis"),
+                (2, "unused-record"),
+                (3, "unused-record"),
+                (4, "unused-record"),
+                (4, "trivial-rwrt"),
+                (5, "unused-record"),
+                (6, "open-context"),
+                (6, "duplicate-formula"),
+            ]
+        );
+        let mut levels = LintLevels::default();
+        levels
+            .read_directives("(* a comment *)\n(* allow: unused-record, open-context *)\n(* deny: trivial-rwrt *)")
+            .unwrap();
+        let proof = Proof::read_proof(&format!("(* allow: unused-record *)\n{}", input)).unwrap();
+        let warnings = proof.lint(&levels);
+        assert_eq!(warnings.len(), 4);
+        assert_eq!(warnings[2].to_string(), "error[trivial-rwrt] at record 4: Rwrt copies record 0 without changing it, which could be cited instead");
+        assert!(matches!(
+            levels.read_directives("(* deny: unused *)"),
+            Err(LintError::UnknownLint(_))
+        ));
+    }
+
+    #[test]
+    fn avoidable_raa() {
+        let input = "0;0;Supposons ¬¬(a∧b);Hyp\n\
+                     1;0;a∧b;Raa 0\n\
+                     2;;Donc ¬¬(a∧b)⇒a∧b;IImpl\n";
+        assert!(codes(input, &LintLevels::default()).is_empty());
+        let input = "0;0;Supposons a;Hyp\n\
+                     1;0,1;Supposons ¬a;Hyp\n\
+                     2;0,1;⊥;ENot 0 1\n\
+                     3;0;Donc ¬¬a;INot\n\
+                     4;0;a;Raa 3\n\
+                     5;;Donc a⇒a;IImpl\n";
+        assert_eq!(
+            codes(input, &LintLevels::default()),
+            vec![(4, "duplicate-formula"), (4, "avoidable-raa")]
+        );
+    }
+}
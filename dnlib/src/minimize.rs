@@ -0,0 +1,816 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::formula::{Formula, NotPropositional};
+
+/// Maximal number of variables of a truth table. Finding a minimal cover is exponential, and
+/// some tables of 8 variables already take minutes.
+pub const MAX_VARIABLES: usize = 7;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum TruthTableError {
+    /// There are too many variables to build the truth table
+    #[error("There are too many variables ({0}), at most {MAX_VARIABLES} are supported")]
+    TooManyVariables(usize),
+    /// A variable is given twice
+    #[error("Variable {0} is given twice")]
+    DuplicateVariable(char),
+    /// A variable name is invalid
+    #[error("'{0}' is not a valid variable name")]
+    InvalidVariable(char),
+    /// The number of values does not match the number of variables
+    #[error("{expected} values were expected, {found} were given")]
+    WrongNumberOfValues { expected: usize, found: usize },
+    /// A value is neither 0 nor 1
+    #[error("'{0}' is not a truth value, use 0 or 1")]
+    InvalidValue(char),
+    /// The formula has quantifiers, predicates or equalities
+    #[error("The formula is not propositional")]
+    NotPropositional,
+}
+
+/// The truth table of a Boolean function.
+///
+/// Rows are in the usual order: the first variable is the most significant bit of the row
+/// number, so the first row sets every variable to false and the last one sets them to true.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TruthTable {
+    variables: Vec<char>,
+    values: Vec<bool>,
+}
+
+impl TruthTable {
+    /// Builds a truth table from its variables and its column of values.
+    pub fn new(variables: Vec<char>, values: Vec<bool>) -> Result<Self, TruthTableError> {
+        if variables.len() > MAX_VARIABLES {
+            return Err(TruthTableError::TooManyVariables(variables.len()));
+        }
+        for (i, v) in variables.iter().enumerate() {
+            if !v.is_ascii_alphabetic() {
+                return Err(TruthTableError::InvalidVariable(*v));
+            }
+            if variables[..i].contains(v) {
+                return Err(TruthTableError::DuplicateVariable(*v));
+            }
+        }
+        let expected = 1 << variables.len();
+        if values.len() != expected {
+            return Err(TruthTableError::WrongNumberOfValues {
+                expected,
+                found: values.len(),
+            });
+        }
+        Ok(Self { variables, values })
+    }
+
+    /// Reads a truth table from its variables, such as `abc`, and its values, such as
+    /// `01101001`.
+    pub fn read(variables: &str, values: &str) -> Result<Self, TruthTableError> {
+        let values = values
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(TruthTableError::InvalidValue(c)),
+            })
+            .collect::<Result<Vec<bool>, TruthTableError>>()?;
+        Self::new(variables.chars().collect(), values)
+    }
+
+    pub fn variables(&self) -> &[char] {
+        &self.variables
+    }
+
+    pub fn values(&self) -> &[bool] {
+        &self.values
+    }
+
+    /// Value of the variable at position `var` in row `row`.
+    pub fn variable_value(&self, row: usize, var: usize) -> bool {
+        row & (1 << (self.variables.len() - 1 - var)) != 0
+    }
+
+    /// Computes a minimal disjunctive normal form with the Quine–McCluskey method.
+    pub fn minimal_dnf(&self) -> Minimization {
+        self.minimize(NormalForm::Dnf)
+    }
+
+    /// Computes a minimal conjunctive normal form with the Quine–McCluskey method, applied to
+    /// the negation of the function.
+    pub fn minimal_cnf(&self) -> Minimization {
+        self.minimize(NormalForm::Cnf)
+    }
+
+    fn minimize(&self, form: NormalForm) -> Minimization {
+        let n = self.variables.len();
+        let wanted = form == NormalForm::Dnf;
+        let terms: Vec<usize> = (0..self.values.len())
+            .filter(|row| self.values[*row] == wanted)
+            .collect();
+        let prime_implicants = prime_implicants(n, &terms);
+        let (essential, cover) = minimum_cover(n, &terms, &prime_implicants);
+        let result = match form {
+            NormalForm::Dnf => fold(
+                cover.iter().map(|i| i.conjunction(&self.variables)),
+                Formula::Or,
+                Formula::Bottom,
+            ),
+            NormalForm::Cnf => fold(
+                cover.iter().map(|i| i.clause(&self.variables)),
+                Formula::And,
+                Formula::Top,
+            ),
+        };
+        Minimization {
+            form,
+            variables: self.variables.clone(),
+            terms,
+            prime_implicants,
+            essential,
+            cover,
+            result,
+        }
+    }
+
+    /// Lays the table out as a Karnaugh map, if it has between one and four variables.
+    pub fn karnaugh(&self) -> Option<KarnaughMap> {
+        let n = self.variables.len();
+        if n == 0 || n > 4 {
+            return None;
+        }
+        let row_bits = n / 2;
+        let column_bits = n - row_bits;
+        let cells = gray_code(row_bits)
+            .into_iter()
+            .map(|r| {
+                gray_code(column_bits)
+                    .into_iter()
+                    .map(|c| self.values[(r << column_bits) | c])
+                    .collect()
+            })
+            .collect();
+        Some(KarnaughMap {
+            row_variables: self.variables[..row_bits].to_vec(),
+            column_variables: self.variables[row_bits..].to_vec(),
+            cells,
+        })
+    }
+}
+
+impl fmt::Display for TruthTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for v in self.variables.iter() {
+            write!(f, "{} ", v)?;
+        }
+        writeln!(f, "|")?;
+        for (row, value) in self.values.iter().enumerate() {
+            for var in 0..self.variables.len() {
+                write!(f, "{} ", self.variable_value(row, var) as u8)?;
+            }
+            writeln!(f, "| {}", *value as u8)?;
+        }
+        Ok(())
+    }
+}
+
+impl Formula {
+    /// Computes the truth table of the formula, over its variables in alphabetical order.
+    pub fn truth_table(&self) -> Result<TruthTable, TruthTableError> {
+        if !self.is_propositional() {
+            return Err(TruthTableError::NotPropositional);
+        }
+        let variables: Vec<char> = self.variables().into_iter().collect();
+        if variables.len() > MAX_VARIABLES {
+            return Err(TruthTableError::TooManyVariables(variables.len()));
+        }
+        let n = variables.len();
+        let values = (0..1usize << n)
+            .map(|row| {
+                self.eval(&|v| {
+                    let var = variables.iter().position(|w| *w == v).unwrap();
+                    row & (1 << (n - 1 - var)) != 0
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|NotPropositional| TruthTableError::NotPropositional)?;
+        TruthTable::new(variables, values)
+    }
+
+    /// Computes a minimal disjunctive normal form equivalent to the formula.
+    pub fn minimal_dnf(&self) -> Result<Minimization, TruthTableError> {
+        Ok(self.truth_table()?.minimal_dnf())
+    }
+
+    /// Computes a minimal conjunctive normal form equivalent to the formula.
+    pub fn minimal_cnf(&self) -> Result<Minimization, TruthTableError> {
+        Ok(self.truth_table()?.minimal_cnf())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalForm {
+    /// Disjunction of conjunctions of literals
+    Dnf,
+    /// Conjunction of disjunctions of literals
+    Cnf,
+}
+
+/// A product term of the Quine–McCluskey method: the rows matching `value` on every bit that
+/// is not set in `free`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Implicant {
+    pub value: usize,
+    pub free: usize,
+}
+
+impl Implicant {
+    pub fn covers(&self, row: usize) -> bool {
+        row & !self.free == self.value
+    }
+
+    /// Number of variables fixed by the implicant.
+    pub fn literal_count(&self, n: usize) -> usize {
+        n - self.free.count_ones() as usize
+    }
+
+    /// Prints the implicant the usual way, such as `1-0`.
+    pub fn pattern(&self, n: usize) -> String {
+        (0..n)
+            .map(|var| {
+                let bit = 1 << (n - 1 - var);
+                if self.free & bit != 0 {
+                    '-'
+                } else if self.value & bit != 0 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+
+    fn literals(&self, variables: &[char]) -> Vec<(char, bool)> {
+        let n = variables.len();
+        variables
+            .iter()
+            .enumerate()
+            .filter(|(var, _)| self.free & (1 << (n - 1 - var)) == 0)
+            .map(|(var, v)| (*v, self.value & (1 << (n - 1 - var)) != 0))
+            .collect()
+    }
+
+    /// The conjunction of literals true exactly on the rows covered by the implicant.
+    pub fn conjunction(&self, variables: &[char]) -> Formula {
+        fold(
+            self.literals(variables)
+                .into_iter()
+                .map(|(v, positive)| literal(v, positive)),
+            Formula::And,
+            Formula::Top,
+        )
+    }
+
+    /// The disjunction of literals false exactly on the rows covered by the implicant.
+    pub fn clause(&self, variables: &[char]) -> Formula {
+        fold(
+            self.literals(variables)
+                .into_iter()
+                .map(|(v, positive)| literal(v, !positive)),
+            Formula::Or,
+            Formula::Bottom,
+        )
+    }
+}
+
+fn literal(v: char, positive: bool) -> Formula {
+    if positive {
+        Formula::Variable(v)
+    } else {
+        Formula::Not(Box::new(Formula::Variable(v)))
+    }
+}
+
+/// Combines formulas with a left-associative connective, or returns `empty` if there are none.
+fn fold<I: Iterator<Item = Formula>>(
+    formulas: I,
+    connective: fn(Box<Formula>, Box<Formula>) -> Formula,
+    empty: Formula,
+) -> Formula {
+    formulas
+        .reduce(|acc, f| connective(Box::new(acc), Box::new(f)))
+        .unwrap_or(empty)
+}
+
+/// Gray code over `bits` bits, in the order used by Karnaugh maps.
+fn gray_code(bits: usize) -> Vec<usize> {
+    (0..1 << bits).map(|i| i ^ (i >> 1)).collect()
+}
+
+/// Computes every prime implicant covering only rows of `terms`, by repeatedly merging
+/// implicants that differ on a single variable. As in the tabular method, an implicant is only
+/// compared with those of the next group: same free variables, and one more variable set,
+/// which are looked up rather than scanned.
+fn prime_implicants(n: usize, terms: &[usize]) -> Vec<Implicant> {
+    let mut current: BTreeSet<Implicant> = terms
+        .iter()
+        .map(|t| Implicant { value: *t, free: 0 })
+        .collect();
+    let mut primes = Vec::new();
+    while !current.is_empty() {
+        let mut next = BTreeSet::new();
+        let mut merged = BTreeSet::new();
+        for a in current.iter() {
+            let unset = (0..n)
+                .map(|i| 1 << i)
+                .filter(|bit| (a.value | a.free) & bit == 0);
+            for bit in unset {
+                let b = Implicant {
+                    value: a.value | bit,
+                    free: a.free,
+                };
+                if current.contains(&b) {
+                    next.insert(Implicant {
+                        value: a.value,
+                        free: a.free | bit,
+                    });
+                    merged.insert(*a);
+                    merged.insert(b);
+                }
+            }
+        }
+        primes.extend(current.difference(&merged).copied());
+        current = next;
+    }
+    primes.sort();
+    primes
+}
+
+/// Chooses the essential prime implicants, then completes them into a cover of `terms` with
+/// as few implicants as possible, and then as few literals as possible.
+fn minimum_cover(
+    n: usize,
+    terms: &[usize],
+    primes: &[Implicant],
+) -> (Vec<Implicant>, Vec<Implicant>) {
+    let mut essential: Vec<Implicant> = Vec::new();
+    for t in terms.iter() {
+        let mut covering = primes.iter().filter(|p| p.covers(*t));
+        if let (Some(p), None) = (covering.next(), covering.next()) {
+            if !essential.contains(p) {
+                essential.push(*p);
+            }
+        }
+    }
+    essential.sort();
+    let uncovered: Vec<usize> = terms
+        .iter()
+        .copied()
+        .filter(|t| !essential.iter().any(|p| p.covers(*t)))
+        .collect();
+    let candidates: Vec<Implicant> = primes
+        .iter()
+        .copied()
+        .filter(|p| !essential.contains(p))
+        .collect();
+    let covering = Covering::new(n, &uncovered, candidates);
+    let mut best = None;
+    covering.search(
+        covering.all_rows(),
+        (0..covering.candidates.len()).collect(),
+        &mut Vec::new(),
+        &mut best,
+    );
+    let mut cover = essential.clone();
+    cover.extend(
+        best.map(|(chosen, _)| chosen)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| covering.candidates[p]),
+    );
+    cover.sort();
+    (essential, cover)
+}
+
+/// A set of rows to cover, one bit per row.
+type Rows = Vec<u64>;
+
+fn is_subset(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x & !y == 0)
+}
+
+fn intersection(a: &[u64], b: &[u64]) -> Rows {
+    a.iter().zip(b).map(|(x, y)| x & y).collect()
+}
+
+fn is_empty(rows: &[u64]) -> bool {
+    rows.iter().all(|x| *x == 0)
+}
+
+/// Tells whether the sorted list `a` is included in the sorted list `b`.
+fn is_sublist(a: &[usize], b: &[usize]) -> bool {
+    let mut b = b.iter();
+    a.iter().all(|x| b.any(|y| y == x))
+}
+
+/// The cost of a set of implicants: their number, then their number of literals.
+type Cost = (usize, usize);
+
+/// The rows left to cover once the essential prime implicants are chosen, and the candidates
+/// able to cover them.
+struct Covering {
+    rows: usize,
+    candidates: Vec<Implicant>,
+    /// The rows each candidate covers
+    covered: Vec<Rows>,
+    literals: Vec<usize>,
+}
+
+impl Covering {
+    fn new(n: usize, uncovered: &[usize], candidates: Vec<Implicant>) -> Self {
+        let covered = candidates
+            .iter()
+            .map(|p| {
+                let mut rows = vec![0; uncovered.len().div_ceil(64)];
+                for (r, t) in uncovered.iter().enumerate() {
+                    if p.covers(*t) {
+                        rows[r / 64] |= 1 << (r % 64);
+                    }
+                }
+                rows
+            })
+            .collect();
+        let literals = candidates.iter().map(|p| p.literal_count(n)).collect();
+        Self {
+            rows: uncovered.len(),
+            candidates,
+            covered,
+            literals,
+        }
+    }
+
+    fn all_rows(&self) -> Rows {
+        let mut rows = vec![0; self.rows.div_ceil(64)];
+        (0..self.rows).for_each(|r| rows[r / 64] |= 1 << (r % 64));
+        rows
+    }
+
+    /// The candidates of `available` covering each row of `rows`.
+    fn covering(&self, rows: &[u64], available: &[usize]) -> Vec<Vec<usize>> {
+        (0..self.rows)
+            .filter(|r| rows[r / 64] >> (r % 64) & 1 == 1)
+            .map(|r| {
+                available
+                    .iter()
+                    .copied()
+                    .filter(|p| self.covered[*p][r / 64] >> (r % 64) & 1 == 1)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Leaves out the candidates covering no more rows than another one with no fewer
+    /// literals.
+    fn dominant(&self, rows: &[u64], available: &[usize]) -> Vec<usize> {
+        let useful: Vec<(usize, Rows)> = available
+            .iter()
+            .map(|p| (*p, intersection(&self.covered[*p], rows)))
+            .filter(|(_, covered)| !is_empty(covered))
+            .collect();
+        let dominated = |i: usize| {
+            let (p, covered) = &useful[i];
+            useful.iter().enumerate().any(|(j, (q, other))| {
+                j != i
+                    && self.literals[*q] <= self.literals[*p]
+                    && is_subset(covered, other)
+                    && (self.literals[*q] < self.literals[*p]
+                        || !is_subset(other, covered)
+                        || j < i)
+            })
+        };
+        (0..useful.len())
+            .filter(|i| !dominated(*i))
+            .map(|i| useful[i].0)
+            .collect()
+    }
+
+    /// Branch and bound search of the cheapest set of candidates of `available` covering
+    /// `rows`. The candidates every cover has to use are chosen first, and a row covered by
+    /// all the candidates covering another row is left out, before branching on the row with
+    /// the fewest candidates.
+    fn search(
+        &self,
+        mut rows: Rows,
+        mut available: Vec<usize>,
+        chosen: &mut Vec<usize>,
+        best: &mut Option<(Vec<usize>, Cost)>,
+    ) {
+        let depth = chosen.len();
+        let covering = loop {
+            available = self.dominant(&rows, &available);
+            let mut covering = self.covering(&rows, &available);
+            if covering.iter().any(|c| c.is_empty()) {
+                chosen.truncate(depth);
+                return;
+            }
+            if let Some(c) = covering.iter().find(|c| c.len() == 1) {
+                let p = c[0];
+                chosen.push(p);
+                rows.iter_mut()
+                    .zip(&self.covered[p])
+                    .for_each(|(r, c)| *r &= !c);
+                continue;
+            }
+            covering.sort_by_key(|c| c.len());
+            let mut kept: Vec<Vec<usize>> = Vec::new();
+            for c in covering {
+                if !kept.iter().any(|k| is_sublist(k, &c)) {
+                    kept.push(c);
+                }
+            }
+            break kept;
+        };
+        let cost: Cost = (chosen.len(), chosen.iter().map(|p| self.literals[*p]).sum());
+        if covering.is_empty() {
+            if best.as_ref().is_none_or(|(_, best_cost)| cost < *best_cost) {
+                *best = Some((chosen.clone(), cost));
+            }
+            chosen.truncate(depth);
+            return;
+        }
+        // Rows no two of which have a candidate in common each need a candidate of their own.
+        let mut used = vec![false; self.candidates.len()];
+        let mut bound = cost;
+        for c in &covering {
+            if c.iter().all(|p| !used[*p]) {
+                c.iter().for_each(|p| used[*p] = true);
+                bound.0 += 1;
+                bound.1 += c.iter().map(|p| self.literals[*p]).min().unwrap();
+            }
+        }
+        if best
+            .as_ref()
+            .is_some_and(|(_, best_cost)| bound >= *best_cost)
+        {
+            chosen.truncate(depth);
+            return;
+        }
+        let mut branches = covering[0].clone();
+        let count = |p: &usize| {
+            intersection(&self.covered[*p], &rows)
+                .iter()
+                .map(|x| x.count_ones())
+                .sum::<u32>()
+        };
+        branches.sort_by_key(|p| (std::cmp::Reverse(count(p)), self.literals[*p]));
+        for p in branches {
+            let rest = rows
+                .iter()
+                .zip(&self.covered[p])
+                .map(|(r, c)| r & !c)
+                .collect();
+            available.retain(|q| *q != p);
+            chosen.push(p);
+            self.search(rest, available.clone(), chosen, best);
+            chosen.pop();
+        }
+        chosen.truncate(depth);
+    }
+}
+
+/// The explained result of a Quine–McCluskey minimization.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Minimization {
+    pub form: NormalForm,
+    pub variables: Vec<char>,
+    /// Rows where the function is true (DNF) or false (CNF)
+    pub terms: Vec<usize>,
+    pub prime_implicants: Vec<Implicant>,
+    pub essential: Vec<Implicant>,
+    /// Implicants kept in the minimal form, essential ones included
+    pub cover: Vec<Implicant>,
+    pub result: Formula,
+}
+
+impl Minimization {
+    fn describe(&self, implicant: &Implicant) -> String {
+        let n = self.variables.len();
+        let f = match self.form {
+            NormalForm::Dnf => implicant.conjunction(&self.variables),
+            NormalForm::Cnf => implicant.clause(&self.variables),
+        };
+        format!("{} ({})", implicant.pattern(n), f)
+    }
+
+    fn describe_all(&self, implicants: &[Implicant]) -> String {
+        if implicants.is_empty() {
+            "none".to_string()
+        } else {
+            implicants
+                .iter()
+                .map(|i| self.describe(i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+}
+
+impl fmt::Display for Minimization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (terms, form) = match self.form {
+            NormalForm::Dnf => ("Minterms (rows where the formula is true)", "Minimal DNF"),
+            NormalForm::Cnf => ("Maxterms (rows where the formula is false)", "Minimal CNF"),
+        };
+        let variables: String = self.variables.iter().collect();
+        writeln!(f, "Variables: {}", variables)?;
+        writeln!(
+            f,
+            "{}: {}",
+            terms,
+            self.terms
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        writeln!(
+            f,
+            "Prime implicants: {}",
+            self.describe_all(&self.prime_implicants)
+        )?;
+        writeln!(
+            f,
+            "Essential prime implicants: {}",
+            self.describe_all(&self.essential)
+        )?;
+        writeln!(f, "Chosen cover: {}", self.describe_all(&self.cover))?;
+        writeln!(f, "{}: {}", form, self.result)
+    }
+}
+
+/// A truth table of one to four variables laid out in Gray code order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KarnaughMap {
+    pub row_variables: Vec<char>,
+    pub column_variables: Vec<char>,
+    /// Values of the function, `cells[row][column]`
+    pub cells: Vec<Vec<bool>>,
+}
+
+impl fmt::Display for KarnaughMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row_bits = self.row_variables.len();
+        let column_bits = self.column_variables.len();
+        let label = format!(
+            "{}\\{}",
+            self.row_variables.iter().collect::<String>(),
+            self.column_variables.iter().collect::<String>()
+        );
+        let bits = |code: usize, width: usize| -> String {
+            (0..width)
+                .rev()
+                .map(|i| if code & (1 << i) != 0 { '1' } else { '0' })
+                .collect()
+        };
+        write!(f, "{}", label)?;
+        for c in gray_code(column_bits) {
+            write!(f, " {}", bits(c, column_bits))?;
+        }
+        writeln!(f)?;
+        for (r, row) in gray_code(row_bits).into_iter().zip(self.cells.iter()) {
+            write!(f, "{:>width$}", bits(r, row_bits), width = label.len())?;
+            for cell in row.iter() {
+                write!(f, " {:>width$}", *cell as u8, width = column_bits)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    fn equivalent(a: &Formula, b: &Formula) -> bool {
+        let vars: Vec<char> = a.variables().union(&b.variables()).copied().collect();
+        (0..1usize << vars.len()).all(|row| {
+            let valuation = |v: char| row & (1 << vars.iter().position(|w| *w == v).unwrap()) != 0;
+            a.eval(&valuation) == b.eval(&valuation)
+        })
+    }
+
+    #[test]
+    fn truth_table_order() {
+        let t = f("a∧¬b").truth_table().unwrap();
+        assert_eq!(t.variables(), &['a', 'b']);
+        assert_eq!(t.values(), &[false, false, true, false]);
+        assert_eq!(f("∀xP(x)").truth_table(), Err(TruthTableError::NotPropositional));
+    }
+
+    #[test]
+    fn read_errors() {
+        assert_eq!(
+            TruthTable::read("ab", "010"),
+            Err(TruthTableError::WrongNumberOfValues {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            TruthTable::read("aa", "0101"),
+            Err(TruthTableError::DuplicateVariable('a'))
+        );
+        assert_eq!(
+            TruthTable::read("ab", "01x1"),
+            Err(TruthTableError::InvalidValue('x'))
+        );
+    }
+
+    #[test]
+    fn minimal_dnf_of_majority() {
+        let m = TruthTable::read("abc", "00010111").unwrap().minimal_dnf();
+        assert_eq!(m.terms, vec![3, 5, 6, 7]);
+        assert_eq!(m.prime_implicants.len(), 3);
+        assert_eq!(m.essential.len(), 3);
+        assert_eq!(m.result, f("b∧c∨a∧c∨a∧b"));
+    }
+
+    #[test]
+    fn minimal_dnf_with_cyclic_cover() {
+        // Every prime implicant of this function is non-essential.
+        let m = TruthTable::read("abc", "01111110").unwrap().minimal_dnf();
+        assert!(m.essential.is_empty());
+        assert_eq!(m.prime_implicants.len(), 6);
+        assert_eq!(m.cover.len(), 3);
+        assert!(equivalent(&m.result, &f("¬(a⇔b)∨¬(b⇔c)")));
+    }
+
+    #[test]
+    fn minimal_cnf() {
+        let m = f("(a⇒b)∧(a∨b)").minimal_cnf().unwrap();
+        assert_eq!(m.result, f("b"));
+        let m = f("a⇔b").minimal_cnf().unwrap();
+        assert_eq!(m.result, f("(a∨¬b)∧(¬a∨b)"));
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(f("a∨¬a").minimal_dnf().unwrap().result, Formula::Top);
+        assert_eq!(f("a∧¬a").minimal_dnf().unwrap().result, Formula::Bottom);
+        assert_eq!(f("a∧¬a").minimal_cnf().unwrap().result, Formula::Bottom);
+        assert_eq!(f("⊤").minimal_cnf().unwrap().result, Formula::Top);
+    }
+
+    #[test]
+    fn minimization_preserves_truth() {
+        let g = f("(p⇒q)∧(q⇒r)⇒(p⇒r)∧¬(s∧p)");
+        assert!(equivalent(&g, &g.minimal_dnf().unwrap().result));
+        assert!(equivalent(&g, &g.minimal_cnf().unwrap().result));
+    }
+
+    #[test]
+    fn karnaugh_layout() {
+        let t = TruthTable::read("abcd", "0000000011111111").unwrap();
+        let k = t.karnaugh().unwrap();
+        assert_eq!(k.row_variables, vec!['a', 'b']);
+        assert_eq!(k.cells[2], vec![true; 4]);
+        assert_eq!(k.cells[3], vec![true; 4]);
+        assert_eq!(
+            k.to_string(),
+            "ab\\cd 00 01 11 10\n   00  0  0  0  0\n   01  0  0  0  0\n   11  1  1  1  1\n   10  1  1  1  1\n"
+        );
+        assert!(TruthTable::read("abcde", &"0".repeat(32))
+            .unwrap()
+            .karnaugh()
+            .is_none());
+    }
+
+    #[test]
+    fn largest_table() {
+        // Pseudo-random values, among the slowest to minimize with this many variables
+        let table = TruthTable::read(
+            "abcdefg",
+            "00111001001111111111111111110111111111011110111101101110011111111101111110111110111011111110111011110001111110111111110111011111",
+        )
+        .unwrap();
+        for minimization in [table.minimal_dnf(), table.minimal_cnf()] {
+            for row in 0..table.values().len() {
+                let valuation = |v: char| {
+                    let var = table.variables().iter().position(|w| *w == v).unwrap();
+                    table.variable_value(row, var)
+                };
+                assert_eq!(minimization.result.eval(&valuation), Ok(table.values()[row]));
+            }
+        }
+        assert_eq!(
+            TruthTable::read("abcdefgh", &"0".repeat(256)),
+            Err(TruthTableError::TooManyVariables(8))
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::formula::{Formula, NotPropositional};
+use crate::kripke::KripkeModel;
+
+/// Maximal number of bits (accessibility pairs and forced variables) the countermodel search
+/// enumerates for a single model size.
+const MAX_SEARCH_BITS: usize = 20;
+
+/// A normal modal logic, characterized by the conditions on the accessibility relation of its
+/// frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ModalSystem {
+    /// Any relation
+    #[default]
+    K,
+    /// Reflexive relations, □A⇒A
+    T,
+    /// Reflexive and transitive relations, □A⇒□□A
+    S4,
+    /// Equivalence relations, ◇A⇒□◇A
+    S5,
+}
+
+impl fmt::Display for ModalSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ModalSystem::K => "K",
+            ModalSystem::T => "T",
+            ModalSystem::S4 => "S4",
+            ModalSystem::S5 => "S5",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Tells whether `f` is built from variables, constants, connectives and modalities only.
+fn is_modal_propositional(f: &Formula) -> bool {
+    match f {
+        Formula::Top | Formula::Bottom | Formula::Variable(_) => true,
+        Formula::Not(a) | Formula::Necessarily(a) | Formula::Possibly(a) => {
+            is_modal_propositional(a)
+        }
+        Formula::Or(a, b)
+        | Formula::And(a, b)
+        | Formula::Implies(a, b)
+        | Formula::RLImplies(a, b)
+        | Formula::Equiv(a, b) => is_modal_propositional(a) && is_modal_propositional(b),
+        Formula::Predicate(_, _)
+        | Formula::Equal(_, _)
+        | Formula::Forall(_, _)
+        | Formula::Exists(_, _) => false,
+    }
+}
+
+impl KripkeModel {
+    /// Modal truth: tells whether `f` holds at world `w`, connectives being classical. The
+    /// formula may not have quantifiers, predicates or equalities.
+    pub fn satisfies(&self, w: usize, f: &Formula) -> Result<bool, NotPropositional> {
+        if !is_modal_propositional(f) {
+            return Err(NotPropositional);
+        }
+        Ok(self.holds(w, f))
+    }
+
+    /// Tells whether the formula `f`, without quantifiers, predicates or equalities, holds at
+    /// world `w`.
+    fn holds(&self, w: usize, f: &Formula) -> bool {
+        match f {
+            Formula::Top => true,
+            Formula::Bottom => false,
+            Formula::Variable(v) => self.valuation(w).contains(v),
+            Formula::Not(a) => !self.holds(w, a),
+            Formula::And(a, b) => self.holds(w, a) && self.holds(w, b),
+            Formula::Or(a, b) => self.holds(w, a) || self.holds(w, b),
+            Formula::Implies(a, b) | Formula::RLImplies(b, a) => {
+                !self.holds(w, a) || self.holds(w, b)
+            }
+            Formula::Equiv(a, b) => self.holds(w, a) == self.holds(w, b),
+            Formula::Necessarily(a) => self.accessible(w).iter().all(|v| self.holds(*v, a)),
+            Formula::Possibly(a) => self.accessible(w).iter().any(|v| self.holds(*v, a)),
+            Formula::Predicate(_, _)
+            | Formula::Equal(_, _)
+            | Formula::Forall(_, _)
+            | Formula::Exists(_, _) => unreachable!("{}", NotPropositional),
+        }
+    }
+
+    /// Tells whether the accessibility relation meets the frame conditions of `system`.
+    pub fn is_frame_of(&self, system: ModalSystem) -> bool {
+        let worlds = 0..self.worlds();
+        let reflexive = || worlds.clone().all(|w| self.accessible(w).contains(&w));
+        let transitive = || {
+            worlds.clone().all(|w| {
+                self.accessible(w)
+                    .iter()
+                    .all(|v| self.accessible(*v).is_subset(self.accessible(w)))
+            })
+        };
+        let symmetric = || {
+            worlds
+                .clone()
+                .all(|w| self.accessible(w).iter().all(|v| self.accessible(*v).contains(&w)))
+        };
+        match system {
+            ModalSystem::K => true,
+            ModalSystem::T => reflexive(),
+            ModalSystem::S4 => reflexive() && transitive(),
+            ModalSystem::S5 => reflexive() && transitive() && symmetric(),
+        }
+    }
+
+    /// Displays the model as a modal one: the relation is printed as is and worlds list the
+    /// variables true there.
+    pub fn display_modal(&self) -> ModalDisplay<'_> {
+        ModalDisplay(self)
+    }
+}
+
+/// Displays a [`KripkeModel`] seen as a model of modal logic, see
+/// [`KripkeModel::display_modal`].
+pub struct ModalDisplay<'a>(&'a KripkeModel);
+
+impl fmt::Display for ModalDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let model = self.0;
+        let worlds: Vec<String> = (0..model.worlds()).map(|w| format!("w{}", w)).collect();
+        writeln!(f, "Worlds: {}", worlds.join(", "))?;
+        let pairs: Vec<String> = (0..model.worlds())
+            .flat_map(|w| model.accessible(w).iter().map(move |v| format!("w{} → w{}", w, v)))
+            .collect();
+        if pairs.is_empty() {
+            writeln!(f, "Accessibility: none")?;
+        } else {
+            writeln!(f, "Accessibility: {}", pairs.join(", "))?;
+        }
+        writeln!(f, "Valuation:")?;
+        for w in 0..model.worlds() {
+            if model.valuation(w).is_empty() {
+                writeln!(f, "  w{}: no variable is true", w)?;
+            } else {
+                let vars: Vec<String> = model.valuation(w).iter().map(|v| v.to_string()).collect();
+                writeln!(f, "  w{}: {}", w, vars.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Formula {
+    /// Looks for a model of `system` with at most `max_worlds` worlds, whose world `w0` does not
+    /// satisfy the formula.
+    ///
+    /// The search enumerates every model, smallest first, so it is only meant for small
+    /// formulas: sizes whose enumeration would be too large are skipped. Finding no
+    /// countermodel does not prove the formula valid.
+    pub fn modal_countermodel(
+        &self,
+        system: ModalSystem,
+        max_worlds: usize,
+    ) -> Result<Option<KripkeModel>, NotPropositional> {
+        if !is_modal_propositional(self) {
+            return Err(NotPropositional);
+        }
+        let variables: Vec<char> = self.variables().into_iter().collect();
+        for n in 1..=max_worlds {
+            let relation_bits = n * n;
+            let valuation_bits = n * variables.len();
+            if relation_bits + valuation_bits > MAX_SEARCH_BITS {
+                break;
+            }
+            for relation in 0..1usize << relation_bits {
+                let accessible: Vec<BTreeSet<usize>> = (0..n)
+                    .map(|w| (0..n).filter(|v| relation & (1 << (w * n + v)) != 0).collect())
+                    .collect();
+                let frame = KripkeModel::new(vec![BTreeSet::new(); n], accessible.clone());
+                if !frame.is_frame_of(system) {
+                    continue;
+                }
+                for valuation in 0..1usize << valuation_bits {
+                    let valuation: Vec<BTreeSet<char>> = (0..n)
+                        .map(|w| {
+                            variables
+                                .iter()
+                                .enumerate()
+                                .filter(|(i, _)| valuation & (1 << (w * variables.len() + i)) != 0)
+                                .map(|(_, v)| *v)
+                                .collect()
+                        })
+                        .collect();
+                    let model = KripkeModel::new(valuation, accessible.clone());
+                    if !model.holds(0, self) {
+                        return Ok(Some(model));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    #[test]
+    fn read_and_display() {
+        assert_eq!(
+            f("□A⇒◇¬A"),
+            Formula::Implies(
+                Box::new(Formula::Necessarily(Box::new(Formula::Variable('A')))),
+                Box::new(Formula::Possibly(Box::new(Formula::Not(Box::new(
+                    Formula::Variable('A')
+                ))))),
+            )
+        );
+        for input in ["□(A⇒B)⇒□A⇒□B", "◇□A∨¬◇(A∧B)"] {
+            assert_eq!(f(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn truth_in_a_model() {
+        // w0 sees w1 and w2, A holds in w1 only.
+        let model = KripkeModel::new(
+            vec![BTreeSet::new(), BTreeSet::from(['A']), BTreeSet::new()],
+            vec![BTreeSet::from([1, 2]), BTreeSet::new(), BTreeSet::new()],
+        );
+        assert_eq!(model.satisfies(0, &f("◇A∧◇¬A")), Ok(true));
+        assert_eq!(model.satisfies(0, &f("□A")), Ok(false));
+        // w1 sees no world, everything is necessary there.
+        assert_eq!(model.satisfies(1, &f("□⊥")), Ok(true));
+        assert_eq!(model.satisfies(0, &f("□P(x)")), Err(NotPropositional));
+        assert!(model.is_frame_of(ModalSystem::K));
+        assert!(!model.is_frame_of(ModalSystem::T));
+    }
+
+    #[test]
+    fn characteristic_axioms() {
+        let axioms = [("□A⇒A", ModalSystem::T), ("□A⇒□□A", ModalSystem::S4), ("◇A⇒□◇A", ModalSystem::S5)];
+        let systems = [ModalSystem::K, ModalSystem::T, ModalSystem::S4, ModalSystem::S5];
+        for (axiom, weakest) in axioms {
+            for system in systems {
+                let countermodel = f(axiom).modal_countermodel(system, 3).unwrap();
+                let expected = systems.iter().position(|s| *s == system)
+                    < systems.iter().position(|s| *s == weakest);
+                assert_eq!(countermodel.is_some(), expected, "{} in {}", axiom, system);
+                if let Some(model) = countermodel {
+                    assert!(model.is_frame_of(system));
+                    assert_eq!(model.satisfies(0, &f(axiom)), Ok(false));
+                }
+            }
+        }
+        assert_eq!(f("□(A⇒B)⇒□A⇒□B").modal_countermodel(ModalSystem::K, 3), Ok(None));
+        assert_eq!(f("∀xP(x)").modal_countermodel(ModalSystem::K, 3), Err(NotPropositional));
+    }
+}
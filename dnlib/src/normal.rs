@@ -0,0 +1,461 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::formula::Formula;
+use crate::justif::Jusitification;
+use crate::proof::{CheckUpResult, Proof};
+use crate::prover::prune;
+use crate::record::{Record, Statement};
+use crate::term::Term;
+
+#[derive(Error, Debug)]
+pub enum NormalizationError {
+    /// There is no record to normalize
+    #[error("The proof is empty")]
+    Empty,
+    /// Only a valid proof may be normalized
+    #[error("The proof is not valid, the first error being at record {0}")]
+    NotValid(usize),
+}
+
+/// A connective introduced, then eliminated right away, as by `IAnd` followed by `EAndL`.
+#[derive(Clone, Debug)]
+pub struct Detour {
+    /// The rule introducing the connective
+    pub introduction: String,
+    /// The rule eliminating it
+    pub elimination: String,
+    /// The formula introduced, then eliminated
+    pub formula: Formula,
+}
+
+impl fmt::Display for Detour {
+    /// Describes the detour, as in "IAnd then EAndL on a∧b".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} then {} on {}",
+            self.introduction, self.elimination, self.formula
+        )
+    }
+}
+
+/// A proof in normal form, with the detours eliminated to get it.
+pub struct Normalization {
+    pub proof: Proof,
+    /// The detours eliminated, in order
+    pub detours: Vec<Detour>,
+    /// The detours left, with the record eliminating the connective: eliminating them would
+    /// give an invalid proof, as when a `Soit` variable is not fresh any more.
+    pub remaining: Vec<(usize, Detour)>,
+    /// The records whose formula is neither a subformula of the conclusion nor of an open
+    /// hypothesis, up to the instantiation of quantified variables
+    pub outside: Vec<usize>,
+}
+
+impl Normalization {
+    /// Tells whether every formula of the proof is a subformula of the conclusion or of an open
+    /// hypothesis, as is the case of normal intuitionistic proofs.
+    pub fn has_subformula_property(&self) -> bool {
+        self.outside.is_empty()
+    }
+}
+
+/// How a detour is eliminated.
+enum Reduction {
+    /// The formula eliminated is the one of a record cited by the introduction.
+    Copy(usize),
+    /// The elimination becomes another rule.
+    Justify(Jusitification),
+    /// The box `hyp..=end` the introduction closed is copied in place of the elimination, the
+    /// record `with` standing for the hypothesis.
+    Inline { hyp: usize, end: usize, with: usize },
+}
+
+impl Proof {
+    /// Eliminates the detours of the proof, an introduction followed by an elimination of the
+    /// same connective, until none is left: the result is checked as this proof is. The records
+    /// the conclusion does not depend on any more are removed.
+    ///
+    /// The detours on ∧, ⇔, ∨, ⇒ and ¬ are eliminated, also when the introduced formula is
+    /// copied by `Rwrt` in between.
+    pub fn normal_form(&self) -> Result<Normalization, NormalizationError> {
+        let mut current = self.with_records(self.records().to_vec());
+        if current.records().is_empty() {
+            return Err(NormalizationError::Empty);
+        }
+        current.check();
+        if let CheckUpResult::SemanticErrors { first_error, .. } = current.state() {
+            return Err(NormalizationError::NotValid(*first_error));
+        }
+        let mut detours = Vec::new();
+        // The eliminations whose reduction gives an invalid proof.
+        let mut stuck: BTreeSet<usize> = BTreeSet::new();
+        loop {
+            let records = current.records();
+            let next = (0..records.len())
+                .filter(|id| !stuck.contains(id))
+                .find_map(|id| {
+                    detour(records, id).map(|(detour, reduction)| (id, detour, reduction))
+                });
+            let Some((id, detour, reduction)) = next else {
+                break;
+            };
+            let mut candidate = reduce(&current, id, reduction);
+            candidate.check();
+            if matches!(candidate.state(), CheckUpResult::Valid) {
+                current = candidate;
+                detours.push(detour);
+                stuck.clear();
+            } else {
+                stuck.insert(id);
+            }
+        }
+        let remaining = stuck
+            .into_iter()
+            .filter_map(|id| detour(current.records(), id).map(|(detour, _)| (id, detour)))
+            .collect();
+        let outside = outside(current.records());
+        Ok(Normalization {
+            proof: current,
+            detours,
+            remaining,
+            outside,
+        })
+    }
+}
+
+/// Follows the copies of the record `id` back to the record first stating its formula.
+fn origin(records: &[Record], mut id: usize) -> usize {
+    while let Jusitification::Rwrt(orig) = records[id].justif {
+        if records[orig].stmt.get_formula() != records[id].stmt.get_formula() {
+            break;
+        }
+        id = orig;
+    }
+    id
+}
+
+/// Returns the detour the record `id` ends, if it eliminates a connective just introduced.
+fn detour(records: &[Record], id: usize) -> Option<(Detour, Reduction)> {
+    let rec = &records[id];
+    if !matches!(rec.stmt, Statement::Simple(_)) {
+        return None;
+    }
+    let major = match &rec.justif {
+        Jusitification::EAndL(pos)
+        | Jusitification::EAndR(pos)
+        | Jusitification::EEquivL(pos)
+        | Jusitification::EEquivR(pos) => *pos,
+        Jusitification::EImpl { implication, .. } => *implication,
+        Jusitification::ENot { negation, .. } => *negation,
+        Jusitification::EOr { a_or_b, .. } => *a_or_b,
+        _ => return None,
+    };
+    let intro = origin(records, major);
+    let reduction = match (&rec.justif, &records[intro].justif) {
+        (Jusitification::EAndL(_), Jusitification::IAnd { left, .. }) => Reduction::Copy(*left),
+        (Jusitification::EAndR(_), Jusitification::IAnd { right, .. }) => Reduction::Copy(*right),
+        (Jusitification::EEquivL(_), Jusitification::IEquiv { forward, .. }) => {
+            Reduction::Copy(*forward)
+        }
+        (Jusitification::EEquivR(_), Jusitification::IEquiv { backward, .. }) => {
+            Reduction::Copy(*backward)
+        }
+        (Jusitification::EImpl { hyp, .. }, Jusitification::IImpl)
+        | (Jusitification::ENot { hyp, .. }, Jusitification::INot) => {
+            let end = intro - 1;
+            Reduction::Inline {
+                hyp: *records[end].ctxt.last()?,
+                end,
+                with: *hyp,
+            }
+        }
+        (Jusitification::EOr { a_to_c, .. }, Jusitification::IOrR(pos, _)) => {
+            Reduction::Justify(Jusitification::EImpl {
+                hyp: *pos,
+                implication: *a_to_c,
+            })
+        }
+        (Jusitification::EOr { b_to_c, .. }, Jusitification::IOrL(pos, _)) => {
+            Reduction::Justify(Jusitification::EImpl {
+                hyp: *pos,
+                implication: *b_to_c,
+            })
+        }
+        _ => return None,
+    };
+    let detour = Detour {
+        introduction: records[intro].justif.name().to_string(),
+        elimination: rec.justif.name().to_string(),
+        formula: records[intro].stmt.get_formula().clone(),
+    };
+    Some((detour, reduction))
+}
+
+/// Applies `reduction` to the record `id` of `proof`, then removes the records the conclusion
+/// does not depend on any more.
+fn reduce(proof: &Proof, id: usize, reduction: Reduction) -> Proof {
+    let mut records = proof.records().to_vec();
+    match reduction {
+        Reduction::Copy(pos) => {
+            records[id].justif = Jusitification::Rwrt(pos);
+            // The records citing the copy may cite the original.
+            for rec in &mut records[id + 1..] {
+                rec.justif.renumber(|i| if i == id { pos } else { i });
+            }
+        }
+        Reduction::Justify(justif) => records[id].justif = justif,
+        Reduction::Inline { hyp, end, with } => {
+            let inner = records[hyp].ctxt.len();
+            let base = records[id].ctxt.clone();
+            let map = |i: usize| match i {
+                i if i == hyp => with,
+                i if i > hyp && i <= end => id + i - hyp - 1,
+                i => i,
+            };
+            let copies: Vec<Record> = records[hyp + 1..=end]
+                .iter()
+                .map(|rec| {
+                    let mut copy = rec.clone();
+                    copy.id = map(rec.id);
+                    copy.ctxt = base
+                        .iter()
+                        .copied()
+                        .chain(rec.ctxt[inner..].iter().map(|b| map(*b)))
+                        .collect();
+                    copy.justif.renumber(map);
+                    copy
+                })
+                .collect();
+            let mut spliced = proof.with_records(records);
+            spliced.splice(id, copies);
+            records = spliced.records().to_vec();
+        }
+    }
+    proof.with_records(prune(records, 0))
+}
+
+/// Returns the records whose formula is neither a subformula of the conclusion nor of an open
+/// hypothesis.
+fn outside(records: &[Record]) -> Vec<usize> {
+    let Some(last) = records.last() else {
+        return Vec::new();
+    };
+    let mut subformulas = Vec::new();
+    subformulas_of(last.stmt.get_formula(), &[], &mut subformulas);
+    for open in &last.ctxt {
+        if let Statement::Supposons(hyp) = &records[*open].stmt {
+            subformulas_of(hyp, &[], &mut subformulas);
+        }
+    }
+    records
+        .iter()
+        .filter(|rec| !matches!(rec.stmt, Statement::Soit(_) | Statement::Monde))
+        .filter(|rec| {
+            let formula = rec.stmt.get_formula();
+            !subformulas
+                .iter()
+                .any(|(part, bound)| instance_of(part, formula, bound, &mut BTreeMap::new()))
+        })
+        .map(|rec| rec.id)
+        .collect()
+}
+
+/// Lists the subformulas of `formula`, each with the variables bound above it: ¬a counts as
+/// a⇒⊥ and a⇔b as (a⇒b)∧(b⇒a).
+fn subformulas_of(formula: &Formula, bound: &[char], parts: &mut Vec<(Formula, Vec<char>)>) {
+    parts.push((formula.clone(), bound.to_vec()));
+    match formula {
+        Formula::Not(a) => {
+            parts.push((Formula::Bottom, Vec::new()));
+            subformulas_of(a, bound, parts);
+        }
+        Formula::Necessarily(a) | Formula::Possibly(a) => subformulas_of(a, bound, parts),
+        Formula::Equiv(a, b) => {
+            parts.push((Formula::Implies(a.clone(), b.clone()), bound.to_vec()));
+            parts.push((Formula::Implies(b.clone(), a.clone()), bound.to_vec()));
+            subformulas_of(a, bound, parts);
+            subformulas_of(b, bound, parts);
+        }
+        Formula::Or(a, b)
+        | Formula::And(a, b)
+        | Formula::Implies(a, b)
+        | Formula::RLImplies(a, b) => {
+            subformulas_of(a, bound, parts);
+            subformulas_of(b, bound, parts);
+        }
+        Formula::Forall(x, a) | Formula::Exists(x, a) => {
+            let mut bound = bound.to_vec();
+            bound.push(*x);
+            subformulas_of(a, &bound, parts);
+        }
+        Formula::Top
+        | Formula::Bottom
+        | Formula::Variable(_)
+        | Formula::Predicate(_, _)
+        | Formula::Equal(_, _) => (),
+    }
+}
+
+/// Tells whether `target` is `pattern` with terms for the variables `vars`, as bound so far
+/// by `binding`.
+fn instance_of(
+    pattern: &Formula,
+    target: &Formula,
+    vars: &[char],
+    binding: &mut BTreeMap<char, Term>,
+) -> bool {
+    match (pattern, target) {
+        (Formula::Not(a), Formula::Not(b))
+        | (Formula::Necessarily(a), Formula::Necessarily(b))
+        | (Formula::Possibly(a), Formula::Possibly(b)) => instance_of(a, b, vars, binding),
+        (Formula::Or(a, b), Formula::Or(c, d))
+        | (Formula::And(a, b), Formula::And(c, d))
+        | (Formula::Implies(a, b), Formula::Implies(c, d))
+        | (Formula::RLImplies(a, b), Formula::RLImplies(c, d))
+        | (Formula::Equiv(a, b), Formula::Equiv(c, d)) => {
+            instance_of(a, c, vars, binding) && instance_of(b, d, vars, binding)
+        }
+        (Formula::Predicate(p, args), Formula::Predicate(q, targets)) => {
+            p == q
+                && args.len() == targets.len()
+                && args
+                    .iter()
+                    .zip(targets)
+                    .all(|(arg, target)| term_instance_of(arg, target, vars, binding))
+        }
+        (Formula::Equal(s, t), Formula::Equal(u, v)) => {
+            term_instance_of(s, u, vars, binding) && term_instance_of(t, v, vars, binding)
+        }
+        (Formula::Forall(x, a), Formula::Forall(y, b))
+        | (Formula::Exists(x, a), Formula::Exists(y, b))
+            if x == y =>
+        {
+            let vars: Vec<char> = vars.iter().copied().filter(|v| v != x).collect();
+            instance_of(a, b, &vars, binding)
+        }
+        _ => pattern == target,
+    }
+}
+
+fn term_instance_of(
+    pattern: &Term,
+    target: &Term,
+    vars: &[char],
+    binding: &mut BTreeMap<char, Term>,
+) -> bool {
+    match (pattern, target) {
+        (Term::Variable(x), _) if vars.contains(x) => match binding.get(x) {
+            Some(bound) => bound == target,
+            None => {
+                binding.insert(*x, target.clone());
+                true
+            }
+        },
+        (Term::Function(f, args), Term::Function(g, targets)) => {
+            f == g
+                && args.len() == targets.len()
+                && args
+                    .iter()
+                    .zip(targets)
+                    .all(|(arg, target)| term_instance_of(arg, target, vars, binding))
+        }
+        _ => pattern == target,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(input: &str) -> Normalization {
+        Proof::read_proof(input).unwrap().normal_form().unwrap()
+    }
+
+    #[test]
+    fn detours() {
+        let and = normalize(
+            "0;0;Supposons a;Hyp\n\
+             1;0,1;Supposons b;Hyp\n\
+             2;0,1;a∧b;IAnd 0 1\n\
+             3;0,1;a;EAndL 2\n\
+             4;0;Donc b⇒a;IImpl\n\
+             5;;Donc a⇒b⇒a;IImpl\n",
+        );
+        assert_eq!(and.detours.len(), 1);
+        assert_eq!(and.detours[0].to_string(), "IAnd then EAndL on a∧b");
+        assert_eq!(
+            and.proof.to_string(),
+            "0;0;Supposons a;Hyp\n1;0,1;Supposons b;Hyp\n2;0,1;a;Rwrt 0\n\
+             3;0;Donc b⇒a;IImpl\n4;;Donc a⇒b⇒a;IImpl\n"
+        );
+        assert!(and.has_subformula_property());
+
+        let implication = normalize(
+            "0;0;Supposons a;Hyp\n\
+             1;0,1;Supposons a;Hyp\n\
+             2;0,1;a∧a;IAnd 1 1\n\
+             3;0;Donc a⇒a∧a;IImpl\n\
+             4;0;a∧a;EImpl 0 3\n\
+             5;;Donc a⇒a∧a;IImpl\n",
+        );
+        assert_eq!(
+            implication.proof.to_string(),
+            "0;0;Supposons a;Hyp\n1;0;a∧a;IAnd 0 0\n2;;Donc a⇒a∧a;IImpl\n"
+        );
+
+        // The EOr becomes an EImpl, itself a detour.
+        let or = normalize(
+            "0;0;Supposons a;Hyp\n\
+             1;0;a∨b;IOrR 0 b\n\
+             2;0,2;Supposons a;Hyp\n\
+             3;0,2;a;Rwrt 2\n\
+             4;0;Donc a⇒a;IImpl\n\
+             5;0,5;Supposons b;Hyp\n\
+             6;0,5;a;Rwrt 0\n\
+             7;0;Donc b⇒a;IImpl\n\
+             8;0;a;EOr 4 7 1\n\
+             9;;Donc a⇒a;IImpl\n",
+        );
+        let steps: Vec<String> = or.detours.iter().map(|d| d.to_string()).collect();
+        assert_eq!(steps, ["IOrR then EOr on a∨b", "IImpl then EImpl on a⇒a"]);
+        assert_eq!(
+            or.proof.to_string(),
+            "0;0;Supposons a;Hyp\n1;0;a;Rwrt 0\n2;;Donc a⇒a;IImpl\n"
+        );
+        assert!(or.remaining.is_empty());
+    }
+
+    #[test]
+    fn normal_proofs() {
+        for input in [
+            include_str!("../proofs/first.proof"),
+            include_str!("../proofs/third.proof"),
+            include_str!("../proofs/fourth.proof"),
+            include_str!("../proofs/fifth.proof"),
+            include_str!("../proofs/sixth.proof"),
+        ] {
+            let normal = normalize(input);
+            assert!(normal.detours.is_empty());
+            let proof = Proof::read_proof(input).unwrap();
+            assert_eq!(normal.proof.to_string(), proof.to_string());
+        }
+        let unused =
+            normalize("0;0;Supposons a;Hyp\n1;0;a∨c;IOrR 0 c\n2;0;a;Rwrt 0\n3;;Donc a⇒a;IImpl\n");
+        assert_eq!(unused.outside, [1]);
+        let instance = normalize(
+            "0;0;Supposons ∀x(P(x)∧Q(x));Hyp\n\
+             1;0;P(c())∧Q(c());EForall 0 c()\n\
+             2;0;P(c());EAndL 1\n\
+             3;;Donc ∀x(P(x)∧Q(x))⇒P(c());IImpl\n",
+        );
+        assert!(instance.has_subformula_property());
+        assert!(matches!(
+            Proof::read_proof("0;0;a;Hyp\n").unwrap().normal_form(),
+            Err(NormalizationError::NotValid(0))
+        ));
+    }
+}
@@ -1,9 +1,17 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
 use thiserror::Error;
 
 use crate::{
+    edit::History,
     formula::Formula,
     justif::Jusitification,
+    modal::ModalSystem,
     record::{Record, Statement},
+    rule::{Rule, RuleRegistry},
+    theorem::Theorem,
 };
 
 #[derive(Error,Debug)]
@@ -19,6 +27,9 @@ pub enum SemanticError {
     /// Internal error. Shouldn't happen
     #[error("Internal error. Shouldn't happen")]
     InternalError,
+    /// A proof should have a record
+    #[error("The proof has no record")]
+    EmptyProof,
     /// Id should match the record's position
     #[error("Id should match the record's position")]
     IncorrectId,
@@ -35,6 +46,35 @@ pub enum SemanticError {
     #[error("A Supposons has to justified by a Hyp")]
     SuppJustIsHyp,
 
+    /// A Soit should have the same amount of context as the previous statement, plus one term.
+    #[error("A Soit should have the same amount of context as the previous statement, plus one term.")]
+    SoitCtxtOneMoreThenBefore,
+    /// A Soit should share the same context as the previous statement, except for the last term.
+    #[error("A Soit should share the same context as the previous statement, except for the last term.")]
+    SoitCtxtSameAsBefore,
+    /// The last term of the context of a Soit should be the id of the record.
+    #[error("The last term of the context of a Soit should be the id of the record.")]
+    SoitCtxtLastIsId,
+    /// A Soit has to justified by a Hyp
+    #[error("A Soit has to justified by a Hyp")]
+    SoitJustIsHyp,
+    /// A Soit variable should not be free in an open hypothesis or a premise, nor be introduced by an open Soit
+    #[error("A Soit variable should not be free in an open hypothesis or a premise, nor be introduced by an open Soit")]
+    SoitNotFresh,
+
+    /// A Monde should have the same amount of context as the previous statement, plus one term.
+    #[error("A Monde should have the same amount of context as the previous statement, plus one term.")]
+    MondeCtxtOneMoreThenBefore,
+    /// A Monde should share the same context as the previous statement, except for the last term.
+    #[error("A Monde should share the same context as the previous statement, except for the last term.")]
+    MondeCtxtSameAsBefore,
+    /// The last term of the context of a Monde should be the id of the record.
+    #[error("The last term of the context of a Monde should be the id of the record.")]
+    MondeCtxtLastIsId,
+    /// A Monde has to justified by a Hyp
+    #[error("A Monde has to justified by a Hyp")]
+    MondeJustIsHyp,
+
     /// Cannot start with a Donc statement
     #[error("Cannot start with a Donc statement")]
     DoncNotFirst,
@@ -56,9 +96,42 @@ pub enum SemanticError {
     /// Donc formula should be an implies.
     #[error("Donc formula should be an implies.")]
     DoncFormulaIsImplies,
-    /// Donc should have implication introduction justification
-    #[error("Donc should have implication introduction justification")]
+    /// Donc should have implication, negation, universal or necessity introduction justification
+    #[error("Donc should have implication, negation, universal or necessity introduction justification")]
     DoncJustifIsIImpl,
+    /// An IImpl Donc should close a Supposons
+    #[error("An IImpl Donc should close a Supposons")]
+    DoncImplNotSupposons,
+    /// An INot Donc should close a Supposons
+    #[error("An INot Donc should close a Supposons")]
+    DoncNotNotSupposons,
+    /// An INot Donc formula should be a Not
+    #[error("An INot Donc formula should be a Not")]
+    DoncFormulaIsNot,
+    /// An INot Donc consequence should be ⊥
+    #[error("An INot Donc consequence should be ⊥")]
+    DoncNotConsIsBottom,
+    /// An IForall Donc should close a Soit
+    #[error("An IForall Donc should close a Soit")]
+    DoncForallNotSoit,
+    /// An IForall Donc formula should be a Forall
+    #[error("An IForall Donc formula should be a Forall")]
+    DoncFormulaIsForall,
+    /// An IForall Donc formula should match the consequence, for the variable of the Soit
+    #[error("An IForall Donc formula should match the consequence, for the variable of the Soit")]
+    DoncForallNotMatching,
+    /// The variable of the Soit should not be free in an IForall Donc formula
+    #[error("The variable of the Soit should not be free in an IForall Donc formula")]
+    DoncForallVariableFree,
+    /// An INec Donc should close a Monde
+    #[error("An INec Donc should close a Monde")]
+    DoncNecNotMonde,
+    /// An INec Donc formula should be a Necessarily
+    #[error("An INec Donc formula should be a Necessarily")]
+    DoncFormulaIsNecessarily,
+    /// An INec Donc formula should be the necessity of the consequence
+    #[error("An INec Donc formula should be the necessity of the consequence")]
+    DoncNecNotMatching,
 
     /// Simple shouldn't start a proof
     #[error("Simple shouldn't start a proof")]
@@ -66,6 +139,9 @@ pub enum SemanticError {
     /// Simple context should be the same as the statement before
     #[error("Simple context should be the same as the statement before")]
     SimpleCtxtSameAsBefore,
+    /// A Soit or a Monde states no formula, no rule may cite it
+    #[error("Record {0} is a Soit or a Monde, it states no formula to cite")]
+    CitesNoFormula(usize),
 
     /// IOrL right reference should be lesser than id
     #[error("IOrL right reference should be lesser than id")]
@@ -183,6 +259,12 @@ pub enum SemanticError {
     /// IImpl may not justify a simple statement
     #[error("IImpl may not justify a simple statement")]
     IImplNotSimple,
+    /// IForall may not justify a simple statement
+    #[error("IForall may not justify a simple statement")]
+    IForallNotSimple,
+    /// INec may not justify a simple statement
+    #[error("INec may not justify a simple statement")]
+    INecNotSimple,
 
     /// EImpl hyp reference should be lesser than id
     #[error("EImpl hyp reference should be lesser than id")]
@@ -228,6 +310,127 @@ pub enum SemanticError {
     /// Raa formula should match
     #[error("Raa formula should match")]
     RaaNotMatching,
+    /// Raa is a classical rule
+    #[error("Raa is a classical rule, it is not allowed in intuitionistic or minimal logic")]
+    RaaNotIntuitionistic,
+
+    /// A premise should be out of every box
+    #[error("A premise should be out of every box")]
+    PremiseInBox,
+    /// A premise should be justified by Hyp
+    #[error("A premise should be justified by Hyp")]
+    PremiseJustIsHyp,
+    /// A premise should be one of the declared theorem
+    #[error("{0} is not a premise of the theorem")]
+    PremiseNotDeclared(Box<Formula>),
+    /// The proof of a declared theorem should end out of every box
+    #[error("The proof should end out of every box")]
+    GoalInBox,
+    /// The proof of a declared theorem should end by its goal
+    #[error("The proof ends with {0}, not with the goal of the theorem")]
+    GoalNotProved(Box<Formula>),
+
+    /// The rule has not been added to the proof
+    #[error("{0} is not a rule of the proof")]
+    DerivedUnknown(String),
+    /// A schema rule discharging a hypothesis should justify a Donc
+    #[error("{0} closes a box, it should justify a Donc")]
+    SchemaNotSimple(String),
+    /// The box closed by a schema rule should be opened by Supposons
+    #[error("The box closed should be opened by Supposons")]
+    SchemaNotSupposons,
+    /// The schema rule needs a stronger logic
+    #[error("{0} needs a stronger logic than the one the proof is checked in")]
+    SchemaNotInLogic(String),
+    /// Schema rule formulas should be an instance of its schema
+    #[error("The formulas should be an instance of the schema of {0}")]
+    SchemaNotMatching(String),
+    /// A rule added to the proof rejected the record
+    #[error("{0}")]
+    RuleRejected(String),
+    /// The proof of the derived rule needs a stronger logic
+    #[error("The derived rule needs a stronger logic than the one the proof is checked in")]
+    DerivedNotInLogic,
+    /// Derived rule references should match its premises
+    #[error("The rule should cite one record per premise")]
+    DerivedArity,
+    /// Derived rule references should be lesser than id
+    #[error("Rule references should be lesser than id")]
+    DerivedPosLesser,
+    /// Derived rule statements should be compatible
+    #[error("Rule statements should be compatible")]
+    DerivedIncompatibleCtxt,
+    /// Derived rule formulas should be an instance of its schema
+    #[error("The formulas should be an instance of the schema of the derived rule")]
+    DerivedNotMatching,
+
+    /// ENot hyp reference should be lesser than id
+    #[error("ENot hyp reference should be lesser than id")]
+    ENotHypPosLesser,
+    /// ENot negation reference should be lesser than id
+    #[error("ENot negation reference should be lesser than id")]
+    ENotNegationPosLesser,
+    /// ENot hyp statement should be compatible
+    #[error("ENot hyp statement should be compatible")]
+    ENotHypIncompatibleCtxt,
+    /// ENot negation statement should be compatible
+    #[error("ENot negation statement should be compatible")]
+    ENotNegationIncompatibleCtxt,
+    /// ENot referenced negation should be a Not
+    #[error("ENot referenced negation should be a Not")]
+    ENotNegationIsNot,
+    /// ENot hyp formula should be the negated formula
+    #[error("ENot hyp formula should be the negated formula")]
+    ENotHypNotMatching,
+    /// ENot formula should be a Bot
+    #[error("ENot formula should be a Bot")]
+    ENotFormulaIsBot,
+
+    /// IEquiv forward reference should be lesser than id
+    #[error("IEquiv forward reference should be lesser than id")]
+    IEquivForwardPosLesser,
+    /// IEquiv backward reference should be lesser than id
+    #[error("IEquiv backward reference should be lesser than id")]
+    IEquivBackwardPosLesser,
+    /// IEquiv forward statement should be compatible
+    #[error("IEquiv forward statement should be compatible")]
+    IEquivForwardIncompatibleCtxt,
+    /// IEquiv backward statement should be compatible
+    #[error("IEquiv backward statement should be compatible")]
+    IEquivBackwardIncompatibleCtxt,
+    /// IEquiv forward formula should be the left to right implication
+    #[error("IEquiv forward formula should be the left to right implication")]
+    IEquivForwardNotMatching,
+    /// IEquiv backward formula should be the right to left implication
+    #[error("IEquiv backward formula should be the right to left implication")]
+    IEquivBackwardNotMatching,
+    /// IEquiv formula should be an Equiv
+    #[error("IEquiv formula should be an Equiv")]
+    IEquivFormulaIsEquiv,
+
+    /// EEquiv reference should be lesser than id
+    #[error("EEquiv reference should be lesser than id")]
+    EEquivPosLesser,
+    /// EEquiv statement should be compatible
+    #[error("EEquiv statement should be compatible")]
+    EEquivIncompatibleCtxt,
+    /// EEquiv referenced formula should be an Equiv
+    #[error("EEquiv referenced formula should be an Equiv")]
+    EEquivFormulaIsEquiv,
+    /// EEquiv formula should be the implication of one side of the equivalence to the other
+    #[error("EEquiv formula should be the implication of one side of the equivalence to the other")]
+    EEquivNotMatching,
+
+    /// ITop formula should be a Top
+    #[error("ITop formula should be a Top")]
+    ITopFormulaIsTop,
+    /// INot may not justify a simple statement
+    #[error("INot may not justify a simple statement")]
+    INotNotSimple,
+
+    /// Efq is not a rule of minimal logic
+    #[error("Efq is not allowed in minimal logic")]
+    EfqNotMinimal,
 
     /// Rwrt reference should be lesser than id
     #[error("Rwrt reference should be lesser than id")]
@@ -241,6 +444,93 @@ pub enum SemanticError {
     /// Rwrt formula should match
     #[error("Rwrt formula should match")]
     RwrtNotMatching,
+
+    /// EForall reference should be lesser than id
+    #[error("EForall reference should be lesser than id")]
+    EForallPosLesser,
+    /// EForall statement should be compatible
+    #[error("EForall statement should be compatible")]
+    EForallIncompatibleCtxt,
+    /// EForall referenced formula should be a Forall
+    #[error("EForall referenced formula should be a Forall")]
+    EForallFormulaIsForall,
+    /// EForall formula should be the instance of the Forall for the term
+    #[error("EForall formula should be the instance of the Forall for the term")]
+    EForallNotMatching,
+
+    /// IExists reference should be lesser than id
+    #[error("IExists reference should be lesser than id")]
+    IExistsPosLesser,
+    /// IExists statement should be compatible
+    #[error("IExists statement should be compatible")]
+    IExistsIncompatibleCtxt,
+    /// IExists formula should be an Exists
+    #[error("IExists formula should be an Exists")]
+    IExistsFormulaIsExists,
+    /// IExists referenced formula should be the instance of the Exists for the witness
+    #[error("IExists referenced formula should be the instance of the Exists for the witness")]
+    IExistsNotMatching,
+
+    /// EExists exists reference should be lesser than id
+    #[error("EExists exists reference should be lesser than id")]
+    EExistsExistsPosLesser,
+    /// EExists forall reference should be lesser than id
+    #[error("EExists forall reference should be lesser than id")]
+    EExistsForallPosLesser,
+    /// EExists exists statement should be compatible
+    #[error("EExists exists statement should be compatible")]
+    EExistsExistsIncompatibleCtxt,
+    /// EExists forall statement should be compatible
+    #[error("EExists forall statement should be compatible")]
+    EExistsForallIncompatibleCtxt,
+    /// EExists formulas should be of the form ∃x*, ∀y(*⇒*)
+    #[error("EExists formulas should be of the form ∃x*, ∀y(*⇒*)")]
+    EExistsFormulasNotRightKind,
+    /// EExists hypothesis of the implication should be the body of the Exists, for y
+    #[error("EExists hypothesis of the implication should be the body of the Exists, for y")]
+    EExistsBodyNotMatching,
+    /// EExists variable y should not be free in the conclusion
+    #[error("EExists variable y should not be free in the conclusion")]
+    EExistsVariableFree,
+    /// EExists conclusion should match the consequence of the implication
+    #[error("EExists conclusion should match the consequence of the implication")]
+    EExistsConclusionNotMatching,
+
+    /// ENec reference should be lesser than id
+    #[error("ENec reference should be lesser than id")]
+    ENecPosLesser,
+    /// ENec statement should be compatible, modal boxes aside
+    #[error("ENec statement should be compatible, modal boxes aside")]
+    ENecIncompatibleCtxt,
+    /// ENec should cross as many Monde boxes as the modal system allows
+    #[error("ENec should cross exactly one Monde in K, at most one in T, any number in S4 and S5")]
+    ENecWrongDepth,
+    /// ENec referenced formula should be a Necessarily
+    #[error("ENec referenced formula should be a Necessarily")]
+    ENecFormulaIsNecessarily,
+    /// ENec formula should match
+    #[error("ENec formula should match")]
+    ENecNotMatching,
+
+    /// RPos is only allowed in S5
+    #[error("RPos is only allowed in S5")]
+    RPosNotS5,
+    /// RPos reference should be lesser than id
+    #[error("RPos reference should be lesser than id")]
+    RPosPosLesser,
+    /// RPos statement should be compatible, modal boxes aside
+    #[error("RPos statement should be compatible, modal boxes aside")]
+    RPosIncompatibleCtxt,
+    /// RPos formula should be a Possibly
+    #[error("RPos formula should be a Possibly")]
+    RPosFormulaIsPossibly,
+    /// RPos formula should match
+    #[error("RPos formula should match")]
+    RPosNotMatching,
+
+    /// A ? record has to be filled in
+    #[error("The ? placeholder has to be replaced by a derivation, see Proof::fill_gaps")]
+    GapNotFilled,
 }
 
 #[derive(Debug)]
@@ -254,17 +544,74 @@ pub enum CheckUpResult {
     },
 }
 
+/// The logic a proof is checked in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Logic {
+    /// Every rule is allowed
+    #[default]
+    Classical,
+    /// Raa is forbidden
+    Intuitionistic,
+    /// Raa and Efq are forbidden
+    Minimal,
+}
+
+impl Logic {
+    /// Tells whether what holds in this logic holds in `logic` too.
+    pub fn holds_in(self, logic: Logic) -> bool {
+        matches!(
+            (self, logic),
+            (Logic::Minimal, _)
+                | (_, Logic::Classical)
+                | (Logic::Intuitionistic, Logic::Intuitionistic)
+        )
+    }
+}
+
 pub struct Proof {
     records: Vec<Record>,
     valid: CheckUpResult,
+    logic: Logic,
+    modal_system: ModalSystem,
+    rules: Arc<RuleRegistry>,
+    theorem: Option<Theorem>,
+    history: History,
 }
 
 impl Proof {
-    /// Reads a proof from a string.
+    /// Reads a proof from a string. Blank lines and comment lines, starting by `(*`, are ignored.
+    /// The records may follow a header declaring the theorem the proof proves, as in
+    /// `Théorème a⇒b, a ⊢ b`.
     pub fn read_proof(input: &str) -> Result<Self, ReadError> {
+        Self::read_proof_citing(input, RuleRegistry::shared_standard())
+    }
+
+    /// Reads a proof whose records cite the rules of `rules`, which it is then checked with.
+    pub fn read_proof_with(input: &str, rules: RuleRegistry) -> Result<Self, ReadError> {
+        Self::read_proof_citing(input, Arc::new(rules))
+    }
+
+    fn read_proof_citing(input: &str, rules: Arc<RuleRegistry>) -> Result<Self, ReadError> {
         let mut records: Vec<Record> = Vec::new();
+        let mut theorem = None;
         for (stmt_no, record) in input.split('\n').enumerate() {
-            match Record::read_record(record) {
+            if record.trim().is_empty() || record.trim().starts_with("(*") {
+                continue;
+            }
+            if let Some(header) = record.trim().strip_prefix("Théorème ") {
+                if theorem.is_some() || !records.is_empty() {
+                    return Err(ReadError {
+                        stmt: stmt_no,
+                        content: crate::record::RecordError::InvalidTheorem,
+                    });
+                }
+                theorem = Some(Theorem::read(header).map_err(|e| ReadError {
+                    stmt: stmt_no,
+                    content: e,
+                })?);
+                continue;
+            }
+            match Record::read_record_with(record, &rules) {
                 Ok(r) => records.push(r),
                 Err(e) => {
                     return Err(ReadError {
@@ -277,12 +624,87 @@ impl Proof {
         Ok(Self {
             records,
             valid: CheckUpResult::NotChecked,
+            logic: Logic::default(),
+            modal_system: ModalSystem::default(),
+            rules,
+            theorem,
+            history: History::default(),
         })
     }
 
+    /// Builds an unchecked proof made of `records`.
+    pub(crate) fn from_records(records: Vec<Record>) -> Self {
+        Self {
+            records,
+            valid: CheckUpResult::NotChecked,
+            logic: Logic::default(),
+            modal_system: ModalSystem::default(),
+            rules: RuleRegistry::shared_standard(),
+            theorem: None,
+            history: History::default(),
+        }
+    }
+
+    /// Builds an unchecked proof made of `records`, checked in the logic, the modal system and
+    /// with the rules of this one.
+    pub(crate) fn with_records(&self, records: Vec<Record>) -> Self {
+        Self {
+            records,
+            valid: CheckUpResult::NotChecked,
+            logic: self.logic,
+            modal_system: self.modal_system,
+            rules: Arc::clone(&self.rules),
+            theorem: self.theorem.clone(),
+            history: History::default(),
+        }
+    }
+
+    /// Replaces the record `at` by `replacement`, whose records are numbered from `at`. The
+    /// records after it are renumbered, and references to the record `at` now point to the
+    /// last replacing record.
+    pub(crate) fn splice(&mut self, at: usize, replacement: Vec<Record>) {
+        let shift = replacement.len();
+        let map = |i: usize| if i < at { i } else { i + shift - 1 };
+        let rest: Vec<Record> = self.records.drain(at..).skip(1).collect();
+        self.records.extend(replacement);
+        for mut rec in rest {
+            rec.renumber(map);
+            self.records.push(rec);
+        }
+        self.valid = CheckUpResult::NotChecked;
+    }
+
+    /// Replaces the records by `records`, keeping the changed ones to undo the edit. The proof
+    /// has to be checked again.
+    pub(crate) fn edit(&mut self, records: Vec<Record>) {
+        let former = std::mem::replace(&mut self.records, records);
+        self.history.done(former, &self.records);
+        self.valid = CheckUpResult::NotChecked;
+    }
+
+    /// Undoes the last edit. Returns false if there is none. The proof has to be checked
+    /// again.
+    pub fn undo(&mut self) -> bool {
+        let undone = self.history.undo(&mut self.records);
+        if undone {
+            self.valid = CheckUpResult::NotChecked;
+        }
+        undone
+    }
+
+    /// Redoes the last undone edit. Returns false if there is none, as after a new edit. The
+    /// proof has to be checked again.
+    pub fn redo(&mut self) -> bool {
+        let redone = self.history.redo(&mut self.records);
+        if redone {
+            self.valid = CheckUpResult::NotChecked;
+        }
+        redone
+    }
+
     /// Reads a record from input and adds it to the proof.
     pub fn import_record(&mut self, input: &str) -> Result<(), ReadError> {
-        match Record::read_record(input) {
+        match Record::read_record_with(input, &self.rules) {
             Ok(r) => {
                 self.add_record(r);
                 Ok(())
@@ -294,8 +716,9 @@ impl Proof {
         }
     }
 
-    /// Adds a record to the proof
+    /// Adds a record to the proof, which may be undone.
     pub fn add_record(&mut self, record: Record) {
+        self.history.appended(self.records.len());
         if let CheckUpResult::Valid = self.valid {
             self.valid = CheckUpResult::ValidUntil(self.records.len())
         }
@@ -304,15 +727,23 @@ impl Proof {
 
     /// Checks the proof for record 0..=id. Returns Err if the provided id
     /// is invalid.
+    #[allow(clippy::result_unit_err)]
     pub fn check_up_to(&mut self, id: usize) -> Result<(), ()> {
         if id >= self.records.len() {
-            return Err(());
+            Err(())
         } else {
             let mut erred = false;
             let mut until: usize = 0;
             let mut errors: Vec<(usize, SemanticError)> = Vec::new();
             for id in 0..=id {
-                match self.check_single_record(id) {
+                let checked = self.check_single_record(id).and_then(|()| {
+                    if id + 1 == self.records.len() {
+                        self.check_goal()
+                    } else {
+                        Ok(())
+                    }
+                });
+                match checked {
                     Ok(()) => (),
                     Err(e) => {
                         if !erred {
@@ -334,16 +765,122 @@ impl Proof {
             } else {
                 CheckUpResult::ValidUntil(id + 1)
             };
-            return Ok(());
+            Ok(())
         }
     }
 
-    /// Checks the proof for record 0..=id. Returns Err if the provided id
-    /// is invalid.
+    /// Checks the whole proof. A proof without records proves nothing, it is not valid.
     pub fn check(&mut self) {
+        if self.records.is_empty() {
+            self.valid = CheckUpResult::SemanticErrors {
+                first_error: 0,
+                errors: vec![(0, SemanticError::EmptyProof)],
+            };
+            return;
+        }
         self.check_up_to(self.records.len() - 1).unwrap()
     }
 
+    /// Sets the logic the proof is checked in. The proof has to be checked again.
+    pub fn set_logic(&mut self, logic: Logic) {
+        self.logic = logic;
+        self.valid = CheckUpResult::NotChecked;
+    }
+
+    pub fn logic(&self) -> Logic {
+        self.logic
+    }
+
+    /// Sets the modal system the `ENec` and `RPos` rules follow. The proof has to be checked
+    /// again.
+    pub fn set_modal_system(&mut self, modal_system: ModalSystem) {
+        self.modal_system = modal_system;
+        self.valid = CheckUpResult::NotChecked;
+    }
+
+    pub fn modal_system(&self) -> ModalSystem {
+        self.modal_system
+    }
+
+    /// Lets the records cite `rule`, such as a [`crate::derived::DerivedRule`], replacing the
+    /// rule of the same name. The proof has to be checked again.
+    pub fn add_rule(&mut self, rule: impl Rule + 'static) {
+        Arc::make_mut(&mut self.rules).register(rule);
+        self.valid = CheckUpResult::NotChecked;
+    }
+
+    /// Returns the theorem the proof is declared to prove.
+    pub fn theorem(&self) -> Option<&Theorem> {
+        self.theorem.as_ref()
+    }
+
+    /// Declares the theorem the proof proves. The proof has to be checked again.
+    pub fn set_theorem(&mut self, theorem: Option<Theorem>) {
+        self.theorem = theorem;
+        self.valid = CheckUpResult::NotChecked;
+    }
+
+    /// Returns the rules the proof is checked with.
+    pub fn rules(&self) -> &RuleRegistry {
+        &self.rules
+    }
+
+    /// Returns the records the record `id` was directly derived from: the references of its
+    /// justification, and for a `Donc` the box it closes and the consequence.
+    ///
+    /// References that do not point to an earlier record are ignored.
+    pub fn premises(&self, id: usize) -> Vec<usize> {
+        let rec = match self.records.get(id) {
+            Some(rec) => rec,
+            None => return Vec::new(),
+        };
+        let closing = matches!(rec.stmt, Statement::Donc(_))
+            && self.rules.get(rec.justif.name()).is_some_and(|rule| rule.closes_box());
+        let mut premises = match &rec.justif {
+            justif if closing && id > 0 => {
+                let cons = &self.records[id - 1];
+                let mut premises: Vec<usize> = cons.ctxt.last().copied().into_iter().collect();
+                premises.push(id - 1);
+                premises.extend(justif.references());
+                premises
+            }
+            justif => justif.references(),
+        };
+        premises.retain(|p| *p < id);
+        premises.dedup();
+        premises
+    }
+
+    /// Tells whether the record is justified by a rule holding in classical logic only.
+    pub(crate) fn is_classical_step(&self, rec: &Record) -> bool {
+        self.rules
+            .get(rec.justif.name())
+            .is_some_and(|rule| rule.logic() == Logic::Classical)
+    }
+
+    /// For each record, lists the classical steps (those of a rule holding in classical logic
+    /// only, such as `Raa`) it transitively depends on, itself included.
+    ///
+    /// A record with an empty list has a constructive derivation.
+    pub fn classical_dependencies(&self) -> Vec<BTreeSet<usize>> {
+        let mut deps: Vec<BTreeSet<usize>> = Vec::with_capacity(self.records.len());
+        for (id, rec) in self.records.iter().enumerate() {
+            let mut classical = BTreeSet::new();
+            if self.is_classical_step(rec) {
+                classical.insert(id);
+            }
+            for premise in self.premises(id) {
+                classical.extend(deps[premise].iter().copied());
+            }
+            deps.push(classical);
+        }
+        deps
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
     pub fn state(&self) -> &CheckUpResult {
         &self.valid
     }
@@ -387,314 +924,461 @@ impl Proof {
                     _ => Err(SemanticError::SuppJustIsHyp),
                 }
             }
-            Statement::Donc(conclusion) => {
+            Statement::Soit(x) => {
+                // Check context
+                if id != 0 {
+                    let ante = &self.records[id - 1];
+                    if rec.ctxt.len() != ante.ctxt.len() + 1 {
+                        return Err(SemanticError::SoitCtxtOneMoreThenBefore);
+                    } else if rec.ctxt[0..rec.ctxt.len() - 1] != ante.ctxt {
+                        return Err(SemanticError::SoitCtxtSameAsBefore);
+                    } else if rec.ctxt[rec.ctxt.len() - 1] != rec.id {
+                        return Err(SemanticError::SoitCtxtLastIsId);
+                    }
+                } else {
+                    if rec.ctxt.len() != 1 {
+                        return Err(SemanticError::SoitCtxtOneMoreThenBefore);
+                    }
+                    if rec.ctxt[0] != rec.id {
+                        return Err(SemanticError::SoitCtxtLastIsId);
+                    }
+                };
+                // The variable must be fresh: nothing is assumed about it in the open boxes.
+                for open in rec.ctxt[0..rec.ctxt.len() - 1].iter().filter(|open| **open < id) {
+                    let fresh = match &self.records[*open].stmt {
+                        Statement::Soit(y) => y != x,
+                        stmt => !stmt.get_formula().has_free(*x),
+                    };
+                    if !fresh {
+                        return Err(SemanticError::SoitNotFresh);
+                    }
+                }
+                // Nor in the premises the box may use.
+                let assumed = self.records[..id].iter().any(|other| {
+                    matches!(&other.stmt, Statement::Premise(f) if f.has_free(*x))
+                        && self.accessible(&rec.ctxt, other)
+                });
+                if assumed {
+                    return Err(SemanticError::SoitNotFresh);
+                }
+                match rec.justif {
+                    Jusitification::Hyp => Ok(()),
+                    _ => Err(SemanticError::SoitJustIsHyp),
+                }
+            }
+            Statement::Monde => {
+                // Check context
+                if id != 0 {
+                    let ante = &self.records[id - 1];
+                    if rec.ctxt.len() != ante.ctxt.len() + 1 {
+                        return Err(SemanticError::MondeCtxtOneMoreThenBefore);
+                    } else if rec.ctxt[0..rec.ctxt.len() - 1] != ante.ctxt {
+                        return Err(SemanticError::MondeCtxtSameAsBefore);
+                    } else if rec.ctxt[rec.ctxt.len() - 1] != rec.id {
+                        return Err(SemanticError::MondeCtxtLastIsId);
+                    }
+                } else {
+                    if rec.ctxt.len() != 1 {
+                        return Err(SemanticError::MondeCtxtOneMoreThenBefore);
+                    }
+                    if rec.ctxt[0] != rec.id {
+                        return Err(SemanticError::MondeCtxtLastIsId);
+                    }
+                };
                 match rec.justif {
-                    Jusitification::IImpl => {
-                        if id == 0 {
-                            return Err(SemanticError::DoncNotFirst);
-                        }
-                        let cons = &self.records[id - 1];
-                        if cons.ctxt.len() != rec.ctxt.len() + 1 {
-                            return Err(SemanticError::DoncCtxtOneLessThenBefore);
-                        }
-                        if cons.ctxt[0..rec.ctxt.len()] != rec.ctxt {
-                            return Err(SemanticError::DoncCtxtSameAsBefore);
-                        }
-
-                        let hyp_pos = cons.ctxt[cons.ctxt.len() - 1];
-
-                        // We ensure the hypothesis was formulated before the current record,
-                        // furthermore we want the consequence record to be distinct from the
-                        // record.
-                        if hyp_pos + 2 > id {
-                            return Err(SemanticError::DoncHypDifCons);
-                        }
-                        let hyp = &self.records[hyp_pos];
-                        match conclusion {
-                            Formula::Implies(hyp_formula, cons_formula) => {
-                                if hyp_formula.as_ref() != hyp.stmt.get_formula() {
-                                    Err(SemanticError::DoncHypNotMatching)
-                                } else if cons_formula.as_ref() != cons.stmt.get_formula() {
-                                    Err(SemanticError::DoncConsNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            _ => Err(SemanticError::DoncFormulaIsImplies),
-                        }
+                    Jusitification::Hyp => Ok(()),
+                    _ => Err(SemanticError::MondeJustIsHyp),
+                }
+            }
+            Statement::Premise(formula) => {
+                if !rec.ctxt.is_empty() {
+                    return Err(SemanticError::PremiseInBox);
+                }
+                if let Some(theorem) = &self.theorem {
+                    if !theorem.has_premise(formula) {
+                        return Err(SemanticError::PremiseNotDeclared(Box::new(formula.clone())));
                     }
-                    _ => Err(SemanticError::DoncJustifIsIImpl),
+                }
+                match rec.justif {
+                    Jusitification::Hyp => Ok(()),
+                    _ => Err(SemanticError::PremiseJustIsHyp),
                 }
             }
-            Statement::Simple(formula) => {
+            Statement::Donc(_) => match self.rules.get(rec.justif.name()) {
+                Some(rule) if rule.closes_box() => {
+                    self.check_citations(rec)?;
+                    rule.check(self, rec)
+                }
+                _ => Err(SemanticError::DoncJustifIsIImpl),
+            },
+            Statement::Simple(_) => {
                 if id == 0 {
                     return Err(SemanticError::SimpleIsFirst);
                 }
                 if rec.ctxt != self.records[id - 1].ctxt {
                     return Err(SemanticError::SimpleCtxtSameAsBefore);
                 }
-                match &rec.justif {
-                    Jusitification::IOrL(right_pos, new_left_formula) => {
-                        if *right_pos >= id {
-                            return Err(SemanticError::IOrLPosLesser);
-                        }
-                        // This is valid record, since it comes before and was therefore
-                        // valited at a previous run of this loop.
-                        let right_rec = &self.records[*right_pos];
-                        // We check if the record is usable
-                        if !check_ctxt_compatibility(&rec.ctxt, &right_rec.ctxt) {
-                            return Err(SemanticError::IOrLIncompatibleCtxt);
-                        }
-                        match formula {
-                            Formula::Or(left_formula, right_formula) => {
-                                if **left_formula != *new_left_formula {
-                                    Err(SemanticError::IOrLLeftNotMatching)
-                                } else if **right_formula != *right_rec.stmt.get_formula() {
-                                    Err(SemanticError::IOrLRightNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            _ => return Err(SemanticError::IOrLFormulaIsOr),
-                        }
-                    }
-                    Jusitification::IOrR(left_pos, new_right_formula) => {
-                        if *left_pos >= id {
-                            return Err(SemanticError::IOrRPosLesser);
-                        }
-                        // This is valid record, since it comes before and was therefore
-                        // valited at a previous run of this loop.
-                        let left_rec = &self.records[*left_pos];
-                        // We check if the record is usable
-                        if !check_ctxt_compatibility(&rec.ctxt, &left_rec.ctxt) {
-                            return Err(SemanticError::IOrRIncompatibleCtxt);
-                        }
-                        match formula {
-                            Formula::Or(left_formula, right_formula) => {
-                                if **right_formula != *new_right_formula {
-                                    Err(SemanticError::IOrRRightNotMatching)
-                                } else if **left_formula != *left_rec.stmt.get_formula() {
-                                    Err(SemanticError::IOrRRightNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            _ => Err(SemanticError::IOrRFormulaIsOr),
-                        }
-                    }
-                    Jusitification::EOr {
-                        a_to_c,
-                        b_to_c,
-                        a_or_b,
-                    } => {
-                        // Checking ids
-                        if *a_to_c >= id {
-                            return Err(SemanticError::EOrA2CPosLesser);
-                        }
-                        if *b_to_c >= id {
-                            return Err(SemanticError::EOrB2CPosLesser);
-                        }
-                        if *a_or_b >= id {
-                            return Err(SemanticError::EOrAOBPosLesser);
-                        }
-                        // Since we checked the ids its ok to retrieve the corresponding records.
-                        let a_to_c = &self.records[*a_to_c];
-                        let b_to_c = &self.records[*b_to_c];
-                        let a_or_b = &self.records[*a_or_b];
-                        // Checking usability of those records
-                        if !check_ctxt_compatibility(&rec.ctxt, &a_to_c.ctxt) {
-                            return Err(SemanticError::EOrA2CIncompatibleCtxt);
-                        }
-                        if !check_ctxt_compatibility(&rec.ctxt, &b_to_c.ctxt) {
-                            return Err(SemanticError::EOrB2CIncompatibleCtxt);
-                        }
-                        if !check_ctxt_compatibility(&rec.ctxt, &a_or_b.ctxt) {
-                            return Err(SemanticError::EOrAOBIncompatibleCtxt);
-                        }
-                        // Checking the constructed formula is correct
-                        if let (
-                            Formula::Implies(atc_a, atc_c),
-                            Formula::Implies(btc_b, btc_c),
-                            Formula::Or(aob_a, aob_b),
-                        ) = (
-                            a_to_c.stmt.get_formula(),
-                            b_to_c.stmt.get_formula(),
-                            a_or_b.stmt.get_formula(),
-                        ) {
-                            if aob_a != atc_a {
-                                Err(SemanticError::EOrAFormulaNotMatching)
-                            } else if aob_b != btc_b {
-                                Err(SemanticError::EOrBFormulaNotMatching)
-                            } else if atc_c != btc_c {
-                                Err(SemanticError::EOrCFormulaNotMatchingConsequences)
-                            } else if atc_c.as_ref() != formula {
-                                Err(SemanticError::EOrCFormulaNotMatchingEliminated)
-                            } else {
-                                Ok(())
-                            }
-                        } else {
-                            Err(SemanticError::EOrFormulasNotRightKind)
-                        }
-                    }
-                    Jusitification::IAnd { left, right } => {
-                        // Checking ids
-                        if *left >= id {
-                            return Err(SemanticError::IAndLeftPosLesser);
-                        }
-                        if *right >= id {
-                            return Err(SemanticError::IAndRightPosLesser);
-                        }
-                        // Since we checked the ids its ok to retrieve the corresponding records.
-                        let left = &self.records[*left];
-                        let right = &self.records[*right];
-                        // Checking usability of left and right
-                        if !check_ctxt_compatibility(&rec.ctxt, &left.ctxt) {
-                            return Err(SemanticError::IAndLeftIncompatibleCtxt);
-                        }
-                        if !check_ctxt_compatibility(&rec.ctxt, &right.ctxt) {
-                            return Err(SemanticError::IAndRightIncompatibleCtxt);
-                        }
-
-                        // Checking the constructed formula is correct
-                        match formula {
-                            Formula::And(left_formula, right_formula) => {
-                                if **left_formula != *left.stmt.get_formula() {
-                                    Err(SemanticError::IAndLeftNotMatching)
-                                } else if **right_formula != *right.stmt.get_formula() {
-                                    Err(SemanticError::IAndRightNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            _ => Err(SemanticError::IAndFormulaIsAnd),
-                        }
-                    }
-                    Jusitification::EAndL(and_pos) => {
-                        if *and_pos >= id {
-                            return Err(SemanticError::EAndPosLesser);
-                        }
-                        let and = &self.records[*and_pos];
-                        if !check_ctxt_compatibility(&rec.ctxt, &and.ctxt) {
-                            return Err(SemanticError::EAndIncompatibleCtxt);
-                        }
-                        match and.stmt.get_formula() {
-                            Formula::And(left_formula, _) => {
-                                if **left_formula != *formula {
-                                    Err(SemanticError::EAndNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            _ => Err(SemanticError::EAndFormulaIsAnd),
-                        }
-                    }
-                    Jusitification::EAndR(and_pos) => {
-                        if *and_pos >= id {
-                            return Err(SemanticError::EAndPosLesser);
-                        }
-                        let and = &self.records[*and_pos];
-                        if !check_ctxt_compatibility(&rec.ctxt, &and.ctxt) {
-                            return Err(SemanticError::EAndIncompatibleCtxt);
-                        }
-                        match and.stmt.get_formula() {
-                            Formula::And(_, right_formula) => {
-                                if **right_formula != *formula {
-                                    Err(SemanticError::EAndNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            }
-                            _ => Err(SemanticError::EAndFormulaIsAnd),
-                        }
-                    }
-                    Jusitification::Hyp => Err(SemanticError::HypNotSimple),
-                    Jusitification::IImpl => Err(SemanticError::IImplNotSimple),
-                    Jusitification::EImpl { hyp, implication } => {
-                        if *hyp >= id {
-                            return Err(SemanticError::EImplHypPosLesser);
-                        }
-                        if *implication >= id {
-                            return Err(SemanticError::EImplImplPosLesser);
-                        }
-                        let hyp = &self.records[*hyp];
-                        let implication = &self.records[*implication];
-                        if !check_ctxt_compatibility(&rec.ctxt, &hyp.ctxt) {
-                            return Err(SemanticError::EImplHypIncompatibleCtxt);
-                        }
-                        if !check_ctxt_compatibility(&rec.ctxt, &implication.ctxt) {
-                            return Err(SemanticError::EImplImplIncompatibleCtxt);
-                        }
-                        if let Formula::Implies(i_hyp, i_cons) = implication.stmt.get_formula() {
-                            if **i_hyp != *hyp.stmt.get_formula() {
-                                Err(SemanticError::EImplHypNotMatching)
-                            } else if **i_cons != *formula {
-                                Err(SemanticError::EImplImplNotMatching)
-                            } else {
-                                Ok(())
-                            }
-                        } else {
-                            Err(SemanticError::EImplFormulaIsImpl)
-                        }
-                    }
-                    Jusitification::Efq(bot_pos) => {
-                        if *bot_pos >= id {
-                            return Err(SemanticError::EfqPosLesser);
-                        }
-                        let bot = &self.records[*bot_pos];
-                        if !check_ctxt_compatibility(&rec.ctxt, &bot.ctxt) {
-                            return Err(SemanticError::EfqIncompatibleCtxt);
-                        }
-                        if !matches!(bot.stmt.get_formula(), Formula::Bottom) {
-                            return Err(SemanticError::EfqFormulaIsBot);
-                        }
-                        Ok(())
-                    }
-                    Jusitification::Raa(nn_pos) => {
-                        if *nn_pos >= id {
-                            return Err(SemanticError::RaaPosLesser);
-                        }
-                        let nn = &self.records[*nn_pos];
-                        if !check_ctxt_compatibility(&rec.ctxt, &nn.ctxt) {
-                            return Err(SemanticError::RaaIncompatibleCtxt);
-                        }
-                        if let Formula::Not(n_formula) = nn.stmt.get_formula() {
-                            if let Formula::Not(nn_formula) = &**n_formula {
-                                if **nn_formula != *formula {
-                                    Err(SemanticError::RaaNotMatching)
-                                } else {
-                                    Ok(())
-                                }
-                            } else {
-                                Err(SemanticError::RaaFormulaIsNotNot)
-                            }
-                        } else {
-                            Err(SemanticError::RaaFormulaIsNotNot)
-                        }
-                    }
-                    Jusitification::Rwrt(orig_pos) => {
-                        if *orig_pos >= id {
-                            return Err(SemanticError::RwrtPosLesser);
-                        }
-                        let orig = &self.records[*orig_pos];
-                        if !check_ctxt_compatibility(&rec.ctxt, &orig.ctxt) {
-                            return Err(SemanticError::RwrtIncompatibleCtxt);
-                        }
-                        if formula.clone().normalize() == orig.stmt.get_formula().clone().normalize() {
-                            Ok(())
-                        } else {
-                            Err(SemanticError::RwrtNotMatching)
-                        }
+                match self.rules.get(rec.justif.name()) {
+                    Some(rule) => {
+                        self.check_citations(rec)?;
+                        rule.check(self, rec)
                     }
+                    None => Err(SemanticError::DerivedUnknown(rec.justif.name().to_string())),
                 }
             }
         }
     }
+
+    /// Checks the earlier records `rec` cites state a formula: the formula of a `Soit` or a
+    /// `Monde` is ⊤ only for want of another one. The rules check the other references.
+    fn check_citations(&self, rec: &Record) -> Result<(), SemanticError> {
+        let cited = rec.justif.references().into_iter().filter(|r| *r < rec.id);
+        for r in cited {
+            if matches!(self.records[r].stmt, Statement::Soit(_) | Statement::Monde) {
+                return Err(SemanticError::CitesNoFormula(r));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the hypothesis and the consequence of the box the `Donc` record `id` closes,
+    /// checking the record follows the consequence, out of its box.
+    pub fn closed_box(&self, id: usize) -> Result<(&Record, &Record), SemanticError> {
+        let rec = self.records.get(id).ok_or(SemanticError::InternalError)?;
+        if id == 0 {
+            return Err(SemanticError::DoncNotFirst);
+        }
+        let cons = &self.records[id - 1];
+        if cons.ctxt.len() != rec.ctxt.len() + 1 {
+            return Err(SemanticError::DoncCtxtOneLessThenBefore);
+        }
+        if cons.ctxt[0..rec.ctxt.len()] != rec.ctxt {
+            return Err(SemanticError::DoncCtxtSameAsBefore);
+        }
+
+        let hyp_pos = cons.ctxt[cons.ctxt.len() - 1];
+
+        // We ensure the hypothesis was formulated before the current record,
+        // furthermore we want the consequence record to be distinct from the
+        // record.
+        if hyp_pos + 2 > id {
+            return Err(SemanticError::DoncHypDifCons);
+        }
+        Ok((&self.records[hyp_pos], cons))
+    }
 }
 
-fn check_ctxt_compatibility(compatible: &[usize], current: &[usize]) -> bool {
+impl Proof {
+    /// Tells whether a record in context `current` may use a record in context `reference`.
+    /// Besides the contexts being compatible, no `Monde` may be opened in between: the other
+    /// world is only reached through `ENec` and `RPos`.
+    pub fn ctxt_compatible(&self, current: &[usize], reference: &[usize]) -> bool {
+        check_ctxt_compatibility(current, reference)
+            && self.modal_depth(&current[reference.len()..]) == 0
+    }
+
+    /// Tells whether a record in context `current` may use the record `reference`, from a
+    /// [compatible](Proof::ctxt_compatible) context. A premise, out of every box, may thus be
+    /// used from every box but a `Monde` one: a theorem is a local consequence, its premises
+    /// only holding in the world of its goal.
+    pub fn accessible(&self, current: &[usize], reference: &Record) -> bool {
+        self.ctxt_compatible(current, &reference.ctxt)
+    }
+
+    /// Counts the `Monde` boxes among the boxes of `ctxt`.
+    pub(crate) fn modal_depth(&self, ctxt: &[usize]) -> usize {
+        ctxt.iter()
+            .filter(|b| matches!(self.records.get(**b), Some(Record { stmt: Statement::Monde, .. })))
+            .count()
+    }
+}
+
+impl fmt::Display for Proof {
+    /// Prints the proof in the syntax accepted by [`Proof::read_proof`], one record per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(theorem) = &self.theorem {
+            writeln!(f, "Théorème {}", theorem)?;
+        }
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn check_ctxt_compatibility(compatible: &[usize], current: &[usize]) -> bool {
     if current.len() > compatible.len() {
         false
     } else {
         current == &compatible[0..current.len()]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &str) -> Proof {
+        Proof::read_proof(input).unwrap()
+    }
+
+    #[test]
+    fn empty_proofs() {
+        for input in ["", "\n\n", "(* nothing *)\n", "Théorème ⊢ ⊤\n"] {
+            let mut proof = read(input);
+            proof.check();
+            assert!(
+                matches!(
+                    proof.state(),
+                    CheckUpResult::SemanticErrors { errors, .. }
+                        if matches!(errors[..], [(0, SemanticError::EmptyProof)])
+                ),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn example_proofs() {
+        for input in [
+            include_str!("../proofs/first.proof"),
+            include_str!("../proofs/third.proof"),
+            include_str!("../proofs/fourth.proof"),
+            include_str!("../proofs/fifth.proof"),
+            include_str!("../proofs/sixth.proof"),
+        ] {
+            let mut proof = read(input);
+            proof.check();
+            assert!(matches!(proof.state(), CheckUpResult::Valid));
+        }
+        // IOrR 0 b introduces b∨b, not a∨b.
+        let mut proof = read(include_str!("../proofs/second.proof"));
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn intuitionistic_rejects_raa() {
+        let mut proof = read(include_str!("../proofs/third.proof"));
+        proof.set_logic(Logic::Intuitionistic);
+        proof.check();
+        match proof.state() {
+            CheckUpResult::SemanticErrors { first_error, errors } => {
+                assert_eq!(*first_error, 18);
+                assert_eq!(errors.len(), 1);
+                assert!(matches!(errors[0].1, SemanticError::RaaNotIntuitionistic));
+            }
+            _ => panic!("Raa should be rejected"),
+        }
+    }
+
+    #[test]
+    fn minimal_rejects_efq() {
+        let input = "0;0;Supposons ⊥;Hyp\n1;0;a;Efq 0\n2;;Donc ⊥⇒a;IImpl";
+        let mut proof = read(input);
+        proof.set_logic(Logic::Intuitionistic);
+        proof.check();
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        proof.set_logic(Logic::Minimal);
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+    }
+
+    fn first_error(input: &str) -> Option<usize> {
+        let mut proof = read(input);
+        proof.check();
+        match proof.into_state() {
+            CheckUpResult::SemanticErrors { first_error, .. } => Some(first_error),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn negation_equivalence_and_top() {
+        let input = "0;0;Supposons a⇔b;Hyp
+1;0;a⇒b;EEquivL 0
+2;0;b⇒a;EEquivR 0
+3;0;b⇔a;IEquiv 2 1
+4;0;⊤;ITop
+5;0;(b⇔a)∧⊤;IAnd 3 4
+6;;Donc (a⇔b)⇒(b⇔a)∧⊤;IImpl";
+        assert_eq!(first_error(input), None);
+        // The implications of IEquiv are swapped.
+        assert_eq!(first_error(&input.replace("IEquiv 2 1", "IEquiv 1 2")), Some(3));
+        assert_eq!(first_error(&input.replace("EEquivL", "EEquivR")), Some(1));
+        assert_eq!(first_error(&input.replace("⊤;ITop", "a;ITop")), Some(4));
+
+        let input = "0;0;Supposons a;Hyp
+1;0,1;Supposons ¬a;Hyp
+2;0,1;⊥;ENot 0 1
+3;0;Donc ¬¬a;INot";
+        assert_eq!(first_error(input), None);
+        assert_eq!(first_error(&input.replace("ENot 0 1", "ENot 1 0")), Some(2));
+        assert_eq!(first_error(&input.replace("Donc ¬¬a", "Donc ¬a")), Some(3));
+        // INot needs ⊥ as the consequence.
+        let input = "0;0;Supposons a;Hyp\n1;0,1;Supposons b;Hyp\n2;0,1;a;Rwrt 0\n3;0;Donc ¬b;INot";
+        assert_eq!(first_error(input), Some(3));
+    }
+
+    #[test]
+    fn boxes_state_no_formula() {
+        let errors = |input: &str| {
+            let mut proof = read(input);
+            proof.check();
+            match proof.into_state() {
+                CheckUpResult::SemanticErrors { errors, .. } => errors,
+                _ => Vec::new(),
+            }
+        };
+        // The formula of a Soit or a Monde is not ⊤
+        let soit = "0;0;Soit x;Hyp\n1;0;⊤;Rwrt 0\n2;;Donc ∀x⊤;IForall\n";
+        assert!(matches!(
+            errors(soit)[..],
+            [(1, SemanticError::CitesNoFormula(0))]
+        ));
+        assert!(errors(&soit.replace("Rwrt 0", "ITop")).is_empty());
+        let monde = "0;0;Monde;Hyp\n1;0;⊤∨a;IOrR 0 a\n2;;Donc □(⊤∨a);INec\n";
+        assert!(matches!(
+            errors(monde)[..],
+            [(1, SemanticError::CitesNoFormula(0))]
+        ));
+        // Whatever the rule, as one declared by a schema
+        let monde = "0;0;Monde;Hyp\n1;0;⊤∧⊤;IAnd 0 0\n2;;Donc □(⊤∧⊤);INec\n";
+        assert!(matches!(
+            errors(monde)[..],
+            [(1, SemanticError::CitesNoFormula(0))]
+        ));
+    }
+
+    #[test]
+    fn exists_elimination() {
+        let input = "0;0;Supposons ∃xP(x);Hyp
+1;0,1;Soit y;Hyp
+2;0,1,2;Supposons P(y);Hyp
+3;0,1,2;P(y)∨Q(y);IOrR 2 Q(y)
+4;0,1,2;∃x(P(x)∨Q(x));IExists 3 y
+5;0,1;Donc P(y)⇒∃x(P(x)∨Q(x));IImpl
+6;0;Donc ∀y(P(y)⇒∃x(P(x)∨Q(x)));IForall
+7;0;∃x(P(x)∨Q(x));EExists 0 6
+8;;Donc ∃xP(x)⇒∃x(P(x)∨Q(x));IImpl";
+        assert_eq!(first_error(input), None);
+    }
+
+    #[test]
+    fn eigenvariable_conditions() {
+        // y is free in the open hypothesis.
+        let input = "0;0;Supposons P(y);Hyp
+1;0,1;Soit y;Hyp
+2;0,1;P(y);EAndL 0
+3;0;Donc ∀yP(y);IForall";
+        assert_eq!(first_error(input), Some(1));
+        // The variable escapes in the conclusion.
+        let input = "0;0;Supposons ∀xR(x,x);Hyp
+1;0,1;Soit y;Hyp
+2;0,1;R(y,y);EForall 0 y
+3;0;Donc ∀zR(z,y);IForall
+4;;Donc ∀xR(x,x)⇒∀zR(z,y);IImpl";
+        assert_eq!(first_error(input), Some(3));
+        // The witness of an Exists may not leave its box.
+        let input = "0;0;Supposons ∃xP(x);Hyp
+1;0,1;Soit y;Hyp
+2;0,1,2;Supposons P(y);Hyp
+3;0,1,2;P(y);Rwrt 2
+4;0,1;Donc P(y)⇒P(y);IImpl
+5;0;Donc ∀y(P(y)⇒P(y));IForall
+6;0;P(y);EExists 0 5
+7;;Donc ∃xP(x)⇒P(y);IImpl";
+        assert_eq!(first_error(input), Some(6));
+        // y is free in a premise, P(y) ⊢ ∀yP(y) does not hold.
+        let input = "0;;Prémisse P(y);Hyp
+1;1;Soit y;Hyp
+2;1;P(y);Rwrt 0
+3;;Donc ∀yP(y);IForall";
+        assert_eq!(first_error(input), Some(1));
+        assert_eq!(first_error(&input.replace("P(y)", "P(z)")), None);
+    }
+
+    #[test]
+    fn instantiation_avoids_capture() {
+        let input = "0;0;Supposons ∀x∃yR(x,y);Hyp
+1;0;∃yR(y,y);EForall 0 y
+2;;Donc ∀x∃yR(x,y)⇒∃yR(y,y);IImpl";
+        assert_eq!(first_error(input), Some(1));
+        let input = "0;0;Supposons ∀x∃yR(x,y);Hyp
+1;0;∃zR(y,z);EForall 0 y
+2;;Donc ∀x∃yR(x,y)⇒∃zR(y,z);IImpl";
+        assert_eq!(first_error(input), None);
+    }
+
+    fn first_error_in(input: &str, modal_system: ModalSystem) -> Option<usize> {
+        let mut proof = read(input);
+        proof.set_modal_system(modal_system);
+        proof.check();
+        match proof.into_state() {
+            CheckUpResult::SemanticErrors { first_error, .. } => Some(first_error),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn modal_systems() {
+        let t = "0;0;Supposons □A;Hyp
+1;0;A;ENec 0
+2;;Donc □A⇒A;IImpl";
+        let s4 = "0;0;Supposons □A;Hyp
+1;0,1;Monde;Hyp
+2;0,1,2;Monde;Hyp
+3;0,1,2;A;ENec 0
+4;0,1;Donc □A;INec
+5;0;Donc □□A;INec
+6;;Donc □A⇒□□A;IImpl";
+        let s5 = "0;0;Supposons ◇A;Hyp
+1;0,1;Monde;Hyp
+2;0,1;◇A;RPos 0
+3;0;Donc □◇A;INec
+4;;Donc ◇A⇒□◇A;IImpl";
+        let systems = [ModalSystem::K, ModalSystem::T, ModalSystem::S4, ModalSystem::S5];
+        for (input, weakest, error) in [(t, 1, 1), (s4, 2, 3), (s5, 3, 2)] {
+            for (i, system) in systems.into_iter().enumerate() {
+                let expected = if i < weakest { Some(error) } else { None };
+                assert_eq!(first_error_in(input, system), expected, "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn monde_is_opaque() {
+        // A only holds in the current world.
+        let input = "0;0;Supposons A;Hyp
+1;0,1;Monde;Hyp
+2;0,1;A∧A;IAnd 0 0
+3;0;Donc □(A∧A);INec
+4;;Donc A⇒□(A∧A);IImpl";
+        assert_eq!(first_error_in(input, ModalSystem::S5), Some(2));
+        // A Monde is closed by INec only.
+        let input = "0;0;Monde;Hyp
+1;0;⊤;ITop
+2;;Donc ⊤⇒⊤;IImpl";
+        assert_eq!(first_error_in(input, ModalSystem::K), Some(2));
+    }
+
+    #[test]
+    fn classical_dependencies_of_third() {
+        let proof = read(include_str!("../proofs/third.proof"));
+        let deps = proof.classical_dependencies();
+        for (id, dep) in deps.iter().enumerate() {
+            if id < 18 {
+                assert!(dep.is_empty(), "record {} is constructive", id);
+            } else {
+                assert_eq!(dep.iter().copied().collect::<Vec<_>>(), vec![18]);
+            }
+        }
+    }
+}
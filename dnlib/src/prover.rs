@@ -0,0 +1,725 @@
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::formula::Formula;
+use crate::justif::Jusitification;
+use crate::proof::{Logic, Proof};
+use crate::record::{Record, Statement};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProverError {
+    /// The prover only handles propositional formulas
+    #[error("The formula is not propositional")]
+    NotPropositional,
+    /// The search is complete, so finding no proof means a valuation makes every premise
+    /// true and the goal false
+    #[error("The goal does not follow from the premises")]
+    NotValid,
+    /// The search did not finish in time
+    #[error("No proof was found in time")]
+    TimedOut,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum GapError {
+    /// Only a formula can be derived, not a box
+    #[error("A ? placeholder has to justify a formula, not a Supposons, Soit, Monde or Donc")]
+    NotSimple,
+    /// The accessible records do not entail the formula, or not propositionally
+    #[error("The formula could not be derived from the records it may use")]
+    NotFound,
+    /// The search did not finish in time
+    #[error("No derivation was found in time")]
+    TimedOut,
+}
+
+/// Why the search for a sub-proof stopped.
+enum Failure {
+    NotFound,
+    TimedOut,
+}
+
+/// The formulas known to hold, with the record stating them.
+type Facts = Vec<(Formula, usize)>;
+
+fn find(facts: &Facts, formula: &Formula) -> Option<usize> {
+    facts.iter().find(|(f, _)| f == formula).map(|(_, id)| *id)
+}
+
+fn implies(a: Formula, b: Formula) -> Formula {
+    Formula::Implies(Box::new(a), Box::new(b))
+}
+
+/// Rewrites the formula with ⊥, ∧, ∨ and ⇒ only. Both formulas have the same normal form, so
+/// `Rwrt` goes from one to the other.
+fn core(formula: &Formula) -> Formula {
+    match formula {
+        Formula::Top => implies(Formula::Bottom, Formula::Bottom),
+        Formula::Not(a) => implies(core(a), Formula::Bottom),
+        Formula::And(a, b) => Formula::And(Box::new(core(a)), Box::new(core(b))),
+        Formula::Or(a, b) => Formula::Or(Box::new(core(a)), Box::new(core(b))),
+        Formula::Implies(a, b) => implies(core(a), core(b)),
+        Formula::RLImplies(a, b) => implies(core(b), core(a)),
+        Formula::Equiv(a, b) => Formula::And(
+            Box::new(implies(core(a), core(b))),
+            Box::new(implies(core(b), core(a))),
+        ),
+        f => f.clone(),
+    }
+}
+
+/// Looks for a natural-deduction proof of `goal` assuming `premises`, within `timeout`.
+///
+/// The premises are opened as `Supposons` boxes that stay open, the last record being the
+/// goal. The search is complete for intuitionistic logic; when the goal only holds
+/// classically, the proof assumes `¬goal`, derives ⊥ intuitionistically and concludes with
+/// `Raa`.
+pub fn prove(
+    premises: &[Formula],
+    goal: &Formula,
+    timeout: Duration,
+) -> Result<Proof, ProverError> {
+    if !goal.is_propositional() || !premises.iter().all(Formula::is_propositional) {
+        return Err(ProverError::NotPropositional);
+    }
+    let mut search = Search::new(
+        Vec::new(),
+        Vec::new(),
+        Logic::Classical,
+        Instant::now() + timeout,
+    );
+    let facts = search.assume(premises);
+    match search.establish(facts, goal, 0) {
+        Ok(()) => Ok(Proof::from_records(prune(search.records, 0))),
+        Err(Failure::TimedOut) => Err(ProverError::TimedOut),
+        // Glivenko's theorem: cannot happen for a classically valid goal
+        Err(Failure::NotFound) => Err(ProverError::NotValid),
+    }
+}
+
+/// Removes the records from `from` on that the last one does not depend on: the search derives
+/// facts it does not always use. Whole boxes go away, so the remaining records still form a
+/// proof.
+pub(crate) fn prune(records: Vec<Record>, from: usize) -> Vec<Record> {
+    let last = records.len() - 1;
+    let mut needed: Vec<bool> = (0..records.len()).map(|id| id < from).collect();
+    needed[last] = true;
+    for open in &records[last].ctxt {
+        needed[*open] = true;
+    }
+    for id in (from..=last).rev() {
+        if !needed[id] {
+            continue;
+        }
+        let rec = &records[id];
+        match (&rec.stmt, &rec.justif) {
+            (Statement::Donc(_), justif) => {
+                needed[id - 1] = true;
+                needed[*records[id - 1].ctxt.last().unwrap()] = true;
+                for premise in justif.references() {
+                    needed[premise] = true;
+                }
+            }
+            (_, justif) => {
+                for premise in justif.references() {
+                    needed[premise] = true;
+                }
+            }
+        }
+    }
+    let mut renumbered = vec![0; records.len()];
+    let mut pruned = Vec::new();
+    for mut rec in records.into_iter().filter(|r| needed[r.id]) {
+        renumbered[rec.id] = pruned.len();
+        rec.id = pruned.len();
+        rec.ctxt.iter_mut().for_each(|b| *b = renumbered[*b]);
+        rec.justif.renumber(|i| renumbered[i]);
+        pruned.push(rec);
+    }
+    pruned
+}
+
+/// A proof being searched for. Records are written as the search goes, and removed when a
+/// branch of the search fails.
+struct Search {
+    records: Vec<Record>,
+    /// The open boxes
+    ctxt: Vec<usize>,
+    /// Decides whether `Efq` and `Raa` may be used
+    logic: Logic,
+    deadline: Instant,
+}
+
+impl Search {
+    /// Continues `records` in the box `ctxt`, with the rules of `logic`.
+    fn new(records: Vec<Record>, ctxt: Vec<usize>, logic: Logic, deadline: Instant) -> Self {
+        Self {
+            records,
+            ctxt,
+            logic,
+            deadline,
+        }
+    }
+
+    fn push(&mut self, stmt: Statement, justif: Jusitification) -> usize {
+        let id = self.records.len();
+        self.records.push(Record {
+            id,
+            ctxt: self.ctxt.clone(),
+            stmt,
+            justif,
+        });
+        id
+    }
+
+    /// Opens a box assuming `hyp`.
+    fn open(&mut self, hyp: Formula) -> usize {
+        self.ctxt.push(self.records.len());
+        self.push(Statement::Supposons(hyp), Jusitification::Hyp)
+    }
+
+    /// Closes the innermost box, whose last record is the consequence of `implication`.
+    fn close(&mut self, implication: Formula) -> usize {
+        self.ctxt.pop();
+        self.push(Statement::Donc(implication), Jusitification::IImpl)
+    }
+
+    fn derive(&mut self, formula: Formula, justif: Jusitification) -> usize {
+        self.push(Statement::Simple(formula), justif)
+    }
+
+    /// Restates the formula of `id` with only ⊥, ∧, ∨ and ⇒, if it has other connectives.
+    fn rewrite(&mut self, id: usize, formula: &Formula) -> usize {
+        let rewritten = core(formula);
+        if rewritten == *formula {
+            id
+        } else {
+            self.derive(rewritten, Jusitification::Rwrt(id))
+        }
+    }
+
+    /// Makes `formula`, stated at `id`, the last record, written at `since` or later, so that
+    /// it can end the box whose hypothesis is `since - 1`, fill a gap or end the proof.
+    fn conclude(&mut self, id: usize, formula: &Formula, since: usize) {
+        let stated = self.records[id].stmt.get_formula() == formula;
+        if !stated || id + 1 != self.records.len() || id < since {
+            self.derive(formula.clone(), Jusitification::Rwrt(id));
+        }
+    }
+
+    /// Derives `goal` from `facts` as the last record, written at `since` or later. In
+    /// classical logic, a goal with no intuitionistic proof is reached by assuming its
+    /// negation: ⊥ then follows intuitionistically, and `Raa` concludes.
+    fn establish(&mut self, mut facts: Facts, goal: &Formula, since: usize) -> Result<(), Failure> {
+        let mark = self.records.len();
+        match self.prove(facts.clone(), &core(goal)) {
+            Ok(id) => {
+                self.conclude(id, goal, since);
+                return Ok(());
+            }
+            Err(Failure::NotFound) if self.logic == Logic::Classical => self.backtrack(mark),
+            Err(failure) => return Err(failure),
+        }
+        let not_goal = Formula::Not(Box::new(goal.clone()));
+        let hyp = self.open(not_goal.clone());
+        let core_hyp = self.rewrite(hyp, &not_goal);
+        facts.push((core(&not_goal), core_hyp));
+        let id = self.prove(facts, &Formula::Bottom)?;
+        self.conclude(id, &Formula::Bottom, hyp + 1);
+        let absurd = self.close(implies(not_goal.clone(), Formula::Bottom));
+        let not_not_goal = Formula::Not(Box::new(not_goal));
+        let not_not = self.derive(not_not_goal, Jusitification::Rwrt(absurd));
+        self.derive(goal.clone(), Jusitification::Raa(not_not));
+        Ok(())
+    }
+
+    /// Opens a box for each premise.
+    fn assume(&mut self, premises: &[Formula]) -> Facts {
+        premises
+            .iter()
+            .map(|premise| {
+                let hyp = self.open(premise.clone());
+                (core(premise), self.rewrite(hyp, premise))
+            })
+            .collect()
+    }
+
+    /// Adds `formula` to the facts, deriving it with `justif` unless it is already known.
+    fn learn(&mut self, facts: &mut Facts, formula: Formula, justif: Jusitification) {
+        if find(facts, &formula).is_none() {
+            let id = self.derive(formula.clone(), justif);
+            facts.push((formula, id));
+        }
+    }
+
+    /// Proves `hyp⇒goal` in a box, from `facts` and the hypothesis.
+    fn prove_implication(
+        &mut self,
+        mut facts: Facts,
+        hyp: &Formula,
+        goal: &Formula,
+    ) -> Result<usize, Failure> {
+        let h = self.open(hyp.clone());
+        facts.push((hyp.clone(), h));
+        let id = self.prove(facts, goal)?;
+        self.conclude(id, goal, h + 1);
+        Ok(self.close(implies(hyp.clone(), goal.clone())))
+    }
+
+    /// Applies a left rule that loses no provability, replacing a fact by simpler ones.
+    /// Returns `false` if there is none.
+    fn simplify(&mut self, facts: &mut Facts) -> bool {
+        // Without Efq, ⊥ is an atom like any other
+        let efq = self.logic != Logic::Minimal;
+        let position = facts.iter().position(|(f, _)| match f {
+            Formula::And(_, _) => true,
+            Formula::Implies(a, _) => match a.as_ref() {
+                Formula::Bottom if efq => true,
+                Formula::And(_, _) | Formula::Or(_, _) => true,
+                a => find(facts, a).is_some(),
+            },
+            _ => false,
+        });
+        let Some(position) = position else {
+            return false;
+        };
+        let (fact, id) = facts.remove(position);
+        match fact {
+            Formula::And(a, b) => {
+                self.learn(facts, *a, Jusitification::EAndL(id));
+                self.learn(facts, *b, Jusitification::EAndR(id));
+            }
+            Formula::Implies(a, b) => match *a {
+                // ⊥⇒B is useless
+                Formula::Bottom if efq => (),
+                // (C∧D)⇒B becomes C⇒(D⇒B)
+                Formula::And(c, d) => {
+                    let curried = implies(*c.clone(), implies(*d.clone(), *b.clone()));
+                    if find(facts, &curried).is_none() {
+                        let hc = self.open(*c.clone());
+                        let hd = self.open(*d.clone());
+                        let cd = self.derive(
+                            Formula::And(c, d.clone()),
+                            Jusitification::IAnd {
+                                left: hc,
+                                right: hd,
+                            },
+                        );
+                        self.derive(
+                            *b.clone(),
+                            Jusitification::EImpl {
+                                hyp: cd,
+                                implication: id,
+                            },
+                        );
+                        self.close(implies(*d, *b));
+                        let curried_id = self.close(curried.clone());
+                        facts.push((curried, curried_id));
+                    }
+                }
+                // (C∨D)⇒B becomes C⇒B and D⇒B
+                Formula::Or(c, d) => {
+                    for left in [true, false] {
+                        let (side, other) = if left { (&c, &d) } else { (&d, &c) };
+                        let side_implication = implies(*side.clone(), *b.clone());
+                        if find(facts, &side_implication).is_some() {
+                            continue;
+                        }
+                        let h = self.open(*side.clone());
+                        let justif = if left {
+                            Jusitification::IOrR(h, *other.clone())
+                        } else {
+                            Jusitification::IOrL(h, *other.clone())
+                        };
+                        let or = self.derive(Formula::Or(c.clone(), d.clone()), justif);
+                        self.derive(
+                            *b.clone(),
+                            Jusitification::EImpl {
+                                hyp: or,
+                                implication: id,
+                            },
+                        );
+                        let side_id = self.close(side_implication.clone());
+                        facts.push((side_implication, side_id));
+                    }
+                }
+                // A⇒B with A known gives B
+                a => {
+                    let hyp = find(facts, &a).unwrap();
+                    self.learn(
+                        facts,
+                        *b,
+                        Jusitification::EImpl {
+                            hyp,
+                            implication: id,
+                        },
+                    );
+                }
+            },
+            _ => unreachable!(),
+        }
+        true
+    }
+
+    /// Proves `goal` from `facts`, in the current box, following Dyckhoff's contraction-free
+    /// sequent calculus LJT (also known as G4ip), which needs no loop check. Returns the
+    /// record stating the goal.
+    fn prove(&mut self, mut facts: Facts, goal: &Formula) -> Result<usize, Failure> {
+        if Instant::now() > self.deadline {
+            return Err(Failure::TimedOut);
+        }
+        if let Some(id) = find(&facts, goal) {
+            return Ok(id);
+        }
+        let absurd = find(&facts, &Formula::Bottom).filter(|_| self.logic != Logic::Minimal);
+        if let Some(id) = absurd {
+            return Ok(self.derive(goal.clone(), Jusitification::Efq(id)));
+        }
+        if self.simplify(&mut facts) {
+            return self.prove(facts, goal);
+        }
+        match goal {
+            Formula::Implies(a, b) => return self.prove_implication(facts, a, b),
+            Formula::And(a, b) => {
+                let left = self.prove(facts.clone(), a)?;
+                let right = self.prove(facts, b)?;
+                return Ok(self.derive(goal.clone(), Jusitification::IAnd { left, right }));
+            }
+            _ => (),
+        }
+        if let Some(position) = facts
+            .iter()
+            .position(|(f, _)| matches!(f, Formula::Or(_, _)))
+        {
+            let (or, a_or_b) = facts.remove(position);
+            let Formula::Or(a, b) = or else {
+                unreachable!()
+            };
+            let a_to_c = self.prove_implication(facts.clone(), &a, goal)?;
+            let b_to_c = self.prove_implication(facts, &b, goal)?;
+            return Ok(self.derive(
+                goal.clone(),
+                Jusitification::EOr {
+                    a_to_c,
+                    b_to_c,
+                    a_or_b,
+                },
+            ));
+        }
+        // The remaining rules may fail, the records they wrote are then removed.
+        let mark = self.records.len();
+        if let Formula::Or(a, b) = goal {
+            match self.prove(facts.clone(), a) {
+                Ok(id) => {
+                    return Ok(self.derive(goal.clone(), Jusitification::IOrR(id, *b.clone())))
+                }
+                Err(Failure::TimedOut) => return Err(Failure::TimedOut),
+                Err(Failure::NotFound) => self.backtrack(mark),
+            }
+            match self.prove(facts.clone(), b) {
+                Ok(id) => {
+                    return Ok(self.derive(goal.clone(), Jusitification::IOrL(id, *a.clone())))
+                }
+                Err(Failure::TimedOut) => return Err(Failure::TimedOut),
+                Err(Failure::NotFound) => self.backtrack(mark),
+            }
+        }
+        for position in 0..facts.len() {
+            let (Formula::Implies(cd, b), id) = &facts[position] else {
+                continue;
+            };
+            let Formula::Implies(c, d) = cd.as_ref() else {
+                continue;
+            };
+            let (c, d, b, id) = (*c.clone(), *d.clone(), *b.clone(), *id);
+            match self.nested_implication(facts.clone(), position, (c, d, b, id), goal) {
+                Err(Failure::NotFound) => self.backtrack(mark),
+                result => return result,
+            }
+        }
+        Err(Failure::NotFound)
+    }
+
+    /// Uses the fact `(C⇒D)⇒B` at `position`, proving `C⇒D` from `D⇒B`, then `goal` from `B`.
+    fn nested_implication(
+        &mut self,
+        mut facts: Facts,
+        position: usize,
+        (c, d, b, id): (Formula, Formula, Formula, usize),
+        goal: &Formula,
+    ) -> Result<usize, Failure> {
+        facts.remove(position);
+        // D⇒B follows from (C⇒D)⇒B
+        let hd = self.open(d.clone());
+        self.open(c.clone());
+        self.derive(d.clone(), Jusitification::Rwrt(hd));
+        let c_to_d = self.close(implies(c.clone(), d.clone()));
+        self.derive(
+            b.clone(),
+            Jusitification::EImpl {
+                hyp: c_to_d,
+                implication: id,
+            },
+        );
+        let d_to_b = self.close(implies(d.clone(), b.clone()));
+        let mut weakened = facts.clone();
+        weakened.push((implies(d.clone(), b.clone()), d_to_b));
+        let c_to_d = self.prove(weakened, &implies(c, d))?;
+        let b_id = self.derive(
+            b.clone(),
+            Jusitification::EImpl {
+                hyp: c_to_d,
+                implication: id,
+            },
+        );
+        facts.push((b, b_id));
+        self.prove(facts, goal)
+    }
+
+    /// Removes the records written since `mark`, closing the boxes they opened.
+    fn backtrack(&mut self, mark: usize) {
+        self.records.truncate(mark);
+        self.ctxt.retain(|b| *b < mark);
+    }
+}
+
+impl Formula {
+    /// Looks for a natural-deduction proof of the formula, see [`prove`].
+    pub fn prove(&self, timeout: Duration) -> Result<Proof, ProverError> {
+        prove(&[], self, timeout)
+    }
+}
+
+impl Proof {
+    /// Replaces each record justified by `?` with a derivation of its formula, in the same box,
+    /// from the records it may use. The derivation only uses primitive rules, `Raa` being
+    /// reserved to classical proofs and `Efq` to non-minimal ones. Later records are
+    /// renumbered, references to the placeholder pointing to the end of its derivation.
+    ///
+    /// The search is shared by every placeholder and stops after `timeout`. Placeholders that
+    /// could not be filled are left, and returned with their position in the new proof.
+    pub fn fill_gaps(&mut self, timeout: Duration) -> Result<(), Vec<(usize, GapError)>> {
+        let deadline = Instant::now() + timeout;
+        let mut errors = Vec::new();
+        let mut at = 0;
+        while at < self.records().len() {
+            let gap = &self.records()[at];
+            if gap.justif != Jusitification::Gap {
+                at += 1;
+                continue;
+            }
+            let Statement::Simple(goal) = &gap.stmt else {
+                errors.push((at, GapError::NotSimple));
+                at += 1;
+                continue;
+            };
+            let goal = goal.clone();
+            let ctxt = gap.ctxt.clone();
+            let earlier = self.records()[..at].to_vec();
+            let mut search = Search::new(earlier, ctxt.clone(), self.logic(), deadline);
+            let mut facts = Facts::new();
+            for rec in &self.records()[..at] {
+                let usable = !matches!(rec.stmt, Statement::Soit(_) | Statement::Monde)
+                    && rec.justif != Jusitification::Gap
+                    && self.accessible(&ctxt, rec);
+                if usable {
+                    let formula = rec.stmt.get_formula();
+                    facts.push((core(formula), search.rewrite(rec.id, formula)));
+                }
+            }
+            match search.establish(facts, &goal, at) {
+                Ok(()) => {
+                    let derivation = prune(search.records, at).split_off(at);
+                    let length = derivation.len();
+                    self.splice(at, derivation);
+                    at += length;
+                }
+                Err(failure) => {
+                    errors.push((
+                        at,
+                        match failure {
+                            Failure::NotFound => GapError::NotFound,
+                            Failure::TimedOut => GapError::TimedOut,
+                        },
+                    ));
+                    at += 1;
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{CheckUpResult, Logic};
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    fn checked(mut proof: Proof, logic: Logic) -> Proof {
+        proof.set_logic(logic);
+        proof.check();
+        assert!(
+            matches!(proof.state(), CheckUpResult::Valid),
+            "{:?}\n{}",
+            proof.state(),
+            proof
+        );
+        let reread = Proof::read_proof(&proof.to_string()).unwrap();
+        assert_eq!(reread.to_string(), proof.to_string());
+        proof
+    }
+
+    #[test]
+    fn intuitionistic_goals() {
+        for goal in [
+            "a⇒a",
+            "(a⇒b)⇒(b⇒c)⇒a⇒c",
+            "a∧b⇒b∧a",
+            "a∨b⇒b∨a",
+            "¬¬(a∨¬a)",
+            "((a⇒b)⇒c)⇒(b⇒c)",
+            "(a∨b⇒c)⇔(a⇒c)∧(b⇒c)",
+            "⊤",
+            "¬a∨b⇒(a⇒b)",
+        ] {
+            let proof = checked(
+                f(goal).prove(Duration::from_secs(5)).unwrap(),
+                Logic::Intuitionistic,
+            );
+            assert_eq!(proof.records().last().unwrap().stmt.get_formula(), &f(goal));
+        }
+    }
+
+    #[test]
+    fn classical_goals() {
+        for goal in ["a∨¬a", "((a⇒b)⇒a)⇒a", "¬¬a⇒a", "(a⇒b)∨(b⇒a)"] {
+            let proof = checked(
+                f(goal).prove(Duration::from_secs(5)).unwrap(),
+                Logic::Classical,
+            );
+            assert!(proof
+                .records()
+                .iter()
+                .any(|r| matches!(r.justif, Jusitification::Raa(_))));
+            assert_eq!(proof.records().last().unwrap().stmt.get_formula(), &f(goal));
+        }
+    }
+
+    #[test]
+    fn gaps() {
+        let mut proof = Proof::read_proof(
+            "0;0;Supposons a∧(a⇒b);Hyp\n\
+             1;0;b∨c;?\n\
+             2;0;¬¬(b∨c);?\n\
+             3;;Donc a∧(a⇒b)⇒¬¬(b∨c);IImpl\n",
+        )
+        .unwrap();
+        proof.fill_gaps(Duration::from_secs(5)).unwrap();
+        let proof = checked(proof, Logic::Intuitionistic);
+        let last = proof.records().last().unwrap();
+        assert!(matches!(last.stmt, Statement::Donc(_)));
+        assert_eq!(last.ctxt, Vec::<usize>::new());
+        assert!(!proof
+            .records()
+            .iter()
+            .any(|r| r.justif == Jusitification::Gap));
+    }
+
+    #[test]
+    fn gaps_using_premises() {
+        let mut proof = Proof::read_proof(
+            "Théorème a ⊢ b⇒a∧b\n\
+             0;;Prémisse a;Hyp\n\
+             1;1;Supposons b;Hyp\n\
+             2;1;a∧b;?\n\
+             3;;Donc b⇒a∧b;IImpl\n",
+        )
+        .unwrap();
+        proof.fill_gaps(Duration::from_secs(5)).unwrap();
+        checked(proof, Logic::Intuitionistic);
+        let mut proof =
+            Proof::read_proof("0;;Prémisse a;Hyp\n1;1;Monde;Hyp\n2;1;a;?\n3;;Donc □a;INec\n")
+                .unwrap();
+        assert_eq!(
+            proof.fill_gaps(Duration::from_secs(5)),
+            Err(vec![(2, GapError::NotFound)])
+        );
+    }
+
+    #[test]
+    fn unfilled_gaps() {
+        let input = "0;0;Supposons ¬¬a;Hyp\n1;0;a;?\n2;0;a∨b;IOrR 1 b\n";
+        let mut proof = Proof::read_proof(input).unwrap();
+        proof.set_logic(Logic::Intuitionistic);
+        assert_eq!(
+            proof.fill_gaps(Duration::from_secs(5)),
+            Err(vec![(1, GapError::NotFound)])
+        );
+        assert_eq!(proof.to_string(), input);
+        proof.set_logic(Logic::Classical);
+        proof.fill_gaps(Duration::from_secs(5)).unwrap();
+        let proof = checked(proof, Logic::Classical);
+        let last = proof.records().last().unwrap();
+        assert_eq!(last.justif, Jusitification::IOrR(last.id - 1, f("b")));
+    }
+
+    #[test]
+    fn minimal_gaps() {
+        let input = "0;0;Supposons ⊥;Hyp\n1;0;a;?\n";
+        let mut proof = Proof::read_proof(input).unwrap();
+        proof.set_logic(Logic::Minimal);
+        assert_eq!(
+            proof.fill_gaps(Duration::from_secs(5)),
+            Err(vec![(1, GapError::NotFound)])
+        );
+        let mut proof = Proof::read_proof(
+            "0;0;Supposons a∧¬a;Hyp\n1;0;¬b;?\n2;0,2;Supposons ⊥⇒c;Hyp\n3;0,2;c;?\n",
+        )
+        .unwrap();
+        proof.set_logic(Logic::Minimal);
+        proof.fill_gaps(Duration::from_secs(5)).unwrap();
+        checked(proof, Logic::Minimal);
+    }
+
+    #[test]
+    fn premises() {
+        let premises = [f("a⇒b"), f("¬b")];
+        let proof = checked(
+            prove(&premises, &f("¬a"), Duration::from_secs(5)).unwrap(),
+            Logic::Intuitionistic,
+        );
+        let last = proof.records().last().unwrap();
+        assert_eq!(last.ctxt, vec![0, 1]);
+        assert_eq!(
+            prove(&premises, &f("a"), Duration::from_secs(5)).err(),
+            Some(ProverError::NotValid)
+        );
+        assert_eq!(
+            f("∀xP(x)").prove(Duration::from_secs(5)).err(),
+            Some(ProverError::NotPropositional)
+        );
+    }
+
+    #[test]
+    fn many_variables() {
+        let variables: Vec<String> = ('a'..='x').map(String::from).collect();
+        let conjunction = format!("({})", variables.join("∧"));
+        let disjunction = format!("({})", variables.join("∨"));
+        let goal = f(&format!("{}⇒{}", conjunction, disjunction));
+        assert!(goal.prove(Duration::from_secs(60)).is_ok());
+        let goal = f(&format!("{}⇒{}", disjunction, conjunction));
+        assert_eq!(
+            goal.prove(Duration::from_secs(60)).err(),
+            Some(ProverError::NotValid)
+        );
+    }
+}
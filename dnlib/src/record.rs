@@ -1,7 +1,10 @@
+use std::fmt;
+
 use thiserror::Error;
 
 use crate::formula::{Formula,TokenizationError};
 use crate::justif::{Jusitification, ReadError as JusitifReadError};
+use crate::rule::RuleRegistry;
 
 #[derive(Error,Debug)]
 pub enum RecordError {
@@ -24,9 +27,15 @@ pub enum RecordError {
     /// Error parsing the justification
     #[error("Error parsing the justification: {0}")]
     InvalidJustif(JusitifReadError),
+    /// A Soit should introduce a single variable
+    #[error("A Soit should introduce a single variable")]
+    InvalidSoitVariable,
+    /// The theorem is not a sequent with a single goal
+    #[error("A theorem is declared as Théorème premise, … ⊢ goal")]
+    InvalidTheorem,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Record {
     pub id: usize,
     pub ctxt: Vec<usize>,
@@ -37,11 +46,16 @@ pub struct Record {
 impl Record {
     /// Reads a record
     pub fn read_record(input: &str) -> Result<Self, RecordError> {
+        Self::read_record_with(input, RuleRegistry::standard())
+    }
+
+    /// Reads a record whose justification cites one of `rules`
+    pub fn read_record_with(input: &str, rules: &RuleRegistry) -> Result<Self, RecordError> {
         let mut input = input.split(';');
         let id = Self::read_id(input.next().ok_or(RecordError::MissingField)?.trim())?;
         let ctxt = Self::read_ctxt(input.next().ok_or(RecordError::MissingField)?.trim())?;
         let stmt = Self::read_stmt(input.next().ok_or(RecordError::MissingField)?.trim())?;
-        let justif = Self::read_justif(input.next().ok_or(RecordError::MissingField)?.trim())?;
+        let justif = Self::read_justif(input.next().ok_or(RecordError::MissingField)?.trim(), rules)?;
         match input.next() {
             Some(_) => Err(RecordError::TooMuch),
             None => Ok(Self {
@@ -53,6 +67,13 @@ impl Record {
         }
     }
 
+    /// Replaces the id of the record and of every record it refers to, `i`, by `map(i)`.
+    pub(crate) fn renumber(&mut self, map: impl Fn(usize) -> usize) {
+        self.id = map(self.id);
+        self.ctxt.iter_mut().for_each(|b| *b = map(*b));
+        self.justif.renumber(map);
+    }
+
     /// Reads the id of the record
     fn read_id(input: &str) -> Result<usize, RecordError> {
         input.parse::<usize>().map_err(|_| RecordError::InvalidId)
@@ -61,7 +82,7 @@ impl Record {
     /// Reads the context of the record
     fn read_ctxt(input: &str) -> Result<Vec<usize>, RecordError> {
         input
-            .split(|c: char| c == ',')
+            .split(',')
             .filter(|slc| ! slc.is_empty())
             .map(|slc: &str| slc.parse::<usize>().map_err(|_| RecordError::InvalidCtxt))
             .collect()
@@ -71,44 +92,88 @@ impl Record {
     fn read_stmt(input: &str) -> Result<Statement, RecordError> {
         match input.strip_prefix("Donc ") {
             Some(input) => {
-                let f = Formula::read(input).map_err(|e| RecordError::InvalidFormula(e))?;
+                let f = Formula::read(input).map_err(RecordError::InvalidFormula)?;
                 Ok(Statement::Donc(f))
             }
             None => match input.strip_prefix("Supposons ") {
                 Some(input) => {
-                    let f = Formula::read(input).map_err(|e| RecordError::InvalidFormula(e))?;
+                    let f = Formula::read(input).map_err(RecordError::InvalidFormula)?;
                     Ok(Statement::Supposons(f))
                 },
-                None => {
-                    let f = Formula::read(input).map_err(|e| RecordError::InvalidFormula(e))?;
-                    Ok(Statement::Simple(f))
+                None if input == "Monde" => Ok(Statement::Monde),
+                None if input.starts_with("Prémisse ") => {
+                    let f = Formula::read(input["Prémisse ".len()..].trim())
+                        .map_err(RecordError::InvalidFormula)?;
+                    Ok(Statement::Premise(f))
+                },
+                None => match input.strip_prefix("Soit ") {
+                    Some(input) => {
+                        let mut chars = input.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(x), None) if x.is_ascii_alphabetic() => Ok(Statement::Soit(x)),
+                            _ => Err(RecordError::InvalidSoitVariable),
+                        }
+                    },
+                    None => {
+                        let f = Formula::read(input).map_err(RecordError::InvalidFormula)?;
+                        Ok(Statement::Simple(f))
+                    }
                 }
             }
-            
         }
     }
 
     /// Reads the justification
-    fn read_justif(input: &str) -> Result<Jusitification, RecordError> {
-        Jusitification::read(input).map_err(|err| RecordError::InvalidJustif(err))
+    fn read_justif(input: &str, rules: &RuleRegistry) -> Result<Jusitification, RecordError> {
+        rules.read(input).map_err(RecordError::InvalidJustif)
     }
+}
 
-
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ctxt: Vec<String> = self.ctxt.iter().map(|c| c.to_string()).collect();
+        write!(f, "{};{};{};{}", self.id, ctxt.join(","), self.stmt, self.justif)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Statement {
     Supposons(Formula),
+    /// Introduces a fresh term variable
+    Soit(char),
+    /// Opens a box standing for an arbitrary accessible world
+    Monde,
     Donc(Formula),
     Simple(Formula),
+    /// A premise of the theorem, which every record may use
+    Premise(Formula),
 }
 
+static TOP: Formula = Formula::Top;
+
 impl Statement {
+    /// Returns the formula of the statement. A `Soit` or a `Monde` asserts nothing, its
+    /// formula is ⊤, and a proof is not valid if a rule cites it.
     pub fn get_formula(&self) -> &Formula {
         match self {
             Statement::Supposons(formula) => formula,
+            Statement::Soit(_) | Statement::Monde => &TOP,
             Statement::Donc(formula) => formula,
             Statement::Simple(formula) => formula,
+            Statement::Premise(formula) => formula,
+        }
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Supposons(formula) => write!(f, "Supposons {}", formula),
+            Statement::Soit(x) => write!(f, "Soit {}", x),
+            Statement::Monde => write!(f, "Monde"),
+            Statement::Donc(formula) => write!(f, "Donc {}", formula),
+            Statement::Simple(formula) => write!(f, "{}", formula),
+            Statement::Premise(formula) => write!(f, "Prémisse {}", formula),
         }
     }
 }
@@ -138,6 +203,30 @@ mod tests {
         assert!(r.is_ok());
     }
 
+    #[test]
+    fn simple_soit_stmt() {
+        let input = "1;0,1;Soit x;Hyp";
+        let r = Record::read_record(input);
+        assert!(matches!(r, Ok(Record { stmt: Statement::Soit('x'), .. })));
+        let input = "1;0,1;Soit xy;Hyp";
+        let r = Record::read_record(input);
+        assert!(matches!(r, Err(RecordError::InvalidSoitVariable)));
+    }
+
+    #[test]
+    fn simple_monde_stmt() {
+        let input = "1;0,1;Monde;Hyp";
+        let r = Record::read_record(input);
+        assert!(matches!(r, Ok(Record { stmt: Statement::Monde, .. })));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for input in ["1;2,3;A∧B;EImpl 3 4", "0;0;Supposons ¬a;Hyp", "4;;Donc a⇒b;IImpl", "2;0,2;Soit x;Hyp"] {
+            assert_eq!(Record::read_record(input).unwrap().to_string(), input);
+        }
+    }
+
     #[test]
     fn too_much_to_read_v1() {
         let input = "1;2,3;Donc A;EImpl 3 4 ddddddd";
@@ -0,0 +1,665 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::SplitWhitespace;
+
+use thiserror::Error;
+
+use crate::formula::Formula;
+
+#[derive(Error, Debug)]
+pub enum RecordError {
+    /// A field is missing
+    #[error("A field is missing")]
+    MissingField,
+    /// The id field is invalid
+    #[error("The id field is invalid")]
+    InvalidId,
+    /// There are too many fields
+    #[error("There are too many fields")]
+    TooMuch,
+    /// The clause is invalid
+    #[error("A clause is ⊥ or literals such as ¬a joined by ∨")]
+    InvalidClause,
+    /// The justification is missing
+    #[error("The justification is missing")]
+    MissingJustification,
+    /// The justification is neither Hyp, Res nor Fact
+    #[error("The justification is neither Hyp, Res nor Fact")]
+    UnknownJustification,
+    /// The justification is missing the position of a premise
+    #[error("The justification is missing the position of a premise")]
+    MissingReference,
+    /// The position of a premise is not a valid number
+    #[error("The position of a premise is not a valid number: {0}")]
+    InvalidReference(ParseIntError),
+    /// The pivot of Res should be a single letter
+    #[error("The pivot of Res should be a single letter")]
+    InvalidPivot,
+    /// The justification is given too many arguments
+    #[error("The justification is given too many arguments")]
+    TooManyArguments,
+}
+
+#[derive(Error, Debug)]
+#[error("At {line}: {content}")]
+pub struct ReadError {
+    pub line: usize,
+    #[source]
+    pub content: RecordError,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ResolutionError {
+    /// The id of the record is not its position
+    #[error("The id of the record is not its position")]
+    IncorrectId,
+    /// A premise has to be derived before the clause
+    #[error("Premise {0} has to be derived before the clause")]
+    PremiseNotBefore(usize),
+    /// The clause is not a clause of the CNF
+    #[error("The clause is not a clause of the CNF")]
+    NotAPremise,
+    /// The clause is not a resolvent of its premises
+    #[error("The clause is not a resolvent of its premises")]
+    NotAResolvent,
+    /// The clause is not its premise with a repeated literal removed
+    #[error("The clause is not its premise with a repeated literal removed")]
+    NotAFactor,
+    /// The proof does not end with the empty clause
+    #[error("The proof does not end with the empty clause")]
+    NotARefutation,
+}
+
+/// The largest number of clauses the conversion of a formula into CNF may give.
+pub const MAX_CLAUSES: usize = 1024;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CnfError {
+    /// The formula has quantifiers, predicates, equalities or modalities
+    #[error("The formula is not propositional")]
+    NotPropositional,
+    /// Distributing the disjunctions gives too many clauses
+    #[error("The CNF of the formula has more than {MAX_CLAUSES} clauses")]
+    TooManyClauses,
+}
+
+/// A propositional variable or its negation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Literal {
+    pub variable: char,
+    pub positive: bool,
+}
+
+impl Literal {
+    pub fn negated(self) -> Self {
+        Self {
+            variable: self.variable,
+            positive: !self.positive,
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.positive {
+            write!(f, "{}", self.variable)
+        } else {
+            write!(f, "¬{}", self.variable)
+        }
+    }
+}
+
+/// A disjunction of literals. The order of the literals does not matter, but a literal may be
+/// repeated until it is factored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Clause {
+    pub literals: Vec<Literal>,
+}
+
+impl Clause {
+    pub fn new(literals: Vec<Literal>) -> Self {
+        Self { literals }
+    }
+
+    /// Reads a clause such as `a∨¬b`, the empty clause being `⊥`.
+    pub fn read(input: &str) -> Result<Self, RecordError> {
+        let input = input.trim();
+        if input == "⊥" {
+            return Ok(Self::default());
+        }
+        let literals = input
+            .split('∨')
+            .map(|literal| {
+                let literal = literal.trim();
+                let (positive, variable) = match literal.strip_prefix('¬') {
+                    Some(variable) => (false, variable),
+                    None => (true, literal),
+                };
+                let mut chars = variable.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(variable), None) if variable.is_alphabetic() => {
+                        Ok(Literal { variable, positive })
+                    }
+                    _ => Err(RecordError::InvalidClause),
+                }
+            })
+            .collect::<Result<Vec<Literal>, RecordError>>()?;
+        Ok(Self::new(literals))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.literals.is_empty()
+    }
+
+    /// Returns the distinct literals of the clause.
+    pub fn literal_set(&self) -> BTreeSet<Literal> {
+        self.literals.iter().copied().collect()
+    }
+
+    /// Tells whether both clauses have the same literals, repetitions included.
+    pub fn same_as(&self, other: &Clause) -> bool {
+        let (mut a, mut b) = (self.literals.clone(), other.literals.clone());
+        a.sort();
+        b.sort();
+        a == b
+    }
+
+    /// Tells whether the clause holds in every valuation, containing a literal and its negation.
+    pub fn is_tautology(&self) -> bool {
+        self.literals
+            .iter()
+            .any(|l| self.literals.contains(&l.negated()))
+    }
+
+    /// Returns the clause without one occurrence of `literal`.
+    fn without(&self, literal: Literal) -> Clause {
+        let mut literals = self.literals.clone();
+        if let Some(i) = literals.iter().position(|l| *l == literal) {
+            literals.remove(i);
+        }
+        Clause::new(literals)
+    }
+
+    /// Returns the resolvent on the variable of `pivot`, which `self` contains, and whose
+    /// negation `other` contains.
+    fn resolve(&self, other: &Clause, pivot: Literal) -> Clause {
+        let mut resolvent = self.without(pivot);
+        resolvent
+            .literals
+            .extend(other.without(pivot.negated()).literals);
+        resolvent
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.literals.is_empty() {
+            return write!(f, "⊥");
+        }
+        let literals: Vec<String> = self.literals.iter().map(|l| l.to_string()).collect();
+        write!(f, "{}", literals.join("∨"))
+    }
+}
+
+/// Returns the clauses of the CNF of `f` if `positive`, of `¬f` otherwise.
+fn cnf(f: &Formula, positive: bool) -> Result<Vec<Vec<Literal>>, CnfError> {
+    let product = |a: Vec<Vec<Literal>>, b: Vec<Vec<Literal>>| {
+        if a.len() * b.len() > MAX_CLAUSES {
+            return Err(CnfError::TooManyClauses);
+        }
+        Ok(a.iter()
+            .flat_map(|ca| b.iter().map(move |cb| [ca.as_slice(), cb].concat()))
+            .collect())
+    };
+    Ok(match (f, positive) {
+        (Formula::Top, true) | (Formula::Bottom, false) => Vec::new(),
+        (Formula::Top, false) | (Formula::Bottom, true) => vec![Vec::new()],
+        (Formula::Variable(v), positive) => vec![vec![Literal {
+            variable: *v,
+            positive,
+        }]],
+        (Formula::Not(a), positive) => cnf(a, !positive)?,
+        (Formula::And(a, b), true) => [cnf(a, true)?, cnf(b, true)?].concat(),
+        (Formula::And(a, b), false) => product(cnf(a, false)?, cnf(b, false)?)?,
+        (Formula::Or(a, b), true) => product(cnf(a, true)?, cnf(b, true)?)?,
+        (Formula::Or(a, b), false) => [cnf(a, false)?, cnf(b, false)?].concat(),
+        (Formula::Implies(a, b), true) | (Formula::RLImplies(b, a), true) => {
+            product(cnf(a, false)?, cnf(b, true)?)?
+        }
+        (Formula::Implies(a, b), false) | (Formula::RLImplies(b, a), false) => {
+            [cnf(a, true)?, cnf(b, false)?].concat()
+        }
+        (Formula::Equiv(a, b), true) => [
+            product(cnf(a, false)?, cnf(b, true)?)?,
+            product(cnf(a, true)?, cnf(b, false)?)?,
+        ]
+        .concat(),
+        (Formula::Equiv(a, b), false) => [
+            product(cnf(a, true)?, cnf(b, true)?)?,
+            product(cnf(a, false)?, cnf(b, false)?)?,
+        ]
+        .concat(),
+        (
+            Formula::Predicate(_, _)
+            | Formula::Equal(_, _)
+            | Formula::Forall(_, _)
+            | Formula::Exists(_, _)
+            | Formula::Necessarily(_)
+            | Formula::Possibly(_),
+            _,
+        ) => return Err(CnfError::NotPropositional),
+    })
+}
+
+impl Formula {
+    /// Converts the formula into conjunctive normal form, by distributing disjunctions over
+    /// conjunctions. Literals are not repeated in a clause and tautological clauses are
+    /// dropped, so a valid formula has no clause.
+    ///
+    /// The distribution doubles the clauses of `(a∧b)∨(c∧d)∨…` with each disjunct: the
+    /// conversion stops once a step gives more than [`MAX_CLAUSES`] clauses.
+    pub fn clauses(&self) -> Result<Vec<Clause>, CnfError> {
+        let mut clauses: Vec<Clause> = Vec::new();
+        for literals in cnf(self, true)? {
+            let clause = Clause::new(
+                literals
+                    .into_iter()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+            );
+            if !clause.is_tautology() && !clauses.contains(&clause) {
+                clauses.push(clause);
+            }
+        }
+        Ok(clauses)
+    }
+
+    /// Looks for a resolution refutation of the CNF of the formula. Returns `None` if the
+    /// formula is satisfiable.
+    pub fn refutation(&self) -> Result<Option<ResolutionProof>, CnfError> {
+        let clauses = self.clauses()?;
+        Ok(ResolutionProof::refute(&clauses))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResolutionJustification {
+    /// The clause belongs to the CNF
+    Hyp,
+    /// Resolution of two clauses, on a pivot variable that is inferred if not given
+    Res(usize, usize, Option<char>),
+    /// Removes a repeated literal
+    Fact(usize),
+}
+
+fn read_reference(s: &mut SplitWhitespace) -> Result<usize, RecordError> {
+    s.next()
+        .ok_or(RecordError::MissingReference)?
+        .parse::<usize>()
+        .map_err(RecordError::InvalidReference)
+}
+
+impl ResolutionJustification {
+    /// Reads a justification: `Hyp`, `Res 1 3`, `Res 1 3 a` or `Fact 4`.
+    pub fn read(input: &str) -> Result<Self, RecordError> {
+        let s = &mut input.split_whitespace();
+        let justif = match s.next().ok_or(RecordError::MissingJustification)? {
+            "Hyp" => Self::Hyp,
+            "Res" => {
+                let (left, right) = (read_reference(s)?, read_reference(s)?);
+                let pivot = match s.next() {
+                    Some(pivot) => {
+                        let mut chars = pivot.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(v), None) if v.is_alphabetic() => Some(v),
+                            _ => return Err(RecordError::InvalidPivot),
+                        }
+                    }
+                    None => None,
+                };
+                Self::Res(left, right, pivot)
+            }
+            "Fact" => Self::Fact(read_reference(s)?),
+            _ => return Err(RecordError::UnknownJustification),
+        };
+        match s.next() {
+            Some(_) => Err(RecordError::TooManyArguments),
+            None => Ok(justif),
+        }
+    }
+
+    /// Returns the records the clause is derived from.
+    pub fn references(&self) -> Vec<usize> {
+        match self {
+            Self::Hyp => Vec::new(),
+            Self::Res(left, right, _) => vec![*left, *right],
+            Self::Fact(premise) => vec![*premise],
+        }
+    }
+}
+
+impl fmt::Display for ResolutionJustification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hyp => write!(f, "Hyp"),
+            Self::Res(left, right, None) => write!(f, "Res {} {}", left, right),
+            Self::Res(left, right, Some(pivot)) => write!(f, "Res {} {} {}", left, right, pivot),
+            Self::Fact(premise) => write!(f, "Fact {}", premise),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolutionRecord {
+    pub id: usize,
+    pub clause: Clause,
+    pub justif: ResolutionJustification,
+}
+
+impl ResolutionRecord {
+    /// Reads a record `id;clause;justification`.
+    pub fn read_record(input: &str) -> Result<Self, RecordError> {
+        let mut input = input.split(';');
+        let id = input
+            .next()
+            .ok_or(RecordError::MissingField)?
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| RecordError::InvalidId)?;
+        let clause = Clause::read(input.next().ok_or(RecordError::MissingField)?)?;
+        let justif = ResolutionJustification::read(input.next().ok_or(RecordError::MissingField)?)?;
+        match input.next() {
+            Some(_) => Err(RecordError::TooMuch),
+            None => Ok(Self { id, clause, justif }),
+        }
+    }
+}
+
+impl fmt::Display for ResolutionRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};{};{}", self.id, self.clause, self.justif)
+    }
+}
+
+/// A resolution refutation: clauses of a CNF, then resolvents and factors ending with the
+/// empty clause.
+#[derive(Debug, Default)]
+pub struct ResolutionProof {
+    records: Vec<ResolutionRecord>,
+    premises: Option<Vec<Clause>>,
+}
+
+impl ResolutionProof {
+    /// Reads a proof, one record per line. Blank lines are ignored.
+    pub fn read_proof(input: &str) -> Result<Self, ReadError> {
+        let mut records = Vec::new();
+        for (line, record) in input.split('\n').enumerate() {
+            if record.trim().is_empty() {
+                continue;
+            }
+            match ResolutionRecord::read_record(record) {
+                Ok(r) => records.push(r),
+                Err(content) => return Err(ReadError { line, content }),
+            }
+        }
+        Ok(Self {
+            records,
+            premises: None,
+        })
+    }
+
+    pub fn records(&self) -> &[ResolutionRecord] {
+        &self.records
+    }
+
+    /// Sets the clauses `Hyp` records have to be taken from, such as the clauses of a formula.
+    /// Without them, any clause may be a hypothesis.
+    pub fn set_premises(&mut self, premises: Vec<Clause>) {
+        self.premises = Some(premises);
+    }
+
+    /// Checks every record and that the last one is the empty clause.
+    pub fn check(&self) -> Result<(), Vec<(usize, ResolutionError)>> {
+        let mut errors: Vec<(usize, ResolutionError)> = (0..self.records.len())
+            .filter_map(|id| self.check_record(id).err().map(|e| (id, e)))
+            .collect();
+        match self.records.last() {
+            Some(last) if last.clause.is_empty() => (),
+            last => errors.push((
+                last.map(|r| r.id).unwrap_or(0),
+                ResolutionError::NotARefutation,
+            )),
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_record(&self, id: usize) -> Result<(), ResolutionError> {
+        let rec = &self.records[id];
+        if rec.id != id {
+            return Err(ResolutionError::IncorrectId);
+        }
+        for premise in rec.justif.references() {
+            if premise >= id {
+                return Err(ResolutionError::PremiseNotBefore(premise));
+            }
+        }
+        match &rec.justif {
+            ResolutionJustification::Hyp => match &self.premises {
+                Some(premises) if !premises.iter().any(|p| p.same_as(&rec.clause)) => {
+                    Err(ResolutionError::NotAPremise)
+                }
+                _ => Ok(()),
+            },
+            ResolutionJustification::Res(left, right, pivot) => {
+                let (left, right) = (&self.records[*left].clause, &self.records[*right].clause);
+                let resolves = left.literal_set().into_iter().any(|l| {
+                    pivot.is_none_or(|p| p == l.variable)
+                        && right.literals.contains(&l.negated())
+                        && left.resolve(right, l).same_as(&rec.clause)
+                });
+                if resolves {
+                    Ok(())
+                } else {
+                    Err(ResolutionError::NotAResolvent)
+                }
+            }
+            ResolutionJustification::Fact(premise) => {
+                let premise = &self.records[*premise].clause;
+                let factors = rec.clause.literal_set().into_iter().any(|l| {
+                    let mut duplicated = rec.clause.clone();
+                    duplicated.literals.push(l);
+                    duplicated.same_as(premise)
+                });
+                if factors {
+                    Ok(())
+                } else {
+                    Err(ResolutionError::NotAFactor)
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, clause: Clause, justif: ResolutionJustification) -> usize {
+        let id = self.records.len();
+        self.records.push(ResolutionRecord { id, clause, justif });
+        id
+    }
+
+    /// Looks for a refutation of `clauses` by saturation: every clause is resolved with the
+    /// ones before it, resolvents that are tautologies or contain an earlier clause being
+    /// dropped. Returns `None` if the clauses are satisfiable.
+    ///
+    /// Only the records the empty clause depends on are kept.
+    pub fn refute(clauses: &[Clause]) -> Option<ResolutionProof> {
+        let mut search = ResolutionProof::default();
+        // Distinct literals of each kept clause, with the record deriving it.
+        let mut kept: Vec<(BTreeSet<Literal>, usize)> = Vec::new();
+        for clause in clauses {
+            let id = search.push(clause.clone(), ResolutionJustification::Hyp);
+            kept.push((clause.literal_set(), id));
+        }
+        let mut empty = kept.iter().find(|(c, _)| c.is_empty()).map(|(_, id)| *id);
+        let mut given = 0;
+        while empty.is_none() && given < kept.len() {
+            for other in 0..given {
+                let (left, right) = (kept[given].1, kept[other].1);
+                for pivot in kept[given].0.clone() {
+                    if !kept[other].0.contains(&pivot.negated()) {
+                        continue;
+                    }
+                    let resolvent = search.records[left]
+                        .clause
+                        .resolve(&search.records[right].clause, pivot);
+                    let set = resolvent.literal_set();
+                    if resolvent.is_tautology() || kept.iter().any(|(c, _)| c.is_subset(&set)) {
+                        continue;
+                    }
+                    let mut id = search.push(
+                        resolvent,
+                        ResolutionJustification::Res(left, right, Some(pivot.variable)),
+                    );
+                    while search.records[id].clause.literals.len() > set.len() {
+                        let mut factor = search.records[id].clause.clone();
+                        let repeated = factor
+                            .literals
+                            .iter()
+                            .position(|l| factor.literals.iter().filter(|m| *m == l).count() > 1)
+                            .expect("a clause longer than its literal set repeats a literal");
+                        factor.literals.remove(repeated);
+                        id = search.push(factor, ResolutionJustification::Fact(id));
+                    }
+                    if set.is_empty() {
+                        empty = Some(id);
+                        break;
+                    }
+                    kept.push((set, id));
+                }
+                if empty.is_some() {
+                    break;
+                }
+            }
+            given += 1;
+        }
+        empty.map(|empty| search.extract(empty))
+    }
+
+    /// Returns the proof made of the records `last` depends on, renumbered.
+    fn extract(&self, last: usize) -> ResolutionProof {
+        let mut needed = vec![false; last + 1];
+        needed[last] = true;
+        for id in (0..=last).rev() {
+            if needed[id] {
+                for premise in self.records[id].justif.references() {
+                    needed[premise] = true;
+                }
+            }
+        }
+        let mut renumbered = vec![0; last + 1];
+        let mut proof = ResolutionProof::default();
+        for id in (0..=last).filter(|id| needed[*id]) {
+            let justif = match &self.records[id].justif {
+                ResolutionJustification::Hyp => ResolutionJustification::Hyp,
+                ResolutionJustification::Res(left, right, pivot) => {
+                    ResolutionJustification::Res(renumbered[*left], renumbered[*right], *pivot)
+                }
+                ResolutionJustification::Fact(premise) => {
+                    ResolutionJustification::Fact(renumbered[*premise])
+                }
+            };
+            renumbered[id] = proof.push(self.records[id].clause.clone(), justif);
+        }
+        proof
+    }
+}
+
+impl fmt::Display for ResolutionProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    fn clauses(input: &[&str]) -> Vec<Clause> {
+        input.iter().map(|c| Clause::read(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn cnf_conversion() {
+        assert_eq!(f("(a⇒b)∧a∧¬b").clauses(), Ok(clauses(&["¬a∨b", "a", "¬b"])));
+        assert_eq!(f("a⇔b").clauses(), Ok(clauses(&["¬a∨b", "a∨¬b"])));
+        assert_eq!(f("a∨¬a").clauses(), Ok(Vec::new()));
+        assert_eq!(f("⊥").clauses(), Ok(vec![Clause::default()]));
+        assert_eq!(f("∀xP(x)").clauses(), Err(CnfError::NotPropositional));
+        // 2^n clauses for n disjuncts
+        let disjunction = |n: usize| {
+            let pairs: Vec<String> = ('a'..='z')
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .take(n)
+                .map(|pair| format!("({}∧{})", pair[0], pair[1]))
+                .collect();
+            f(&pairs.join("∨"))
+        };
+        assert_eq!(disjunction(10).clauses().unwrap().len(), MAX_CLAUSES);
+        assert_eq!(disjunction(13).clauses(), Err(CnfError::TooManyClauses));
+        assert_eq!(
+            f(&format!("({})∧¬a", disjunction(13))).refutation().unwrap_err(),
+            CnfError::TooManyClauses
+        );
+    }
+
+    #[test]
+    fn check() {
+        let input = "0;a∨b;Hyp\n1;a∨¬b;Hyp\n2;a∨a;Res 0 1 b\n3;a;Fact 2\n4;¬a;Hyp\n5;⊥;Res 3 4";
+        let mut proof = ResolutionProof::read_proof(input).unwrap();
+        assert_eq!(proof.check(), Ok(()));
+        proof.set_premises(clauses(&["a∨b", "¬b∨a", "¬a"]));
+        assert_eq!(proof.check(), Ok(()));
+        proof.set_premises(clauses(&["a∨b", "¬a"]));
+        assert_eq!(proof.check(), Err(vec![(1, ResolutionError::NotAPremise)]));
+        let input = "0;a∨b;Hyp\n1;¬a∨¬b;Hyp\n2;⊥;Res 0 1\n3;b∨a;Fact 0\n4;b;Res 0 1 a";
+        let proof = ResolutionProof::read_proof(input).unwrap();
+        assert_eq!(
+            proof.check(),
+            Err(vec![
+                (2, ResolutionError::NotAResolvent),
+                (3, ResolutionError::NotAFactor),
+                (4, ResolutionError::NotAResolvent),
+                (4, ResolutionError::NotARefutation),
+            ])
+        );
+    }
+
+    #[test]
+    fn generation() {
+        for formula in [
+            "(a⇒b)∧a∧¬b",
+            "¬((a⇒b)⇒(b⇒c)⇒a⇒c)",
+            "(a∨b)∧(a∨¬b)∧(¬a∨b)∧(¬a∨¬b)",
+        ] {
+            let formula = f(formula);
+            let mut proof = formula.refutation().unwrap().expect("unsatisfiable");
+            proof.set_premises(formula.clauses().unwrap());
+            assert_eq!(proof.check(), Ok(()), "{}", proof);
+            let reread = ResolutionProof::read_proof(&proof.to_string()).unwrap();
+            assert_eq!(reread.records(), proof.records());
+        }
+        assert!(f("(a∨b)∧¬a").refutation().unwrap().is_none());
+    }
+}
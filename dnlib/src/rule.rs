@@ -0,0 +1,1525 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::num::ParseIntError;
+use std::sync::{Arc, LazyLock};
+
+use crate::formula::Formula;
+use crate::justif::{Jusitification, ReadError};
+use crate::modal::ModalSystem;
+use crate::proof::{check_ctxt_compatibility, Logic, Proof, SemanticError};
+use crate::record::{Record, Statement};
+use crate::ruleset::SchemaRule;
+use crate::term::Term;
+
+/// A rule of natural deduction: how records cite it, and when a record follows from it.
+///
+/// A proof looks each rule up in its [`RuleRegistry`], by the name of the justification of the
+/// record checked. Downstream crates may thus add rules, or replace the primitive ones.
+pub trait Rule: Send + Sync {
+    /// Returns the name records cite the rule by, as `EImpl` in `EImpl 2 5`.
+    fn name(&self) -> &str;
+
+    /// Reads the arguments following the name of the rule. Rules citing records only may use
+    /// [`read_named`].
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError>;
+
+    /// Checks the record `rec` of `proof` follows from the rule, the earlier records being
+    /// checked already. A `Simple` record is in the boxes of the record before it; a `Donc`
+    /// record may call [`Proof::closed_box`].
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError>;
+
+    /// Tells whether the rule justifies a `Donc`, closing the last box.
+    fn closes_box(&self) -> bool {
+        false
+    }
+
+    /// Returns the weakest logic the rule holds in. The steps of the rules holding in
+    /// classical logic only are listed by [`Proof::classical_dependencies`].
+    fn logic(&self) -> Logic {
+        Logic::Minimal
+    }
+
+    /// Returns the schema the rule is declared by, for the rules of a rule set. The hints of a
+    /// proof follow the schemas of its rules.
+    fn schema(&self) -> Option<&SchemaRule> {
+        None
+    }
+}
+
+/// The rules records may cite, by name.
+#[derive(Clone, Default)]
+pub struct RuleRegistry {
+    rules: BTreeMap<String, Arc<dyn Rule>>,
+}
+
+static PRIMITIVES: LazyLock<RuleRegistry> = LazyLock::new(|| {
+    let mut rules = RuleRegistry::new();
+    rules.register(IOrL);
+    rules.register(IOrR);
+    rules.register(EOr);
+    rules.register(IAnd);
+    rules.register(EAndL);
+    rules.register(EAndR);
+    rules.register(Hyp);
+    rules.register(IImpl);
+    rules.register(EImpl);
+    rules.register(Efq);
+    rules.register(Raa);
+    rules.register(Rwrt);
+    rules.register(IForall);
+    rules.register(EForall);
+    rules.register(IExists);
+    rules.register(EExists);
+    rules.register(INec);
+    rules.register(ENec);
+    rules.register(RPos);
+    rules.register(INot);
+    rules.register(ENot);
+    rules.register(IEquiv);
+    rules.register(EEquivL);
+    rules.register(EEquivR);
+    rules.register(ITop);
+    rules.register(Gap);
+    rules
+});
+
+static STANDARD: LazyLock<Arc<RuleRegistry>> = LazyLock::new(|| {
+    let rules = RuleRegistry::read_rule_set(include_str!("../rules/inf402.rules"))
+        .expect("the default rule set is valid");
+    Arc::new(rules)
+});
+
+impl RuleRegistry {
+    /// Returns a registry without any rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the registry of the rules of `rules/inf402.rules`, proofs are read and checked
+    /// with by default. It may be cloned to be extended.
+    pub fn standard() -> &'static RuleRegistry {
+        &STANDARD
+    }
+
+    /// Returns the registry of the primitive rules, each spelled by its name.
+    pub fn primitives() -> &'static RuleRegistry {
+        &PRIMITIVES
+    }
+
+    /// Returns the primitive rule `name`.
+    pub(crate) fn primitive(name: &str) -> Option<Arc<dyn Rule>> {
+        PRIMITIVES.rules.get(name).cloned()
+    }
+
+    pub(crate) fn shared_standard() -> Arc<RuleRegistry> {
+        Arc::clone(&STANDARD)
+    }
+
+    /// Adds `rule`, replacing the rule of the same name.
+    pub fn register(&mut self, rule: impl Rule + 'static) {
+        self.rules.insert(rule.name().to_string(), Arc::new(rule));
+    }
+
+    /// Lets records spell `rule` as `name`, replacing the rule spelled so.
+    pub(crate) fn insert_spelling(&mut self, name: &str, rule: Arc<dyn Rule>) {
+        self.rules.insert(name.to_string(), rule);
+    }
+
+    /// Lets records spell the rule `name` as `alias`. The justification read is the one of the
+    /// rule, printed with its own name. Returns false if there is no rule `name`.
+    pub fn alias(&mut self, alias: &str, name: &str) -> bool {
+        match self.rules.get(name) {
+            Some(rule) => {
+                let rule = Arc::clone(rule);
+                self.rules.insert(alias.to_string(), rule);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the rule records spell `name`, or else the rule named `name` records spell
+    /// otherwise.
+    pub fn get(&self, name: &str) -> Option<&dyn Rule> {
+        self.rules
+            .get(name)
+            .or_else(|| self.rules.values().find(|rule| rule.name() == name))
+            .map(|rule| rule.as_ref())
+    }
+
+    /// Returns the names records may cite, aliases included.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.rules.keys().map(|name| name.as_str())
+    }
+
+    /// Returns each rule once, whatever the number of its spellings.
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Rule> {
+        let mut seen = BTreeSet::new();
+        self.rules
+            .values()
+            .filter(move |rule| seen.insert(rule.name().to_string()))
+            .map(|rule| rule.as_ref())
+    }
+
+    /// Reads a justification. A name of no rule of the registry, made of a capital letter
+    /// followed by letters and digits, is read by [`read_named`]: the rule may be added to the
+    /// proof afterwards, as derived rules are.
+    pub fn read(&self, input: &str) -> Result<Jusitification, ReadError> {
+        let mut s = input.split(' ');
+        let name = s.next().ok_or(ReadError::InputEmpty)?;
+        let args: Vec<&str> = s.collect();
+        match self.rules.get(name) {
+            Some(rule) => rule.read(&args),
+            None if is_rule_name(name) => read_named(name, &args),
+            None => Err(ReadError::UnknownRule),
+        }
+    }
+}
+
+/// Reads the arguments of the rule `name` citing records only, such as `MT 4 7`.
+pub fn read_named(name: &str, args: &[&str]) -> Result<Jusitification, ReadError> {
+    let premises = args
+        .iter()
+        .map(|p| p.parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|e| ReadError::Invalid_In_Named_Reference(name.to_string(), e))?;
+    Ok(Jusitification::Named(name.to_string(), premises))
+}
+
+/// Returns the formulas of the `count` records cited by `rec`, in the order of its
+/// justification.
+pub fn cited_formulas<'a>(
+    proof: &'a Proof,
+    rec: &Record,
+    count: usize,
+) -> Result<Vec<&'a Formula>, SemanticError> {
+    let premises = rec.justif.references();
+    if premises.len() != count {
+        return Err(SemanticError::DerivedArity);
+    }
+    let mut formulas = Vec::new();
+    for premise in &premises {
+        if *premise >= rec.id {
+            return Err(SemanticError::DerivedPosLesser);
+        }
+        let premise = &proof.records()[*premise];
+        if !proof.accessible(&rec.ctxt, premise) {
+            return Err(SemanticError::DerivedIncompatibleCtxt);
+        }
+        formulas.push(premise.stmt.get_formula());
+    }
+    Ok(formulas)
+}
+
+/// Tells whether `name` may be the name of a rule: a capital letter followed by letters and
+/// digits.
+fn is_rule_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Reads the position of a record.
+fn position(
+    arg: Option<&&str>,
+    missing: ReadError,
+    invalid: fn(ParseIntError) -> ReadError,
+) -> Result<usize, ReadError> {
+    arg.ok_or(missing)?.parse::<usize>().map_err(invalid)
+}
+
+/// Returns `justif`, read from the first `count` arguments, if there is no other one.
+fn at_most(
+    args: &[&str],
+    count: usize,
+    justif: Jusitification,
+) -> Result<Jusitification, ReadError> {
+    if args.len() > count {
+        Err(ReadError::InputTooLarge)
+    } else {
+        Ok(justif)
+    }
+}
+
+/// Introduction of Or (new formula on left)
+pub struct IOrL;
+
+impl Rule for IOrL {
+    fn name(&self) -> &str {
+        "IOrL"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let left_pos = position(
+            args.first(),
+            ReadError::Missing_In_IOrL_LeftPos,
+            ReadError::Invalid_In_IOrL_LeftPos,
+        )?;
+        let right_formula = Formula::read(args.get(1).ok_or(ReadError::Missing_In_IOrL_Formula)?)
+            .map_err(ReadError::Invalid_In_IOrL_Formula)?;
+        at_most(args, 2, Jusitification::IOrL(left_pos, right_formula))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::IOrL(right_pos, new_left_formula) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *right_pos >= rec.id {
+            return Err(SemanticError::IOrLPosLesser);
+        }
+        // This is valid record, since it comes before and was therefore
+        // valited at a previous run of this loop.
+        let right_rec = &proof.records()[*right_pos];
+        // We check if the record is usable
+        if !proof.accessible(&rec.ctxt, right_rec) {
+            return Err(SemanticError::IOrLIncompatibleCtxt);
+        }
+        match rec.stmt.get_formula() {
+            Formula::Or(left_formula, right_formula) => {
+                if **left_formula != *new_left_formula {
+                    Err(SemanticError::IOrLLeftNotMatching)
+                } else if **right_formula != *right_rec.stmt.get_formula() {
+                    Err(SemanticError::IOrLRightNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::IOrLFormulaIsOr),
+        }
+    }
+}
+
+/// Introduction of Or (new formula on right)
+pub struct IOrR;
+
+impl Rule for IOrR {
+    fn name(&self) -> &str {
+        "IOrR"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let right_pos = position(
+            args.first(),
+            ReadError::Missing_In_IOrR_RightPos,
+            ReadError::Invalid_In_IOrR_RightPos,
+        )?;
+        let left_formula = Formula::read(args.get(1).ok_or(ReadError::Missing_In_IOrR_Formula)?)
+            .map_err(ReadError::Invalid_In_IOrR_Formula)?;
+        at_most(args, 2, Jusitification::IOrR(right_pos, left_formula))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::IOrR(left_pos, new_right_formula) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *left_pos >= rec.id {
+            return Err(SemanticError::IOrRPosLesser);
+        }
+        // This is valid record, since it comes before and was therefore
+        // valited at a previous run of this loop.
+        let left_rec = &proof.records()[*left_pos];
+        // We check if the record is usable
+        if !proof.accessible(&rec.ctxt, left_rec) {
+            return Err(SemanticError::IOrRIncompatibleCtxt);
+        }
+        match rec.stmt.get_formula() {
+            Formula::Or(left_formula, right_formula) => {
+                if **right_formula != *new_right_formula {
+                    Err(SemanticError::IOrRRightNotMatching)
+                } else if **left_formula != *left_rec.stmt.get_formula() {
+                    Err(SemanticError::IOrRLeftNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::IOrRFormulaIsOr),
+        }
+    }
+}
+
+/// Elimination of Or
+pub struct EOr;
+
+impl Rule for EOr {
+    fn name(&self) -> &str {
+        "EOr"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let a_to_c = position(
+            args.first(),
+            ReadError::Missing_In_EOr_A_to_C,
+            ReadError::Invalid_In_EOr_A_to_C,
+        )?;
+        let b_to_c = position(
+            args.get(1),
+            ReadError::Missing_In_EOr_B_to_C,
+            ReadError::Invalid_In_EOr_B_to_C,
+        )?;
+        let a_or_b = position(
+            args.get(2),
+            ReadError::Missing_In_EOr_A_or_B,
+            ReadError::Invalid_In_EOr_A_or_B,
+        )?;
+        at_most(
+            args,
+            3,
+            Jusitification::EOr {
+                a_to_c,
+                b_to_c,
+                a_or_b,
+            },
+        )
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EOr {
+            a_to_c,
+            b_to_c,
+            a_or_b,
+        } = &rec.justif
+        else {
+            return Err(SemanticError::InternalError);
+        };
+        // Checking ids
+        if *a_to_c >= rec.id {
+            return Err(SemanticError::EOrA2CPosLesser);
+        }
+        if *b_to_c >= rec.id {
+            return Err(SemanticError::EOrB2CPosLesser);
+        }
+        if *a_or_b >= rec.id {
+            return Err(SemanticError::EOrAOBPosLesser);
+        }
+        // Since we checked the ids its ok to retrieve the corresponding records.
+        let a_to_c = &proof.records()[*a_to_c];
+        let b_to_c = &proof.records()[*b_to_c];
+        let a_or_b = &proof.records()[*a_or_b];
+        // Checking usability of those records
+        if !proof.accessible(&rec.ctxt, a_to_c) {
+            return Err(SemanticError::EOrA2CIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, b_to_c) {
+            return Err(SemanticError::EOrB2CIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, a_or_b) {
+            return Err(SemanticError::EOrAOBIncompatibleCtxt);
+        }
+        // Checking the constructed formula is correct
+        if let (
+            Formula::Implies(atc_a, atc_c),
+            Formula::Implies(btc_b, btc_c),
+            Formula::Or(aob_a, aob_b),
+        ) = (
+            a_to_c.stmt.get_formula(),
+            b_to_c.stmt.get_formula(),
+            a_or_b.stmt.get_formula(),
+        ) {
+            if aob_a != atc_a {
+                Err(SemanticError::EOrAFormulaNotMatching)
+            } else if aob_b != btc_b {
+                Err(SemanticError::EOrBFormulaNotMatching)
+            } else if atc_c != btc_c {
+                Err(SemanticError::EOrCFormulaNotMatchingConsequences)
+            } else if atc_c.as_ref() != rec.stmt.get_formula() {
+                Err(SemanticError::EOrCFormulaNotMatchingEliminated)
+            } else {
+                Ok(())
+            }
+        } else {
+            Err(SemanticError::EOrFormulasNotRightKind)
+        }
+    }
+}
+
+/// Introduction of And
+pub struct IAnd;
+
+impl Rule for IAnd {
+    fn name(&self) -> &str {
+        "IAnd"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let left = position(
+            args.first(),
+            ReadError::Missing_In_IAnd_Left,
+            ReadError::Invalid_In_IAnd_Left,
+        )?;
+        let right = position(
+            args.get(1),
+            ReadError::Missing_In_IAnd_Right,
+            ReadError::Invalid_In_IAnd_Right,
+        )?;
+        at_most(args, 2, Jusitification::IAnd { left, right })
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::IAnd { left, right } = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        // Checking ids
+        if *left >= rec.id {
+            return Err(SemanticError::IAndLeftPosLesser);
+        }
+        if *right >= rec.id {
+            return Err(SemanticError::IAndRightPosLesser);
+        }
+        // Since we checked the ids its ok to retrieve the corresponding records.
+        let left = &proof.records()[*left];
+        let right = &proof.records()[*right];
+        // Checking usability of left and right
+        if !proof.accessible(&rec.ctxt, left) {
+            return Err(SemanticError::IAndLeftIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, right) {
+            return Err(SemanticError::IAndRightIncompatibleCtxt);
+        }
+
+        // Checking the constructed formula is correct
+        match rec.stmt.get_formula() {
+            Formula::And(left_formula, right_formula) => {
+                if **left_formula != *left.stmt.get_formula() {
+                    Err(SemanticError::IAndLeftNotMatching)
+                } else if **right_formula != *right.stmt.get_formula() {
+                    Err(SemanticError::IAndRightNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::IAndFormulaIsAnd),
+        }
+    }
+}
+
+/// Elimination of And (getting left)
+pub struct EAndL;
+
+impl Rule for EAndL {
+    fn name(&self) -> &str {
+        "EAndL"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_EAndL_Reference,
+            ReadError::Invalid_In_EAndL_Reference,
+        )?;
+        at_most(args, 1, Jusitification::EAndL(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EAndL(and_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        let and = conjunction(proof, rec, *and_pos)?;
+        match and {
+            Formula::And(left_formula, _) => {
+                if **left_formula != *rec.stmt.get_formula() {
+                    Err(SemanticError::EAndNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::EAndFormulaIsAnd),
+        }
+    }
+}
+
+/// Elimination of And (getting right)
+pub struct EAndR;
+
+impl Rule for EAndR {
+    fn name(&self) -> &str {
+        "EAndR"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_EAndR_Reference,
+            ReadError::Invalid_In_EAndR_Reference,
+        )?;
+        at_most(args, 1, Jusitification::EAndR(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EAndR(and_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        let and = conjunction(proof, rec, *and_pos)?;
+        match and {
+            Formula::And(_, right_formula) => {
+                if **right_formula != *rec.stmt.get_formula() {
+                    Err(SemanticError::EAndNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::EAndFormulaIsAnd),
+        }
+    }
+}
+
+/// Returns the formula of the record `and_pos` an And is eliminated from.
+fn conjunction<'a>(
+    proof: &'a Proof,
+    rec: &Record,
+    and_pos: usize,
+) -> Result<&'a Formula, SemanticError> {
+    if and_pos >= rec.id {
+        return Err(SemanticError::EAndPosLesser);
+    }
+    let and = &proof.records()[and_pos];
+    if !proof.accessible(&rec.ctxt, and) {
+        return Err(SemanticError::EAndIncompatibleCtxt);
+    }
+    Ok(and.stmt.get_formula())
+}
+
+/// New hypothesis, opening a box. The boxes are checked along with their statement.
+pub struct Hyp;
+
+impl Rule for Hyp {
+    fn name(&self) -> &str {
+        "Hyp"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::Hyp)
+    }
+
+    fn check(&self, _: &Proof, _: &Record) -> Result<(), SemanticError> {
+        Err(SemanticError::HypNotSimple)
+    }
+}
+
+/// Introduction of Implies
+pub struct IImpl;
+
+impl Rule for IImpl {
+    fn name(&self) -> &str {
+        "IImpl"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::IImpl)
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Statement::Donc(conclusion) = &rec.stmt else {
+            return Err(SemanticError::IImplNotSimple);
+        };
+        let (hyp, cons) = proof.closed_box(rec.id)?;
+        match (&hyp.stmt, conclusion) {
+            (Statement::Soit(_) | Statement::Monde, _) => Err(SemanticError::DoncImplNotSupposons),
+            (_, Formula::Implies(hyp_formula, cons_formula)) => {
+                if hyp_formula.as_ref() != hyp.stmt.get_formula() {
+                    Err(SemanticError::DoncHypNotMatching)
+                } else if cons_formula.as_ref() != cons.stmt.get_formula() {
+                    Err(SemanticError::DoncConsNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::DoncFormulaIsImplies),
+        }
+    }
+
+    fn closes_box(&self) -> bool {
+        true
+    }
+}
+
+/// Elimination of Implies
+pub struct EImpl;
+
+impl Rule for EImpl {
+    fn name(&self) -> &str {
+        "EImpl"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let hyp = position(
+            args.first(),
+            ReadError::Missing_In_EImpl_Hyp,
+            ReadError::Invalid_In_EImpl_Hyp,
+        )?;
+        let implication = position(
+            args.get(1),
+            ReadError::Missing_In_EImpl_Implication,
+            ReadError::Invalid_In_EImpl_Implication,
+        )?;
+        at_most(args, 2, Jusitification::EImpl { hyp, implication })
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EImpl { hyp, implication } = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *hyp >= rec.id {
+            return Err(SemanticError::EImplHypPosLesser);
+        }
+        if *implication >= rec.id {
+            return Err(SemanticError::EImplImplPosLesser);
+        }
+        let hyp = &proof.records()[*hyp];
+        let implication = &proof.records()[*implication];
+        if !proof.accessible(&rec.ctxt, hyp) {
+            return Err(SemanticError::EImplHypIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, implication) {
+            return Err(SemanticError::EImplImplIncompatibleCtxt);
+        }
+        if let Formula::Implies(i_hyp, i_cons) = implication.stmt.get_formula() {
+            if **i_hyp != *hyp.stmt.get_formula() {
+                Err(SemanticError::EImplHypNotMatching)
+            } else if **i_cons != *rec.stmt.get_formula() {
+                Err(SemanticError::EImplImplNotMatching)
+            } else {
+                Ok(())
+            }
+        } else {
+            Err(SemanticError::EImplFormulaIsImpl)
+        }
+    }
+}
+
+/// Ex falso quodlibet
+pub struct Efq;
+
+impl Rule for Efq {
+    fn name(&self) -> &str {
+        "Efq"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_Efq_Reference,
+            ReadError::Invalid_In_Efq_Reference,
+        )?;
+        at_most(args, 1, Jusitification::Efq(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::Efq(bot_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if proof.logic() == Logic::Minimal {
+            return Err(SemanticError::EfqNotMinimal);
+        }
+        if *bot_pos >= rec.id {
+            return Err(SemanticError::EfqPosLesser);
+        }
+        let bot = &proof.records()[*bot_pos];
+        if !proof.accessible(&rec.ctxt, bot) {
+            return Err(SemanticError::EfqIncompatibleCtxt);
+        }
+        if !matches!(bot.stmt.get_formula(), Formula::Bottom) {
+            return Err(SemanticError::EfqFormulaIsBot);
+        }
+        Ok(())
+    }
+
+    fn logic(&self) -> Logic {
+        Logic::Intuitionistic
+    }
+}
+
+/// Reductio ad absorbum
+pub struct Raa;
+
+impl Rule for Raa {
+    fn name(&self) -> &str {
+        "Raa"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_Raa_Reference,
+            ReadError::Invalid_In_Raa_Reference,
+        )?;
+        at_most(args, 1, Jusitification::Raa(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::Raa(nn_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if proof.logic() != Logic::Classical {
+            return Err(SemanticError::RaaNotIntuitionistic);
+        }
+        if *nn_pos >= rec.id {
+            return Err(SemanticError::RaaPosLesser);
+        }
+        let nn = &proof.records()[*nn_pos];
+        if !proof.accessible(&rec.ctxt, nn) {
+            return Err(SemanticError::RaaIncompatibleCtxt);
+        }
+        if let Formula::Not(n_formula) = nn.stmt.get_formula() {
+            if let Formula::Not(nn_formula) = &**n_formula {
+                if **nn_formula != *rec.stmt.get_formula() {
+                    Err(SemanticError::RaaNotMatching)
+                } else {
+                    Ok(())
+                }
+            } else {
+                Err(SemanticError::RaaFormulaIsNotNot)
+            }
+        } else {
+            Err(SemanticError::RaaFormulaIsNotNot)
+        }
+    }
+
+    fn logic(&self) -> Logic {
+        Logic::Classical
+    }
+}
+
+/// Rewrite
+pub struct Rwrt;
+
+impl Rule for Rwrt {
+    fn name(&self) -> &str {
+        "Rwrt"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_Rwrt_Reference,
+            ReadError::Invalid_In_Rwrt_Reference,
+        )?;
+        at_most(args, 1, Jusitification::Rwrt(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::Rwrt(orig_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *orig_pos >= rec.id {
+            return Err(SemanticError::RwrtPosLesser);
+        }
+        let orig = &proof.records()[*orig_pos];
+        if !proof.accessible(&rec.ctxt, orig) {
+            return Err(SemanticError::RwrtIncompatibleCtxt);
+        }
+        if rec.stmt.get_formula().clone().normalize() == orig.stmt.get_formula().clone().normalize()
+        {
+            Ok(())
+        } else {
+            Err(SemanticError::RwrtNotMatching)
+        }
+    }
+}
+
+/// Introduction of Forall, closing a `Soit` box
+pub struct IForall;
+
+impl Rule for IForall {
+    fn name(&self) -> &str {
+        "IForall"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::IForall)
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Statement::Donc(conclusion) = &rec.stmt else {
+            return Err(SemanticError::IForallNotSimple);
+        };
+        let (hyp, cons) = proof.closed_box(rec.id)?;
+        match (&hyp.stmt, conclusion) {
+            (Statement::Soit(x), Formula::Forall(z, body)) => {
+                let instance = body.substitute_term(*z, &Term::Variable(*x));
+                if conclusion.has_free(*x) {
+                    Err(SemanticError::DoncForallVariableFree)
+                } else if !instance.alpha_eq(cons.stmt.get_formula()) {
+                    Err(SemanticError::DoncForallNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            (Statement::Soit(_), _) => Err(SemanticError::DoncFormulaIsForall),
+            _ => Err(SemanticError::DoncForallNotSoit),
+        }
+    }
+
+    fn closes_box(&self) -> bool {
+        true
+    }
+}
+
+/// Elimination of Forall, instantiating the bound variable with a term
+pub struct EForall;
+
+impl Rule for EForall {
+    fn name(&self) -> &str {
+        "EForall"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_EForall_Reference,
+            ReadError::Invalid_In_EForall_Reference,
+        )?;
+        let term = Term::read(args.get(1).ok_or(ReadError::Missing_In_EForall_Term)?)
+            .map_err(ReadError::Invalid_In_EForall_Term)?;
+        at_most(args, 2, Jusitification::EForall(reference, term))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EForall(forall_pos, term) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *forall_pos >= rec.id {
+            return Err(SemanticError::EForallPosLesser);
+        }
+        let forall = &proof.records()[*forall_pos];
+        if !proof.accessible(&rec.ctxt, forall) {
+            return Err(SemanticError::EForallIncompatibleCtxt);
+        }
+        match forall.stmt.get_formula() {
+            Formula::Forall(x, body) => {
+                if body
+                    .substitute_term(*x, term)
+                    .alpha_eq(rec.stmt.get_formula())
+                {
+                    Ok(())
+                } else {
+                    Err(SemanticError::EForallNotMatching)
+                }
+            }
+            _ => Err(SemanticError::EForallFormulaIsForall),
+        }
+    }
+}
+
+/// Introduction of Exists, the term being the witness
+pub struct IExists;
+
+impl Rule for IExists {
+    fn name(&self) -> &str {
+        "IExists"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_IExists_Reference,
+            ReadError::Invalid_In_IExists_Reference,
+        )?;
+        let term = Term::read(args.get(1).ok_or(ReadError::Missing_In_IExists_Term)?)
+            .map_err(ReadError::Invalid_In_IExists_Term)?;
+        at_most(args, 2, Jusitification::IExists(reference, term))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::IExists(instance_pos, term) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *instance_pos >= rec.id {
+            return Err(SemanticError::IExistsPosLesser);
+        }
+        let instance = &proof.records()[*instance_pos];
+        if !proof.accessible(&rec.ctxt, instance) {
+            return Err(SemanticError::IExistsIncompatibleCtxt);
+        }
+        match rec.stmt.get_formula() {
+            Formula::Exists(x, body) => {
+                if body
+                    .substitute_term(*x, term)
+                    .alpha_eq(instance.stmt.get_formula())
+                {
+                    Ok(())
+                } else {
+                    Err(SemanticError::IExistsNotMatching)
+                }
+            }
+            _ => Err(SemanticError::IExistsFormulaIsExists),
+        }
+    }
+}
+
+/// Elimination of Exists, from ∃xφ and ∀y(φ[x:=y]⇒c)
+pub struct EExists;
+
+impl Rule for EExists {
+    fn name(&self) -> &str {
+        "EExists"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let exists = position(
+            args.first(),
+            ReadError::Missing_In_EExists_Exists,
+            ReadError::Invalid_In_EExists_Exists,
+        )?;
+        let forall = position(
+            args.get(1),
+            ReadError::Missing_In_EExists_Forall,
+            ReadError::Invalid_In_EExists_Forall,
+        )?;
+        at_most(args, 2, Jusitification::EExists { exists, forall })
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EExists { exists, forall } = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *exists >= rec.id {
+            return Err(SemanticError::EExistsExistsPosLesser);
+        }
+        if *forall >= rec.id {
+            return Err(SemanticError::EExistsForallPosLesser);
+        }
+        let exists = &proof.records()[*exists];
+        let forall = &proof.records()[*forall];
+        if !proof.accessible(&rec.ctxt, exists) {
+            return Err(SemanticError::EExistsExistsIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, forall) {
+            return Err(SemanticError::EExistsForallIncompatibleCtxt);
+        }
+        if let (Formula::Exists(x, body), Formula::Forall(y, implication)) =
+            (exists.stmt.get_formula(), forall.stmt.get_formula())
+        {
+            if let Formula::Implies(hyp, cons) = implication.as_ref() {
+                if !body.substitute_term(*x, &Term::Variable(*y)).alpha_eq(hyp) {
+                    Err(SemanticError::EExistsBodyNotMatching)
+                } else if cons.has_free(*y) {
+                    Err(SemanticError::EExistsVariableFree)
+                } else if !cons.alpha_eq(rec.stmt.get_formula()) {
+                    Err(SemanticError::EExistsConclusionNotMatching)
+                } else {
+                    Ok(())
+                }
+            } else {
+                Err(SemanticError::EExistsFormulasNotRightKind)
+            }
+        } else {
+            Err(SemanticError::EExistsFormulasNotRightKind)
+        }
+    }
+}
+
+/// Introduction of Necessarily, closing a `Monde` box
+pub struct INec;
+
+impl Rule for INec {
+    fn name(&self) -> &str {
+        "INec"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::INec)
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Statement::Donc(conclusion) = &rec.stmt else {
+            return Err(SemanticError::INecNotSimple);
+        };
+        let (hyp, cons) = proof.closed_box(rec.id)?;
+        match (&hyp.stmt, conclusion) {
+            (Statement::Monde, Formula::Necessarily(necessary)) => {
+                if necessary.as_ref() != cons.stmt.get_formula() {
+                    Err(SemanticError::DoncNecNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            (Statement::Monde, _) => Err(SemanticError::DoncFormulaIsNecessarily),
+            _ => Err(SemanticError::DoncNecNotMonde),
+        }
+    }
+
+    fn closes_box(&self) -> bool {
+        true
+    }
+}
+
+/// Elimination of Necessarily, in an accessible world
+pub struct ENec;
+
+impl Rule for ENec {
+    fn name(&self) -> &str {
+        "ENec"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_ENec_Reference,
+            ReadError::Invalid_In_ENec_Reference,
+        )?;
+        at_most(args, 1, Jusitification::ENec(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::ENec(nec_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *nec_pos >= rec.id {
+            return Err(SemanticError::ENecPosLesser);
+        }
+        let nec = &proof.records()[*nec_pos];
+        if !check_ctxt_compatibility(&rec.ctxt, &nec.ctxt) {
+            return Err(SemanticError::ENecIncompatibleCtxt);
+        }
+        // Each Monde crossed is a step along the accessibility relation.
+        let depth = proof.modal_depth(&rec.ctxt[nec.ctxt.len()..]);
+        let allowed = match proof.modal_system() {
+            ModalSystem::K => depth == 1,
+            ModalSystem::T => depth <= 1,
+            ModalSystem::S4 | ModalSystem::S5 => true,
+        };
+        if !allowed {
+            return Err(SemanticError::ENecWrongDepth);
+        }
+        match nec.stmt.get_formula() {
+            Formula::Necessarily(necessary) => {
+                if **necessary != *rec.stmt.get_formula() {
+                    Err(SemanticError::ENecNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::ENecFormulaIsNecessarily),
+        }
+    }
+}
+
+/// Reiteration of a Possibly in an accessible world (S5)
+pub struct RPos;
+
+impl Rule for RPos {
+    fn name(&self) -> &str {
+        "RPos"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_RPos_Reference,
+            ReadError::Invalid_In_RPos_Reference,
+        )?;
+        at_most(args, 1, Jusitification::RPos(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::RPos(pos_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if proof.modal_system() != ModalSystem::S5 {
+            return Err(SemanticError::RPosNotS5);
+        }
+        if *pos_pos >= rec.id {
+            return Err(SemanticError::RPosPosLesser);
+        }
+        let pos = &proof.records()[*pos_pos];
+        if !check_ctxt_compatibility(&rec.ctxt, &pos.ctxt) {
+            return Err(SemanticError::RPosIncompatibleCtxt);
+        }
+        let formula = rec.stmt.get_formula();
+        if !matches!(formula, Formula::Possibly(_)) {
+            Err(SemanticError::RPosFormulaIsPossibly)
+        } else if pos.stmt.get_formula() != formula {
+            Err(SemanticError::RPosNotMatching)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Introduction of Not, closing a `Supposons` box ending with ⊥
+pub struct INot;
+
+impl Rule for INot {
+    fn name(&self) -> &str {
+        "INot"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::INot)
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Statement::Donc(conclusion) = &rec.stmt else {
+            return Err(SemanticError::INotNotSimple);
+        };
+        let (hyp, cons) = proof.closed_box(rec.id)?;
+        match (&hyp.stmt, conclusion) {
+            (Statement::Soit(_) | Statement::Monde, _) => Err(SemanticError::DoncNotNotSupposons),
+            (_, Formula::Not(hyp_formula)) => {
+                if hyp_formula.as_ref() != hyp.stmt.get_formula() {
+                    Err(SemanticError::DoncHypNotMatching)
+                } else if *cons.stmt.get_formula() != Formula::Bottom {
+                    Err(SemanticError::DoncNotConsIsBottom)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::DoncFormulaIsNot),
+        }
+    }
+
+    fn closes_box(&self) -> bool {
+        true
+    }
+}
+
+/// Elimination of Not, from a formula and its negation
+pub struct ENot;
+
+impl Rule for ENot {
+    fn name(&self) -> &str {
+        "ENot"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let hyp = position(
+            args.first(),
+            ReadError::Missing_In_ENot_Hyp,
+            ReadError::Invalid_In_ENot_Hyp,
+        )?;
+        let negation = position(
+            args.get(1),
+            ReadError::Missing_In_ENot_Negation,
+            ReadError::Invalid_In_ENot_Negation,
+        )?;
+        at_most(args, 2, Jusitification::ENot { hyp, negation })
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::ENot { hyp, negation } = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *hyp >= rec.id {
+            return Err(SemanticError::ENotHypPosLesser);
+        }
+        if *negation >= rec.id {
+            return Err(SemanticError::ENotNegationPosLesser);
+        }
+        let hyp = &proof.records()[*hyp];
+        let negation = &proof.records()[*negation];
+        if !proof.accessible(&rec.ctxt, hyp) {
+            return Err(SemanticError::ENotHypIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, negation) {
+            return Err(SemanticError::ENotNegationIncompatibleCtxt);
+        }
+        match negation.stmt.get_formula() {
+            Formula::Not(negated) => {
+                if **negated != *hyp.stmt.get_formula() {
+                    Err(SemanticError::ENotHypNotMatching)
+                } else if *rec.stmt.get_formula() != Formula::Bottom {
+                    Err(SemanticError::ENotFormulaIsBot)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::ENotNegationIsNot),
+        }
+    }
+}
+
+/// Introduction of Equiv, from both implications
+pub struct IEquiv;
+
+impl Rule for IEquiv {
+    fn name(&self) -> &str {
+        "IEquiv"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let forward = position(
+            args.first(),
+            ReadError::Missing_In_IEquiv_Forward,
+            ReadError::Invalid_In_IEquiv_Forward,
+        )?;
+        let backward = position(
+            args.get(1),
+            ReadError::Missing_In_IEquiv_Backward,
+            ReadError::Invalid_In_IEquiv_Backward,
+        )?;
+        at_most(args, 2, Jusitification::IEquiv { forward, backward })
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::IEquiv { forward, backward } = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        if *forward >= rec.id {
+            return Err(SemanticError::IEquivForwardPosLesser);
+        }
+        if *backward >= rec.id {
+            return Err(SemanticError::IEquivBackwardPosLesser);
+        }
+        let forward = &proof.records()[*forward];
+        let backward = &proof.records()[*backward];
+        if !proof.accessible(&rec.ctxt, forward) {
+            return Err(SemanticError::IEquivForwardIncompatibleCtxt);
+        }
+        if !proof.accessible(&rec.ctxt, backward) {
+            return Err(SemanticError::IEquivBackwardIncompatibleCtxt);
+        }
+        match rec.stmt.get_formula() {
+            Formula::Equiv(a, b) => {
+                let a_to_b = Formula::Implies(a.clone(), b.clone());
+                let b_to_a = Formula::Implies(b.clone(), a.clone());
+                if *forward.stmt.get_formula() != a_to_b {
+                    Err(SemanticError::IEquivForwardNotMatching)
+                } else if *backward.stmt.get_formula() != b_to_a {
+                    Err(SemanticError::IEquivBackwardNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::IEquivFormulaIsEquiv),
+        }
+    }
+}
+
+/// Elimination of Equiv (getting the left to right implication)
+pub struct EEquivL;
+
+impl Rule for EEquivL {
+    fn name(&self) -> &str {
+        "EEquivL"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_EEquivL_Reference,
+            ReadError::Invalid_In_EEquivL_Reference,
+        )?;
+        at_most(args, 1, Jusitification::EEquivL(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EEquivL(equiv_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        match equivalence(proof, rec, *equiv_pos)? {
+            Formula::Equiv(a, b) => {
+                if Formula::Implies(a.clone(), b.clone()) != *rec.stmt.get_formula() {
+                    Err(SemanticError::EEquivNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::EEquivFormulaIsEquiv),
+        }
+    }
+}
+
+/// Elimination of Equiv (getting the right to left implication)
+pub struct EEquivR;
+
+impl Rule for EEquivR {
+    fn name(&self) -> &str {
+        "EEquivR"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        let reference = position(
+            args.first(),
+            ReadError::Missing_In_EEquivR_Reference,
+            ReadError::Invalid_In_EEquivR_Reference,
+        )?;
+        at_most(args, 1, Jusitification::EEquivR(reference))
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let Jusitification::EEquivR(equiv_pos) = &rec.justif else {
+            return Err(SemanticError::InternalError);
+        };
+        match equivalence(proof, rec, *equiv_pos)? {
+            Formula::Equiv(a, b) => {
+                if Formula::Implies(b.clone(), a.clone()) != *rec.stmt.get_formula() {
+                    Err(SemanticError::EEquivNotMatching)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(SemanticError::EEquivFormulaIsEquiv),
+        }
+    }
+}
+
+/// Returns the formula of the record `equiv_pos` an Equiv is eliminated from.
+fn equivalence<'a>(
+    proof: &'a Proof,
+    rec: &Record,
+    equiv_pos: usize,
+) -> Result<&'a Formula, SemanticError> {
+    if equiv_pos >= rec.id {
+        return Err(SemanticError::EEquivPosLesser);
+    }
+    let equiv = &proof.records()[equiv_pos];
+    if !proof.accessible(&rec.ctxt, equiv) {
+        return Err(SemanticError::EEquivIncompatibleCtxt);
+    }
+    Ok(equiv.stmt.get_formula())
+}
+
+/// Introduction of Top
+pub struct ITop;
+
+impl Rule for ITop {
+    fn name(&self) -> &str {
+        "ITop"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::ITop)
+    }
+
+    fn check(&self, _: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        match rec.stmt.get_formula() {
+            Formula::Top => Ok(()),
+            _ => Err(SemanticError::ITopFormulaIsTop),
+        }
+    }
+}
+
+/// Placeholder, `?`: the formula is to be derived from earlier records
+pub struct Gap;
+
+impl Rule for Gap {
+    fn name(&self) -> &str {
+        "?"
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+        at_most(args, 0, Jusitification::Gap)
+    }
+
+    fn check(&self, _: &Proof, _: &Record) -> Result<(), SemanticError> {
+        Err(SemanticError::GapNotFilled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::CheckUpResult;
+
+    /// Tertium non datur, `TND`: any A∨¬A, citing no record.
+    struct Tnd;
+
+    impl Rule for Tnd {
+        fn name(&self) -> &str {
+            "TND"
+        }
+
+        fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+            read_named(self.name(), args)
+        }
+
+        fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+            if proof.logic() != Logic::Classical || !rec.justif.references().is_empty() {
+                return Err(SemanticError::RuleRejected(
+                    "TND is classical and cites no record".to_string(),
+                ));
+            }
+            match rec.stmt.get_formula() {
+                Formula::Or(a, not_a) if **not_a == Formula::Not(a.clone()) => Ok(()),
+                _ => Err(SemanticError::RuleRejected("TND gives A∨¬A".to_string())),
+            }
+        }
+
+        fn logic(&self) -> Logic {
+            Logic::Classical
+        }
+    }
+
+    #[test]
+    fn aliases() {
+        let mut rules = RuleRegistry::standard().clone();
+        assert!(rules.alias("→E", "EImpl"));
+        assert!(!rules.alias("∧I", "AndI"));
+        let input = "0;0;Supposons a;Hyp\n1;0,1;Supposons a⇒b;Hyp\n2;0,1;b;→E 0 1\n";
+        assert!(Proof::read_proof(input).is_err());
+        let mut proof = Proof::read_proof_with(input, rules).unwrap();
+        proof.check();
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        assert_eq!(proof.to_string().lines().nth(2), Some("2;0,1;b;EImpl 0 1"));
+    }
+
+    #[test]
+    fn custom_rules() {
+        let input = "0;0;Supposons a;Hyp\n1;0;b∨¬b;TND\n2;0;a∧(b∨¬b);IAnd 0 1\n";
+        let mut proof = Proof::read_proof(input).unwrap();
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+        proof.add_rule(Tnd);
+        proof.check();
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        assert_eq!(
+            proof.classical_dependencies()[2]
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        proof.set_logic(Logic::Intuitionistic);
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+
+        // A primitive rule may be replaced, here by one forbidding Rwrt.
+        struct NoRwrt;
+        impl Rule for NoRwrt {
+            fn name(&self) -> &str {
+                "Rwrt"
+            }
+            fn read(&self, args: &[&str]) -> Result<Jusitification, ReadError> {
+                Rwrt.read(args)
+            }
+            fn check(&self, _: &Proof, _: &Record) -> Result<(), SemanticError> {
+                Err(SemanticError::RuleRejected(
+                    "Rwrt is not allowed".to_string(),
+                ))
+            }
+        }
+        let mut proof = Proof::read_proof("0;0;Supposons a∧b;Hyp\n1;0;a∧b;Rwrt 0\n").unwrap();
+        proof.check();
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        proof.add_rule(NoRwrt);
+        proof.check();
+        assert!(matches!(
+            proof.state(),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn ior_errors() {
+        // IOrR 0 c introduces a∨c from a: the left formula is the one cited.
+        for (input, left) in [
+            ("0;0;Supposons a;Hyp\n1;0;b∨c;IOrR 0 c\n", true),
+            ("0;0;Supposons a;Hyp\n1;0;a∨b;IOrR 0 c\n", false),
+        ] {
+            let mut proof = Proof::read_proof(input).unwrap();
+            proof.check();
+            let CheckUpResult::SemanticErrors { errors, .. } = proof.state() else {
+                panic!("{} is not valid", input);
+            };
+            match &errors[..] {
+                [(1, SemanticError::IOrRLeftNotMatching)] => assert!(left),
+                [(1, SemanticError::IOrRRightNotMatching)] => assert!(!left),
+                _ => panic!("{:?}", errors),
+            }
+        }
+    }
+}
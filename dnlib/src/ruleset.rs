@@ -0,0 +1,383 @@
+use std::fmt;
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::formula::{Formula, TokenizationError};
+use crate::justif::{Jusitification, ReadError as JustifReadError};
+use crate::proof::{Logic, Proof, SemanticError};
+use crate::record::{Record, Statement};
+use crate::rule::{cited_formulas, read_named, Rule, RuleRegistry};
+use crate::sequent::{RecordError, Sequent};
+use crate::subst::Substitution;
+
+#[derive(Error, Debug)]
+pub enum RuleSetError {
+    /// The line declares no rule
+    #[error("A rule is declared as Name: premise, … ⊢ conclusion, or as Name = Primitive")]
+    InvalidDeclaration,
+    /// The name spells a rule already
+    #[error("{0} is declared twice")]
+    Duplicate(String),
+    /// Only the logics are allowed before the name
+    #[error("{0} is not a logic, the rule may be declared minimal, intuitionistic or classical")]
+    UnknownLogic(String),
+    /// The name after `=` is not the one of a primitive rule
+    #[error("{0} is not a primitive rule")]
+    UnknownPrimitive(String),
+    /// The discharged hypothesis is not closed by a `]`
+    #[error("The discharged hypothesis should be written between [ and ]")]
+    UnclosedHypothesis,
+    /// The discharged hypothesis is not a formula
+    #[error("Error reading the discharged hypothesis: {0}")]
+    InvalidHypothesis(TokenizationError),
+    /// The schema is not a sequent
+    #[error("Error reading the schema of the rule: {0}")]
+    InvalidSchema(RecordError),
+    /// The schema concludes no formula, or several
+    #[error("The schema should have exactly one conclusion")]
+    NotOneConclusion,
+    /// A box is discharged, but how it ends is not told
+    #[error("The discharged hypothesis should be followed by the formula ending its box")]
+    MissingBoxEnd,
+}
+
+#[derive(Error, Debug)]
+#[error("At line {line}: {content}")]
+pub struct ReadError {
+    pub line: usize,
+    #[source]
+    pub content: RuleSetError,
+}
+
+/// A rule described by its schema, such as `∧I: A, B ⊢ A∧B` or `⇒I: [A] B ⊢ A⇒B`.
+///
+/// Records cite the premises in order, as `∧I 2 5`. A rule discharging a hypothesis justifies a
+/// `Donc` closing the box opened by `Supposons` the hypothesis and ending with the first premise,
+/// the records cited being the other premises.
+///
+/// A rule named after a primitive one, as `IAnd: A, B ⊢ A∧B`, is read as the primitive rule, so
+/// that the prover and the translations of proofs still know its records. The schema decides
+/// whether a record is valid, the primitive rule telling why it is not when it can.
+#[derive(Clone)]
+pub struct SchemaRule {
+    name: String,
+    discharged: Option<Formula>,
+    premises: Vec<Formula>,
+    conclusion: Formula,
+    logic: Logic,
+    primitive: Option<Arc<dyn Rule>>,
+}
+
+impl fmt::Debug for SchemaRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaRule")
+            .field("name", &self.name)
+            .field("discharged", &self.discharged)
+            .field("premises", &self.premises)
+            .field("conclusion", &self.conclusion)
+            .field("logic", &self.logic)
+            .finish()
+    }
+}
+
+impl SchemaRule {
+    /// Reads the rule `name` holding in `logic` from its schema. It is read as the primitive rule
+    /// `name`, if there is one.
+    pub fn new(name: &str, schema: &str, logic: Logic) -> Result<Self, RuleSetError> {
+        let schema = schema.trim();
+        let (discharged, schema) = match schema.strip_prefix('[') {
+            Some(rest) => {
+                let (hyp, rest) = rest
+                    .split_once(']')
+                    .ok_or(RuleSetError::UnclosedHypothesis)?;
+                let hyp = Formula::read(hyp.trim()).map_err(RuleSetError::InvalidHypothesis)?;
+                (Some(hyp), rest)
+            }
+            None => (None, schema),
+        };
+        let schema = Sequent::read(schema).map_err(RuleSetError::InvalidSchema)?;
+        let [conclusion] = &schema.succedent[..] else {
+            return Err(RuleSetError::NotOneConclusion);
+        };
+        if discharged.is_some() && schema.antecedent.is_empty() {
+            return Err(RuleSetError::MissingBoxEnd);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            discharged,
+            conclusion: conclusion.clone(),
+            premises: schema.antecedent,
+            logic,
+            primitive: RuleRegistry::primitive(name),
+        })
+    }
+
+    /// Returns the hypothesis discharged by the rule.
+    pub fn discharged(&self) -> Option<&Formula> {
+        self.discharged.as_ref()
+    }
+
+    /// Returns the premises, the formula ending the box first if a hypothesis is discharged.
+    pub fn premises(&self) -> &[Formula] {
+        &self.premises
+    }
+
+    pub fn conclusion(&self) -> &Formula {
+        &self.conclusion
+    }
+
+    /// Checks `rec` against the schema of the rule.
+    fn check_schema(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        if !self.logic.holds_in(proof.logic()) {
+            return Err(SemanticError::SchemaNotInLogic(self.name.clone()));
+        }
+        let mut subst = Substitution::new();
+        let mut premises = self.premises.iter();
+        if let Some(discharged) = &self.discharged {
+            if !matches!(rec.stmt, Statement::Donc(_)) {
+                return Err(SemanticError::SchemaNotSimple(self.name.clone()));
+            }
+            let (hyp, cons) = proof.closed_box(rec.id)?;
+            let Statement::Supposons(hyp) = &hyp.stmt else {
+                return Err(SemanticError::SchemaNotSupposons);
+            };
+            let end = premises.next().ok_or(SemanticError::InternalError)?;
+            if !discharged.match_pattern_into(hyp, &mut subst)
+                || !end.match_pattern_into(cons.stmt.get_formula(), &mut subst)
+            {
+                return Err(SemanticError::SchemaNotMatching(self.name.clone()));
+            }
+        }
+        let premises: Vec<&Formula> = premises.collect();
+        let cited = cited_formulas(proof, rec, premises.len())?;
+        let matching = premises
+            .iter()
+            .zip(cited)
+            .all(|(pattern, premise)| pattern.match_pattern_into(premise, &mut subst))
+            && self
+                .conclusion
+                .match_pattern_into(rec.stmt.get_formula(), &mut subst);
+        if matching {
+            Ok(())
+        } else {
+            Err(SemanticError::SchemaNotMatching(self.name.clone()))
+        }
+    }
+}
+
+impl Rule for SchemaRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn read(&self, args: &[&str]) -> Result<Jusitification, JustifReadError> {
+        match &self.primitive {
+            Some(primitive) => primitive.read(args),
+            None => read_named(&self.name, args),
+        }
+    }
+
+    fn check(&self, proof: &Proof, rec: &Record) -> Result<(), SemanticError> {
+        let checked = self.check_schema(proof, rec);
+        match &self.primitive {
+            Some(primitive) if checked.is_err() => primitive.check(proof, rec).and(checked),
+            _ => checked,
+        }
+    }
+
+    fn closes_box(&self) -> bool {
+        self.discharged.is_some()
+    }
+
+    fn logic(&self) -> Logic {
+        self.logic
+    }
+
+    fn schema(&self) -> Option<&SchemaRule> {
+        Some(self)
+    }
+}
+
+impl RuleRegistry {
+    /// Reads a rule set, one rule per line, the rules of which replace the primitive ones:
+    ///
+    /// - `⊥E = Efq` lets records spell the primitive rule `Efq` as `⊥E`;
+    /// - `∧I: A, B ⊢ A∧B` declares a [`SchemaRule`], `⇒I: [A] B ⊢ A⇒B` discharging `A`. The
+    ///   declaration may start with the logic the rule holds in, as in
+    ///   `classical ¬¬E: ¬¬A ⊢ A`; it holds in minimal logic otherwise.
+    ///
+    /// Blank lines and lines starting by `(*` are ignored.
+    pub fn read_rule_set(input: &str) -> Result<Self, ReadError> {
+        let mut rules = RuleRegistry::new();
+        for (line, declaration) in input.split('\n').enumerate() {
+            let declaration = declaration.trim();
+            if declaration.is_empty() || declaration.starts_with("(*") {
+                continue;
+            }
+            rules
+                .declare(declaration)
+                .map_err(|content| ReadError { line, content })?;
+        }
+        Ok(rules)
+    }
+
+    /// Adds the rule declared on a line of a rule set.
+    fn declare(&mut self, declaration: &str) -> Result<(), RuleSetError> {
+        if let Some((name, schema)) = declaration.split_once(':') {
+            let (logic, name) = match name.split_whitespace().collect::<Vec<_>>()[..] {
+                [name] => (Logic::Minimal, name),
+                ["minimal", name] => (Logic::Minimal, name),
+                ["intuitionistic", name] => (Logic::Intuitionistic, name),
+                ["classical", name] => (Logic::Classical, name),
+                [logic, _] => return Err(RuleSetError::UnknownLogic(logic.to_string())),
+                _ => return Err(RuleSetError::InvalidDeclaration),
+            };
+            let rule = SchemaRule::new(name, schema, logic)?;
+            self.insert(name, Arc::new(rule))
+        } else if let Some((name, primitive)) = declaration.split_once('=') {
+            let (name, primitive) = (name.trim(), primitive.trim());
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(RuleSetError::InvalidDeclaration);
+            }
+            let rule = RuleRegistry::primitive(primitive)
+                .ok_or_else(|| RuleSetError::UnknownPrimitive(primitive.to_string()))?;
+            self.insert(name, rule)
+        } else {
+            Err(RuleSetError::InvalidDeclaration)
+        }
+    }
+
+    fn insert(&mut self, name: &str, rule: Arc<dyn Rule>) -> Result<(), RuleSetError> {
+        if self.get(name).is_some() {
+            return Err(RuleSetError::Duplicate(name.to_string()));
+        }
+        self.insert_spelling(name, rule);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::CheckUpResult;
+
+    const ALTERNATIVE: &str = include_str!("../rules/alternative.rules");
+
+    fn check(input: &str, rules: &RuleRegistry, logic: Logic) -> CheckUpResult {
+        let mut proof = Proof::read_proof_with(input, rules.clone()).unwrap();
+        proof.set_logic(logic);
+        proof.check();
+        proof.into_state()
+    }
+
+    #[test]
+    fn default_rule_set() {
+        let primitives: Vec<&str> = RuleRegistry::primitives().names().collect();
+        let standard: Vec<&str> = RuleRegistry::standard().names().collect();
+        assert_eq!(primitives, standard);
+    }
+
+    #[test]
+    fn schemas_check_as_primitives() {
+        for input in [
+            include_str!("../proofs/first.proof"),
+            include_str!("../proofs/second.proof"),
+            include_str!("../proofs/third.proof"),
+            include_str!("../proofs/fourth.proof"),
+            include_str!("../proofs/fifth.proof"),
+            include_str!("../proofs/sixth.proof"),
+        ] {
+            for logic in [Logic::Minimal, Logic::Intuitionistic, Logic::Classical] {
+                let standard = check(input, RuleRegistry::standard(), logic);
+                let primitive = check(input, RuleRegistry::primitives(), logic);
+                assert_eq!(format!("{:?}", standard), format!("{:?}", primitive));
+            }
+        }
+        // A record rejected by its schema is explained by the primitive rule.
+        let input = "0;0;Supposons a;Hyp\n1;0;a∧b;IAnd 0 0\n";
+        let standard = check(input, RuleRegistry::standard(), Logic::Classical);
+        assert!(matches!(
+            standard,
+            CheckUpResult::SemanticErrors { ref errors, .. }
+                if matches!(errors[..], [(1, SemanticError::IAndRightNotMatching)])
+        ));
+    }
+
+    #[test]
+    fn alternative_rule_set() {
+        let rules = RuleRegistry::read_rule_set(ALTERNATIVE).unwrap();
+        let input = "0;0;Supposons ¬¬a;Hyp\n\
+                     1;0;a;¬¬E 0\n\
+                     2;0,2;Supposons b;Hyp\n\
+                     3;0,2;a∧b;∧I 1 2\n\
+                     4;0;Donc b⇒a∧b;⇒I\n\
+                     5;0,5;Supposons ⊥;Hyp\n\
+                     6;0,5;a;⊥E 5\n\
+                     7;0;Donc ⊥⇒a;⇒I\n";
+        assert!(matches!(
+            check(input, &rules, Logic::Classical),
+            CheckUpResult::Valid
+        ));
+        assert!(matches!(
+            check(input, &rules, Logic::Intuitionistic),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+        // The primitive spellings are not part of the rule set.
+        let primitive = "0;0;Supposons a;Hyp\n1;0;a∧a;IAnd 0 0\n";
+        assert!(matches!(
+            check(primitive, &rules, Logic::Classical),
+            CheckUpResult::SemanticErrors { first_error: 1, .. }
+        ));
+        // ¬I discharges a hypothesis, the box ending with ⊥.
+        let input = "0;0;Supposons ¬a;Hyp\n\
+                     1;0,1;Supposons a;Hyp\n\
+                     2;0,1;⊥;¬E 1 0\n\
+                     3;0;Donc ¬a;¬I\n";
+        assert!(matches!(
+            check(input, &rules, Logic::Minimal),
+            CheckUpResult::Valid
+        ));
+        let input = "0;0;Supposons ¬a;Hyp\n\
+                     1;0,1;Supposons a;Hyp\n\
+                     2;0,1;a;Rwrt 1\n\
+                     3;0;Donc ¬a;¬I\n";
+        assert!(matches!(
+            check(input, &rules, Logic::Minimal),
+            CheckUpResult::SemanticErrors { first_error: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_rule_sets() {
+        let error = |input: &str| {
+            RuleRegistry::read_rule_set(input)
+                .err()
+                .map(|e| (e.line, e.content))
+        };
+        assert!(matches!(
+            error("∧I = AndI"),
+            Some((0, RuleSetError::UnknownPrimitive(_)))
+        ));
+        assert!(matches!(
+            error("(* comment *)\n\n⊥E = Efq\n⊥E = Raa"),
+            Some((3, RuleSetError::Duplicate(_)))
+        ));
+        assert!(matches!(
+            error("⇒I: [A B ⊢ A⇒B"),
+            Some((0, RuleSetError::UnclosedHypothesis))
+        ));
+        assert!(matches!(
+            error("⇒I: [A] ⊢ A⇒B"),
+            Some((0, RuleSetError::MissingBoxEnd))
+        ));
+        assert!(matches!(
+            error("modal □E: □A ⊢ A"),
+            Some((0, RuleSetError::UnknownLogic(_)))
+        ));
+        assert!(matches!(
+            error("∨I: A ⊢ A∨B, B∨A"),
+            Some((0, RuleSetError::NotOneConclusion))
+        ));
+    }
+}
@@ -0,0 +1,1440 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::SplitWhitespace;
+
+use thiserror::Error;
+
+use crate::formula::{Formula, TokenizationError};
+use crate::justif::Jusitification;
+use crate::proof::{CheckUpResult, Proof};
+use crate::record::{Record, Statement};
+use crate::term::{fresh_variable, Term};
+
+#[derive(Error, Debug)]
+pub enum RecordError {
+    /// A field is missing
+    #[error("A field is missing")]
+    MissingField,
+    /// The id field is invalid
+    #[error("The id field is invalid")]
+    InvalidId,
+    /// There are too many fields
+    #[error("There are too many fields")]
+    TooMuch,
+    /// The sequent has no turnstile
+    #[error("The sequent has no ⊢")]
+    MissingTurnstile,
+    /// Error parsing a formula of the sequent
+    #[error("Error parsing a formula of the sequent: {0}")]
+    InvalidFormula(TokenizationError),
+    /// The rule is missing
+    #[error("The rule is missing")]
+    MissingRule,
+    /// The rule is unknown
+    #[error("The rule is unknown")]
+    UnknownRule,
+    /// The rule is missing the position of a premise
+    #[error("The rule is missing the position of a premise")]
+    MissingReference,
+    /// The position of a premise is not a valid number
+    #[error("The position of a premise is not a valid number: {0}")]
+    InvalidReference(ParseIntError),
+    /// The rule is missing the term instantiating the quantifier
+    #[error("The rule is missing the term instantiating the quantifier")]
+    MissingTerm,
+    /// The term instantiating the quantifier is invalid
+    #[error("The term instantiating the quantifier is invalid: {0}")]
+    InvalidTerm(TokenizationError),
+    /// The eigenvariable should be a single letter
+    #[error("The eigenvariable should be a single letter")]
+    InvalidVariable,
+    /// The rule is given too many arguments
+    #[error("The rule is given too many arguments")]
+    TooManyArguments,
+}
+
+#[derive(Error, Debug)]
+#[error("At {line}: {content}")]
+pub struct ReadError {
+    pub line: usize,
+    #[source]
+    pub content: RecordError,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SequentError {
+    /// The id of the record is not its position
+    #[error("The id of the record is not its position")]
+    IncorrectId,
+    /// A premise has to be derived before the sequent
+    #[error("Premise {0} has to be derived before the sequent")]
+    PremiseNotBefore(usize),
+    /// In LJ, a sequent has at most one formula on the right
+    #[error("In LJ, a sequent has at most one formula on the right")]
+    NotIntuitionistic,
+    /// The eigenvariable must not be free in the conclusion
+    #[error("The eigenvariable {0} must not be free in the conclusion")]
+    EigenvariableNotFresh(char),
+    /// The sequent is not an instance of the axiom
+    #[error("The sequent is not an instance of {0}")]
+    NotAnAxiom(&'static str),
+    /// The sequent does not follow from its premises by the rule
+    #[error("The sequent does not follow from its premises by {0}")]
+    DoesNotFollow(&'static str),
+}
+
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum TranslationError {
+    /// Only a checked, valid proof can be translated
+    #[error("Only a checked, valid proof can be translated")]
+    NotValid,
+    /// The record has no counterpart in LK
+    #[error("Record {0} has no counterpart in LK")]
+    Unsupported(usize),
+}
+
+/// The two sequent calculi of Gentzen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Calculus {
+    /// Classical sequent calculus
+    #[default]
+    Lk,
+    /// Intuitionistic sequent calculus: at most one formula on the right
+    Lj,
+}
+
+/// A sequent `Γ ⊢ Δ`: the conjunction of the antecedent `Γ` entails the disjunction of the
+/// succedent `Δ`.
+///
+/// Both sides are multisets: the order of the formulas does not matter (exchange is implicit)
+/// but their number does.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequent {
+    pub antecedent: Vec<Formula>,
+    pub succedent: Vec<Formula>,
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Sequent {
+    pub fn new(antecedent: Vec<Formula>, succedent: Vec<Formula>) -> Self {
+        Self {
+            antecedent,
+            succedent,
+        }
+    }
+
+    /// Reads a sequent such as `A, A⇒B ⊢ B`.
+    pub fn read(input: &str) -> Result<Self, RecordError> {
+        let (left, right) = input.split_once('⊢').ok_or(RecordError::MissingTurnstile)?;
+        Ok(Self::new(read_formulas(left)?, read_formulas(right)?))
+    }
+
+    /// Tells whether both sequents have the same formulas on each side, up to their order and
+    /// the names of bound variables.
+    pub fn same_as(&self, other: &Sequent) -> bool {
+        same_formulas(&self.antecedent, &other.antecedent)
+            && same_formulas(&self.succedent, &other.succedent)
+    }
+
+    /// Returns the term variables free in a formula of the sequent.
+    pub fn free_term_variables(&self) -> BTreeSet<char> {
+        self.antecedent
+            .iter()
+            .chain(self.succedent.iter())
+            .flat_map(|f| f.free_term_variables())
+            .collect()
+    }
+
+    fn side(&self, side: Side) -> &[Formula] {
+        match side {
+            Side::Left => &self.antecedent,
+            Side::Right => &self.succedent,
+        }
+    }
+
+    /// Returns the sequent where the formula `i` of `side` is replaced by `left` on the left
+    /// and `right` on the right.
+    fn replace(&self, side: Side, i: usize, left: &[Formula], right: &[Formula]) -> Sequent {
+        let (mut antecedent, mut succedent) = (self.antecedent.clone(), self.succedent.clone());
+        match side {
+            Side::Left => antecedent.remove(i),
+            Side::Right => succedent.remove(i),
+        };
+        antecedent.extend_from_slice(left);
+        succedent.extend_from_slice(right);
+        Sequent::new(antecedent, succedent)
+    }
+
+    /// Returns the sequent with `left` added on the left and `right` added on the right.
+    fn extended(&self, left: &[Formula], right: &[Formula]) -> Sequent {
+        Sequent::new(
+            [self.antecedent.as_slice(), left].concat(),
+            [self.succedent.as_slice(), right].concat(),
+        )
+    }
+}
+
+impl fmt::Display for Sequent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |formulas: &[Formula]| {
+            formulas
+                .iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        if self.antecedent.is_empty() {
+            write!(f, "⊢")?;
+        } else {
+            write!(f, "{} ⊢", join(&self.antecedent))?;
+        }
+        if !self.succedent.is_empty() {
+            write!(f, " {}", join(&self.succedent))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a comma separated list of formulas, commas between the parentheses of a term being
+/// part of the formula.
+fn read_formulas(input: &str) -> Result<Vec<Formula>, RecordError> {
+    let mut formulas = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                formulas.push(&input[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    formulas.push(&input[start..]);
+    if formulas.len() == 1 && formulas[0].trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    formulas
+        .into_iter()
+        .map(|f| Formula::read(f.trim()).map_err(RecordError::InvalidFormula))
+        .collect()
+}
+
+/// Tells whether `a` and `b` are the same multiset of formulas, up to the names of bound
+/// variables.
+fn same_formulas(a: &[Formula], b: &[Formula]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut unused: Vec<&Formula> = b.iter().collect();
+    a.iter()
+        .all(|f| match unused.iter().position(|g| f.alpha_eq(g)) {
+            Some(i) => {
+                unused.swap_remove(i);
+                true
+            }
+            None => false,
+        })
+}
+
+/// Removes one occurrence of `f` from `formulas`, returning whether there was one.
+fn remove_one(formulas: &mut Vec<Formula>, f: &Formula) -> bool {
+    match formulas.iter().position(|g| g.alpha_eq(f)) {
+        Some(i) => {
+            formulas.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the formulas of `a` that are not in `b`, counting multiplicities.
+fn difference(a: &[Formula], b: &[Formula]) -> Vec<Formula> {
+    let mut b = b.to_vec();
+    a.iter()
+        .filter(|f| !remove_one(&mut b, f))
+        .cloned()
+        .collect()
+}
+
+/// Returns the formulas of `a` that are also in `b`, counting multiplicities.
+fn intersection(a: &[Formula], b: &[Formula]) -> Vec<Formula> {
+    let mut b = b.to_vec();
+    a.iter()
+        .filter(|f| remove_one(&mut b, f))
+        .cloned()
+        .collect()
+}
+
+fn implies(a: &Formula, b: &Formula) -> Formula {
+    Formula::Implies(Box::new(a.clone()), Box::new(b.clone()))
+}
+
+/// A rule of the sequent calculus, with the positions of its premises.
+///
+/// Context formulas are shared by the premises of `AndR`, `OrL` and `EquivR`; the premises of
+/// `Cut`, `ImpL` and `RLImpL` have their own contexts, which the conclusion gathers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SequentRule {
+    /// Γ, A ⊢ A, Δ
+    Axiom,
+    /// Γ, ⊥ ⊢ Δ
+    BottomLeft,
+    /// Γ ⊢ ⊤, Δ
+    TopRight,
+    /// Γ ⊢ A, Δ and Σ, A ⊢ Π give Γ, Σ ⊢ Δ, Π
+    Cut(usize, usize),
+    WeakeningLeft(usize),
+    WeakeningRight(usize),
+    ContractionLeft(usize),
+    ContractionRight(usize),
+    NotLeft(usize),
+    NotRight(usize),
+    AndLeft1(usize),
+    AndLeft2(usize),
+    AndRight(usize, usize),
+    OrLeft(usize, usize),
+    OrRight1(usize),
+    OrRight2(usize),
+    ImpliesLeft(usize, usize),
+    ImpliesRight(usize),
+    RLImpliesLeft(usize, usize),
+    RLImpliesRight(usize),
+    /// Γ, A⇒B, B⇒A ⊢ Δ gives Γ, A⇔B ⊢ Δ
+    EquivLeft(usize),
+    EquivRight(usize, usize),
+    /// Instantiates the universal formula with a term
+    ForallLeft(usize, Term),
+    /// Introduces a universal formula from an eigenvariable
+    ForallRight(usize, char),
+    /// Introduces an existential formula on the left from an eigenvariable
+    ExistsLeft(usize, char),
+    /// Introduces an existential formula from a witness
+    ExistsRight(usize, Term),
+}
+
+fn read_reference(s: &mut SplitWhitespace) -> Result<usize, RecordError> {
+    s.next()
+        .ok_or(RecordError::MissingReference)?
+        .parse::<usize>()
+        .map_err(RecordError::InvalidReference)
+}
+
+fn read_term(s: &mut SplitWhitespace) -> Result<Term, RecordError> {
+    Term::read(s.next().ok_or(RecordError::MissingTerm)?).map_err(RecordError::InvalidTerm)
+}
+
+fn read_variable(s: &mut SplitWhitespace) -> Result<char, RecordError> {
+    let mut chars = s.next().ok_or(RecordError::InvalidVariable)?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(x), None) if x.is_ascii_alphabetic() => Ok(x),
+        _ => Err(RecordError::InvalidVariable),
+    }
+}
+
+impl SequentRule {
+    /// Reads a rule and the positions of its premises, such as `Cut 2 5`.
+    pub fn read(input: &str) -> Result<Self, RecordError> {
+        let s = &mut input.split_whitespace();
+        let rule = match s.next().ok_or(RecordError::MissingRule)? {
+            "Ax" => Self::Axiom,
+            "BotL" => Self::BottomLeft,
+            "TopR" => Self::TopRight,
+            "Cut" => Self::Cut(read_reference(s)?, read_reference(s)?),
+            "WL" => Self::WeakeningLeft(read_reference(s)?),
+            "WR" => Self::WeakeningRight(read_reference(s)?),
+            "CL" => Self::ContractionLeft(read_reference(s)?),
+            "CR" => Self::ContractionRight(read_reference(s)?),
+            "NotL" => Self::NotLeft(read_reference(s)?),
+            "NotR" => Self::NotRight(read_reference(s)?),
+            "AndL1" => Self::AndLeft1(read_reference(s)?),
+            "AndL2" => Self::AndLeft2(read_reference(s)?),
+            "AndR" => Self::AndRight(read_reference(s)?, read_reference(s)?),
+            "OrL" => Self::OrLeft(read_reference(s)?, read_reference(s)?),
+            "OrR1" => Self::OrRight1(read_reference(s)?),
+            "OrR2" => Self::OrRight2(read_reference(s)?),
+            "ImpL" => Self::ImpliesLeft(read_reference(s)?, read_reference(s)?),
+            "ImpR" => Self::ImpliesRight(read_reference(s)?),
+            "RLImpL" => Self::RLImpliesLeft(read_reference(s)?, read_reference(s)?),
+            "RLImpR" => Self::RLImpliesRight(read_reference(s)?),
+            "EquivL" => Self::EquivLeft(read_reference(s)?),
+            "EquivR" => Self::EquivRight(read_reference(s)?, read_reference(s)?),
+            "ForallL" => Self::ForallLeft(read_reference(s)?, read_term(s)?),
+            "ForallR" => Self::ForallRight(read_reference(s)?, read_variable(s)?),
+            "ExistsL" => Self::ExistsLeft(read_reference(s)?, read_variable(s)?),
+            "ExistsR" => Self::ExistsRight(read_reference(s)?, read_term(s)?),
+            _ => return Err(RecordError::UnknownRule),
+        };
+        match s.next() {
+            Some(_) => Err(RecordError::TooManyArguments),
+            None => Ok(rule),
+        }
+    }
+
+    /// Returns the name of the rule, as written in a proof.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Axiom => "Ax",
+            Self::BottomLeft => "BotL",
+            Self::TopRight => "TopR",
+            Self::Cut(_, _) => "Cut",
+            Self::WeakeningLeft(_) => "WL",
+            Self::WeakeningRight(_) => "WR",
+            Self::ContractionLeft(_) => "CL",
+            Self::ContractionRight(_) => "CR",
+            Self::NotLeft(_) => "NotL",
+            Self::NotRight(_) => "NotR",
+            Self::AndLeft1(_) => "AndL1",
+            Self::AndLeft2(_) => "AndL2",
+            Self::AndRight(_, _) => "AndR",
+            Self::OrLeft(_, _) => "OrL",
+            Self::OrRight1(_) => "OrR1",
+            Self::OrRight2(_) => "OrR2",
+            Self::ImpliesLeft(_, _) => "ImpL",
+            Self::ImpliesRight(_) => "ImpR",
+            Self::RLImpliesLeft(_, _) => "RLImpL",
+            Self::RLImpliesRight(_) => "RLImpR",
+            Self::EquivLeft(_) => "EquivL",
+            Self::EquivRight(_, _) => "EquivR",
+            Self::ForallLeft(_, _) => "ForallL",
+            Self::ForallRight(_, _) => "ForallR",
+            Self::ExistsLeft(_, _) => "ExistsL",
+            Self::ExistsRight(_, _) => "ExistsR",
+        }
+    }
+
+    /// Returns the positions of the premises of the rule.
+    pub fn premises(&self) -> Vec<usize> {
+        match self {
+            Self::Axiom | Self::BottomLeft | Self::TopRight => Vec::new(),
+            Self::Cut(a, b)
+            | Self::AndRight(a, b)
+            | Self::OrLeft(a, b)
+            | Self::ImpliesLeft(a, b)
+            | Self::RLImpliesLeft(a, b)
+            | Self::EquivRight(a, b) => vec![*a, *b],
+            Self::WeakeningLeft(a)
+            | Self::WeakeningRight(a)
+            | Self::ContractionLeft(a)
+            | Self::ContractionRight(a)
+            | Self::NotLeft(a)
+            | Self::NotRight(a)
+            | Self::AndLeft1(a)
+            | Self::AndLeft2(a)
+            | Self::OrRight1(a)
+            | Self::OrRight2(a)
+            | Self::ImpliesRight(a)
+            | Self::RLImpliesRight(a)
+            | Self::EquivLeft(a)
+            | Self::ForallLeft(a, _)
+            | Self::ForallRight(a, _)
+            | Self::ExistsLeft(a, _)
+            | Self::ExistsRight(a, _) => vec![*a],
+        }
+    }
+}
+
+impl fmt::Display for SequentRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+        for premise in self.premises() {
+            write!(f, " {}", premise)?;
+        }
+        match self {
+            Self::ForallLeft(_, t) | Self::ExistsRight(_, t) => write!(f, " {}", t),
+            Self::ForallRight(_, y) | Self::ExistsLeft(_, y) => write!(f, " {}", y),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A sequent, derived by a rule from earlier records.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SequentRecord {
+    pub id: usize,
+    pub sequent: Sequent,
+    pub rule: SequentRule,
+}
+
+impl SequentRecord {
+    /// Reads a record `id;sequent;rule`.
+    pub fn read_record(input: &str) -> Result<Self, RecordError> {
+        let mut input = input.split(';');
+        let id = input
+            .next()
+            .ok_or(RecordError::MissingField)?
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| RecordError::InvalidId)?;
+        let sequent = Sequent::read(input.next().ok_or(RecordError::MissingField)?)?;
+        let rule = SequentRule::read(input.next().ok_or(RecordError::MissingField)?)?;
+        match input.next() {
+            Some(_) => Err(RecordError::TooMuch),
+            None => Ok(Self { id, sequent, rule }),
+        }
+    }
+}
+
+impl fmt::Display for SequentRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};{};{}", self.id, self.sequent, self.rule)
+    }
+}
+
+/// Tells whether `premise` is `conclusion` where a formula of `side`, accepted by `decompose`,
+/// is replaced by the formulas it returns for the left and the right.
+fn introduces(
+    premise: &Sequent,
+    conclusion: &Sequent,
+    side: Side,
+    decompose: impl Fn(&Formula) -> Option<(Vec<Formula>, Vec<Formula>)>,
+) -> bool {
+    conclusion
+        .side(side)
+        .iter()
+        .enumerate()
+        .any(|(i, f)| match decompose(f) {
+            Some((left, right)) => premise.same_as(&conclusion.replace(side, i, &left, &right)),
+            None => false,
+        })
+}
+
+/// Same as [`introduces`], for a rule with two premises sharing their context.
+#[allow(clippy::type_complexity)]
+fn introduces2(
+    premises: (&Sequent, &Sequent),
+    conclusion: &Sequent,
+    side: Side,
+    decompose: impl Fn(&Formula) -> Option<[(Vec<Formula>, Vec<Formula>); 2]>,
+) -> bool {
+    conclusion
+        .side(side)
+        .iter()
+        .enumerate()
+        .any(|(i, f)| match decompose(f) {
+            Some([(l1, r1), (l2, r2)]) => {
+                premises.0.same_as(&conclusion.replace(side, i, &l1, &r1))
+                    && premises.1.same_as(&conclusion.replace(side, i, &l2, &r2))
+            }
+            None => false,
+        })
+}
+
+/// Tells whether `conclusion` follows from `premises` by an implication on the left, given by
+/// `decompose` as its antecedent and consequent. The first premise proves the antecedent, the
+/// second one uses the consequent, each in its own context.
+fn implies_left(
+    premises: (&Sequent, &Sequent),
+    conclusion: &Sequent,
+    decompose: impl Fn(&Formula) -> Option<(&Formula, &Formula)>,
+) -> bool {
+    conclusion.antecedent.iter().any(|f| match decompose(f) {
+        Some((a, b)) => {
+            let mut proved = premises.0.succedent.clone();
+            let mut used = premises.1.antecedent.clone();
+            remove_one(&mut proved, a)
+                && remove_one(&mut used, b)
+                && conclusion.same_as(&Sequent::new(
+                    [
+                        premises.0.antecedent.as_slice(),
+                        &used,
+                        std::slice::from_ref(f),
+                    ]
+                    .concat(),
+                    [proved, premises.1.succedent.clone()].concat(),
+                ))
+        }
+        None => false,
+    })
+}
+
+pub struct SequentProof {
+    records: Vec<SequentRecord>,
+    calculus: Calculus,
+}
+
+impl SequentProof {
+    /// Reads a sequent proof, one record per line. Blank lines are ignored.
+    pub fn read_proof(input: &str) -> Result<Self, ReadError> {
+        let mut records = Vec::new();
+        for (line, record) in input.split('\n').enumerate() {
+            if record.trim().is_empty() {
+                continue;
+            }
+            match SequentRecord::read_record(record) {
+                Ok(r) => records.push(r),
+                Err(content) => return Err(ReadError { line, content }),
+            }
+        }
+        Ok(Self {
+            records,
+            calculus: Calculus::default(),
+        })
+    }
+
+    pub fn records(&self) -> &[SequentRecord] {
+        &self.records
+    }
+
+    /// Sets the calculus the proof is checked in.
+    pub fn set_calculus(&mut self, calculus: Calculus) {
+        self.calculus = calculus;
+    }
+
+    pub fn calculus(&self) -> Calculus {
+        self.calculus
+    }
+
+    /// Returns the sequent the proof ends with.
+    pub fn conclusion(&self) -> Option<&Sequent> {
+        self.records.last().map(|r| &r.sequent)
+    }
+
+    /// Checks every record, returning the errors found.
+    pub fn check(&self) -> Result<(), Vec<(usize, SequentError)>> {
+        let errors: Vec<(usize, SequentError)> = (0..self.records.len())
+            .filter_map(|id| self.check_record(id).err().map(|e| (id, e)))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_record(&self, id: usize) -> Result<(), SequentError> {
+        let rec = &self.records[id];
+        if rec.id != id {
+            return Err(SequentError::IncorrectId);
+        }
+        if self.calculus == Calculus::Lj && rec.sequent.succedent.len() > 1 {
+            return Err(SequentError::NotIntuitionistic);
+        }
+        let mut premises = Vec::new();
+        for premise in rec.rule.premises() {
+            if premise >= id {
+                return Err(SequentError::PremiseNotBefore(premise));
+            }
+            premises.push(&self.records[premise].sequent);
+        }
+        let c = &rec.sequent;
+        let follows = match &rec.rule {
+            SequentRule::Axiom => c
+                .antecedent
+                .iter()
+                .any(|f| c.succedent.iter().any(|g| f.alpha_eq(g))),
+            SequentRule::BottomLeft => c.antecedent.contains(&Formula::Bottom),
+            SequentRule::TopRight => c.succedent.contains(&Formula::Top),
+            SequentRule::Cut(_, _) => premises[0].succedent.iter().any(|a| {
+                let (mut proved, mut used) = (
+                    premises[0].succedent.clone(),
+                    premises[1].antecedent.clone(),
+                );
+                remove_one(&mut proved, a)
+                    && remove_one(&mut used, a)
+                    && c.same_as(&Sequent::new(
+                        [premises[0].antecedent.clone(), used].concat(),
+                        [proved, premises[1].succedent.clone()].concat(),
+                    ))
+            }),
+            SequentRule::WeakeningLeft(_) => introduces(premises[0], c, Side::Left, |_| {
+                Some((Vec::new(), Vec::new()))
+            }),
+            SequentRule::WeakeningRight(_) => introduces(premises[0], c, Side::Right, |_| {
+                Some((Vec::new(), Vec::new()))
+            }),
+            SequentRule::ContractionLeft(_) => introduces(premises[0], c, Side::Left, |f| {
+                Some((vec![f.clone(), f.clone()], Vec::new()))
+            }),
+            SequentRule::ContractionRight(_) => introduces(premises[0], c, Side::Right, |f| {
+                Some((Vec::new(), vec![f.clone(), f.clone()]))
+            }),
+            SequentRule::NotLeft(_) => introduces(premises[0], c, Side::Left, |f| match f {
+                Formula::Not(a) => Some((Vec::new(), vec![(**a).clone()])),
+                _ => None,
+            }),
+            SequentRule::NotRight(_) => introduces(premises[0], c, Side::Right, |f| match f {
+                Formula::Not(a) => Some((vec![(**a).clone()], Vec::new())),
+                _ => None,
+            }),
+            SequentRule::AndLeft1(_) => introduces(premises[0], c, Side::Left, |f| match f {
+                Formula::And(a, _) => Some((vec![(**a).clone()], Vec::new())),
+                _ => None,
+            }),
+            SequentRule::AndLeft2(_) => introduces(premises[0], c, Side::Left, |f| match f {
+                Formula::And(_, b) => Some((vec![(**b).clone()], Vec::new())),
+                _ => None,
+            }),
+            SequentRule::AndRight(_, _) => {
+                introduces2((premises[0], premises[1]), c, Side::Right, |f| match f {
+                    Formula::And(a, b) => Some([
+                        (Vec::new(), vec![(**a).clone()]),
+                        (Vec::new(), vec![(**b).clone()]),
+                    ]),
+                    _ => None,
+                })
+            }
+            SequentRule::OrLeft(_, _) => {
+                introduces2((premises[0], premises[1]), c, Side::Left, |f| match f {
+                    Formula::Or(a, b) => Some([
+                        (vec![(**a).clone()], Vec::new()),
+                        (vec![(**b).clone()], Vec::new()),
+                    ]),
+                    _ => None,
+                })
+            }
+            SequentRule::OrRight1(_) => introduces(premises[0], c, Side::Right, |f| match f {
+                Formula::Or(a, _) => Some((Vec::new(), vec![(**a).clone()])),
+                _ => None,
+            }),
+            SequentRule::OrRight2(_) => introduces(premises[0], c, Side::Right, |f| match f {
+                Formula::Or(_, b) => Some((Vec::new(), vec![(**b).clone()])),
+                _ => None,
+            }),
+            SequentRule::ImpliesLeft(_, _) => {
+                implies_left((premises[0], premises[1]), c, |f| match f {
+                    Formula::Implies(a, b) => Some((a, b)),
+                    _ => None,
+                })
+            }
+            SequentRule::ImpliesRight(_) => introduces(premises[0], c, Side::Right, |f| match f {
+                Formula::Implies(a, b) => Some((vec![(**a).clone()], vec![(**b).clone()])),
+                _ => None,
+            }),
+            SequentRule::RLImpliesLeft(_, _) => {
+                implies_left((premises[0], premises[1]), c, |f| match f {
+                    Formula::RLImplies(b, a) => Some((a, b)),
+                    _ => None,
+                })
+            }
+            SequentRule::RLImpliesRight(_) => {
+                introduces(premises[0], c, Side::Right, |f| match f {
+                    Formula::RLImplies(b, a) => Some((vec![(**a).clone()], vec![(**b).clone()])),
+                    _ => None,
+                })
+            }
+            SequentRule::EquivLeft(_) => introduces(premises[0], c, Side::Left, |f| match f {
+                Formula::Equiv(a, b) => Some((vec![implies(a, b), implies(b, a)], Vec::new())),
+                _ => None,
+            }),
+            SequentRule::EquivRight(_, _) => {
+                introduces2((premises[0], premises[1]), c, Side::Right, |f| match f {
+                    Formula::Equiv(a, b) => Some([
+                        (Vec::new(), vec![implies(a, b)]),
+                        (Vec::new(), vec![implies(b, a)]),
+                    ]),
+                    _ => None,
+                })
+            }
+            SequentRule::ForallLeft(_, t) => introduces(premises[0], c, Side::Left, |f| match f {
+                Formula::Forall(x, a) => Some((vec![a.substitute_term(*x, t)], Vec::new())),
+                _ => None,
+            }),
+            SequentRule::ExistsRight(_, t) => {
+                introduces(premises[0], c, Side::Right, |f| match f {
+                    Formula::Exists(x, a) => Some((Vec::new(), vec![a.substitute_term(*x, t)])),
+                    _ => None,
+                })
+            }
+            SequentRule::ForallRight(_, y) => {
+                if c.free_term_variables().contains(y) {
+                    return Err(SequentError::EigenvariableNotFresh(*y));
+                }
+                let y = Term::Variable(*y);
+                introduces(premises[0], c, Side::Right, |f| match f {
+                    Formula::Forall(x, a) => Some((Vec::new(), vec![a.substitute_term(*x, &y)])),
+                    _ => None,
+                })
+            }
+            SequentRule::ExistsLeft(_, y) => {
+                if c.free_term_variables().contains(y) {
+                    return Err(SequentError::EigenvariableNotFresh(*y));
+                }
+                let y = Term::Variable(*y);
+                introduces(premises[0], c, Side::Left, |f| match f {
+                    Formula::Exists(x, a) => Some((vec![a.substitute_term(*x, &y)], Vec::new())),
+                    _ => None,
+                })
+            }
+        };
+        match (follows, rec.rule.premises().is_empty()) {
+            (true, _) => Ok(()),
+            (false, true) => Err(SequentError::NotAnAxiom(rec.rule.name())),
+            (false, false) => Err(SequentError::DoesNotFollow(rec.rule.name())),
+        }
+    }
+}
+
+impl fmt::Display for SequentProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            writeln!(f, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds an LK derivation from a natural-deduction proof, see
+/// [`Proof::to_sequent_calculus`].
+struct Translation<'a> {
+    proof: &'a [Record],
+    records: Vec<SequentRecord>,
+    translated: Vec<Option<usize>>,
+}
+
+impl Translation<'_> {
+    fn push(&mut self, sequent: Sequent, rule: SequentRule) -> usize {
+        let id = self.records.len();
+        self.records.push(SequentRecord { id, sequent, rule });
+        id
+    }
+
+    fn sequent(&self, i: usize) -> &Sequent {
+        &self.records[i].sequent
+    }
+
+    /// The formula proved by the translation `i` of a natural-deduction record.
+    fn proved(&self, i: usize) -> Formula {
+        self.sequent(i).succedent[0].clone()
+    }
+
+    /// Returns the formulas of the open `Supposons` of a context.
+    fn hypotheses(&self, ctxt: &[usize]) -> Vec<Formula> {
+        ctxt.iter()
+            .filter_map(|b| match &self.proof[*b].stmt {
+                Statement::Supposons(f) => Some(f.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn weaken_left(&mut self, i: usize, f: &Formula) -> usize {
+        let sequent = self.sequent(i).extended(std::slice::from_ref(f), &[]);
+        self.push(sequent, SequentRule::WeakeningLeft(i))
+    }
+
+    /// Contracts one copy of each formula of `left` and `right`.
+    fn contract(&mut self, mut i: usize, left: &[Formula], right: &[Formula]) -> usize {
+        for f in left {
+            let mut sequent = self.sequent(i).clone();
+            remove_one(&mut sequent.antecedent, f);
+            i = self.push(sequent, SequentRule::ContractionLeft(i));
+        }
+        for f in right {
+            let mut sequent = self.sequent(i).clone();
+            remove_one(&mut sequent.succedent, f);
+            i = self.push(sequent, SequentRule::ContractionRight(i));
+        }
+        i
+    }
+
+    /// From `Γ ⊢ A` and `Σ, A ⊢ B`, derives `Γ ∪ Σ ⊢ B` by a cut followed by contractions of
+    /// the hypotheses both share.
+    fn eliminate(&mut self, premise: usize, lemma: usize) -> usize {
+        let a = self.proved(premise);
+        let mut context = self.sequent(lemma).antecedent.clone();
+        remove_one(&mut context, &a);
+        let shared = intersection(&self.sequent(premise).antecedent, &context);
+        let sequent = Sequent::new(
+            [self.sequent(premise).antecedent.clone(), context].concat(),
+            self.sequent(lemma).succedent.clone(),
+        );
+        let i = self.push(sequent, SequentRule::Cut(premise, lemma));
+        self.contract(i, &shared, &[])
+    }
+
+    /// From `Γ ⊢ A⇒B` and `Γ ⊢ A`, derives `Γ ⊢ B`.
+    fn modus_ponens(&mut self, implication: usize, antecedent: usize) -> Option<usize> {
+        let imp = self.proved(implication);
+        let b = match &imp {
+            Formula::Implies(_, b) => (**b).clone(),
+            _ => return None,
+        };
+        let axiom = self.push(
+            Sequent::new(vec![b.clone()], vec![b.clone()]),
+            SequentRule::Axiom,
+        );
+        let context = self.sequent(antecedent).antecedent.clone();
+        let lemma = self.push(
+            Sequent::new([context, vec![imp]].concat(), vec![b]),
+            SequentRule::ImpliesLeft(antecedent, axiom),
+        );
+        Some(self.eliminate(implication, lemma))
+    }
+
+    /// Translates a record and weakens it to the hypotheses `hyps`.
+    fn premise(&mut self, id: usize, hyps: &[Formula]) -> Result<usize, TranslationError> {
+        let mut i = self.translate(id)?;
+        for f in difference(hyps, &self.sequent(i).antecedent) {
+            i = self.weaken_left(i, &f);
+        }
+        Ok(i)
+    }
+
+    /// Translates the record `id` into a derivation of `Γ ⊢ φ`, where `φ` is its formula and `Γ`
+    /// its open hypotheses.
+    fn translate(&mut self, id: usize) -> Result<usize, TranslationError> {
+        if let Some(i) = self.translated[id] {
+            return Ok(i);
+        }
+        let proof = self.proof;
+        let rec = &proof[id];
+        let hyps = self.hypotheses(&rec.ctxt);
+        let unsupported = TranslationError::Unsupported(id);
+        let i = match (&rec.stmt, &rec.justif) {
+            (Statement::Supposons(f), _) => self.push(
+                Sequent::new(hyps.clone(), vec![f.clone()]),
+                SequentRule::Axiom,
+            ),
+            (Statement::Soit(_), _) => self.push(
+                Sequent::new(hyps.clone(), vec![Formula::Top]),
+                SequentRule::TopRight,
+            ),
+            (Statement::Donc(f), Jusitification::IImpl) if id > 0 => {
+                let cons = self.translate(id - 1)?;
+                self.push(
+                    Sequent::new(hyps.clone(), vec![f.clone()]),
+                    SequentRule::ImpliesRight(cons),
+                )
+            }
+            (Statement::Donc(f), Jusitification::INot) if id > 0 => {
+                let cons = self.translate(id - 1)?;
+                let bottom = self.push(
+                    Sequent::new(vec![Formula::Bottom], Vec::new()),
+                    SequentRule::BottomLeft,
+                );
+                let refuted = self.eliminate(cons, bottom);
+                self.push(
+                    Sequent::new(hyps.clone(), vec![f.clone()]),
+                    SequentRule::NotRight(refuted),
+                )
+            }
+            (Statement::Donc(f), Jusitification::IForall) if id > 0 => {
+                let y = match proof[id - 1].ctxt.last().map(|b| &proof[*b].stmt) {
+                    Some(Statement::Soit(y)) => *y,
+                    _ => return Err(unsupported),
+                };
+                let cons = self.translate(id - 1)?;
+                self.push(
+                    Sequent::new(hyps.clone(), vec![f.clone()]),
+                    SequentRule::ForallRight(cons, y),
+                )
+            }
+            (Statement::Simple(f), justif) => {
+                self.simple(&hyps, f, justif).ok_or(unsupported.clone())??
+            }
+            _ => return Err(unsupported),
+        };
+        if !self
+            .sequent(i)
+            .same_as(&Sequent::new(hyps, vec![rec.stmt.get_formula().clone()]))
+        {
+            return Err(unsupported);
+        }
+        self.translated[id] = Some(i);
+        Ok(i)
+    }
+
+    /// Translates a simple record of formula `f`, justified by `justif`. Returns `None` when the
+    /// rule has no counterpart in LK.
+    fn simple(
+        &mut self,
+        hyps: &[Formula],
+        f: &Formula,
+        justif: &Jusitification,
+    ) -> Option<Result<usize, TranslationError>> {
+        let goal = Sequent::new(hyps.to_vec(), vec![f.clone()]);
+        let i = match justif {
+            Jusitification::IOrL(p, _) => match self.premise(*p, hyps) {
+                Ok(p) => self.push(goal, SequentRule::OrRight2(p)),
+                Err(e) => return Some(Err(e)),
+            },
+            Jusitification::IOrR(p, _) => match self.premise(*p, hyps) {
+                Ok(p) => self.push(goal, SequentRule::OrRight1(p)),
+                Err(e) => return Some(Err(e)),
+            },
+            Jusitification::IAnd { left, right } => {
+                match (self.premise(*left, hyps), self.premise(*right, hyps)) {
+                    (Ok(l), Ok(r)) => self.push(goal, SequentRule::AndRight(l, r)),
+                    (Err(e), _) | (_, Err(e)) => return Some(Err(e)),
+                }
+            }
+            Jusitification::EAndL(p) | Jusitification::EAndR(p) => {
+                let p = match self.premise(*p, hyps) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e)),
+                };
+                let axiom = self.push(
+                    Sequent::new(vec![f.clone()], vec![f.clone()]),
+                    SequentRule::Axiom,
+                );
+                let rule = match justif {
+                    Jusitification::EAndL(_) => SequentRule::AndLeft1(axiom),
+                    _ => SequentRule::AndLeft2(axiom),
+                };
+                let lemma = self.push(Sequent::new(vec![self.proved(p)], vec![f.clone()]), rule);
+                self.eliminate(p, lemma)
+            }
+            Jusitification::EOr {
+                a_to_c,
+                b_to_c,
+                a_or_b,
+            } => {
+                let premises = [*a_to_c, *b_to_c, *a_or_b].map(|p| self.premise(p, hyps));
+                let [a_to_c, b_to_c, a_or_b] = match premises {
+                    [Ok(ac), Ok(bc), Ok(ab)] => [ac, bc, ab],
+                    [Err(e), _, _] | [_, Err(e), _] | [_, _, Err(e)] => return Some(Err(e)),
+                };
+                let disjunction = self.proved(a_or_b);
+                let (a, b) = match &disjunction {
+                    Formula::Or(a, b) => (a, b),
+                    _ => return None,
+                };
+                let mut cases = Vec::new();
+                for (implication, case) in [(a_to_c, a), (b_to_c, b)] {
+                    let implication = self.weaken_left(implication, case);
+                    let context = [hyps, &[(**case).clone()]].concat();
+                    let axiom = self.push(
+                        Sequent::new(context, vec![(**case).clone()]),
+                        SequentRule::Axiom,
+                    );
+                    cases.push(self.modus_ponens(implication, axiom)?);
+                }
+                let lemma = self.push(
+                    Sequent::new(
+                        [hyps, std::slice::from_ref(&disjunction)].concat(),
+                        vec![f.clone()],
+                    ),
+                    SequentRule::OrLeft(cases[0], cases[1]),
+                );
+                self.eliminate(a_or_b, lemma)
+            }
+            Jusitification::EImpl { hyp, implication } => {
+                match (self.premise(*implication, hyps), self.premise(*hyp, hyps)) {
+                    (Ok(implication), Ok(hyp)) => self.modus_ponens(implication, hyp)?,
+                    (Err(e), _) | (_, Err(e)) => return Some(Err(e)),
+                }
+            }
+            Jusitification::Efq(p) => {
+                let p = match self.premise(*p, hyps) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e)),
+                };
+                let lemma = self.push(
+                    Sequent::new(vec![Formula::Bottom], vec![f.clone()]),
+                    SequentRule::BottomLeft,
+                );
+                self.eliminate(p, lemma)
+            }
+            Jusitification::Raa(p) => {
+                let p = match self.premise(*p, hyps) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e)),
+                };
+                let not_f = Formula::Not(Box::new(f.clone()));
+                let axiom = self.push(
+                    Sequent::new(vec![f.clone()], vec![f.clone()]),
+                    SequentRule::Axiom,
+                );
+                let excluded = self.push(
+                    Sequent::new(Vec::new(), vec![not_f, f.clone()]),
+                    SequentRule::NotRight(axiom),
+                );
+                let lemma = self.push(
+                    Sequent::new(vec![self.proved(p)], vec![f.clone()]),
+                    SequentRule::NotLeft(excluded),
+                );
+                self.eliminate(p, lemma)
+            }
+            Jusitification::ITop => self.push(goal, SequentRule::TopRight),
+            Jusitification::ENot { hyp: p, negation: q }
+            | Jusitification::IEquiv {
+                forward: p,
+                backward: q,
+            } => {
+                let (p, q) = match (self.premise(*p, hyps), self.premise(*q, hyps)) {
+                    (Ok(p), Ok(q)) => (p, q),
+                    (Err(e), _) | (_, Err(e)) => return Some(Err(e)),
+                };
+                let lemma = self.prove(Sequent::new(
+                    vec![self.proved(p), self.proved(q)],
+                    vec![f.clone()],
+                ))?;
+                let lemma = self.eliminate(p, lemma);
+                self.eliminate(q, lemma)
+            }
+            Jusitification::EEquivL(p) | Jusitification::EEquivR(p) => {
+                let p = match self.premise(*p, hyps) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e)),
+                };
+                let lemma = self.prove(Sequent::new(vec![self.proved(p)], vec![f.clone()]))?;
+                self.eliminate(p, lemma)
+            }
+            Jusitification::Rwrt(p) => {
+                let p = match self.premise(*p, hyps) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e)),
+                };
+                let lemma = self.prove(Sequent::new(vec![self.proved(p)], vec![f.clone()]))?;
+                self.eliminate(p, lemma)
+            }
+            Jusitification::EForall(p, t) => {
+                let p = match self.premise(*p, hyps) {
+                    Ok(p) => p,
+                    Err(e) => return Some(Err(e)),
+                };
+                let axiom = self.push(
+                    Sequent::new(vec![f.clone()], vec![f.clone()]),
+                    SequentRule::Axiom,
+                );
+                let lemma = self.push(
+                    Sequent::new(vec![self.proved(p)], vec![f.clone()]),
+                    SequentRule::ForallLeft(axiom, t.clone()),
+                );
+                self.eliminate(p, lemma)
+            }
+            Jusitification::IExists(p, t) => match self.premise(*p, hyps) {
+                Ok(p) => self.push(goal, SequentRule::ExistsRight(p, t.clone())),
+                Err(e) => return Some(Err(e)),
+            },
+            Jusitification::EExists { exists, forall } => {
+                let (exists, forall) =
+                    match (self.premise(*exists, hyps), self.premise(*forall, hyps)) {
+                        (Ok(exists), Ok(forall)) => (exists, forall),
+                        (Err(e), _) | (_, Err(e)) => return Some(Err(e)),
+                    };
+                let (existential, universal) = (self.proved(exists), self.proved(forall));
+                let ((x, body), (y, implication)) = match (&existential, &universal) {
+                    (Formula::Exists(x, body), Formula::Forall(y, implication)) => {
+                        ((*x, body), (*y, implication))
+                    }
+                    _ => return None,
+                };
+                // The eigenvariable has to be fresh for the whole derivation of the case.
+                let mut avoid: BTreeSet<char> =
+                    hyps.iter().flat_map(|h| h.term_variables()).collect();
+                avoid.extend(existential.term_variables());
+                avoid.extend(universal.term_variables());
+                avoid.extend(f.term_variables());
+                let z = fresh_variable(&avoid);
+                let witness = body.substitute_term(x, &Term::Variable(z));
+                let instance = implication.substitute_term(y, &Term::Variable(z));
+                let axiom = self.push(
+                    Sequent::new(vec![instance.clone()], vec![instance.clone()]),
+                    SequentRule::Axiom,
+                );
+                let lemma = self.push(
+                    Sequent::new(vec![universal.clone()], vec![instance]),
+                    SequentRule::ForallLeft(axiom, Term::Variable(z)),
+                );
+                let instance = self.eliminate(forall, lemma);
+                let instance = self.weaken_left(instance, &witness);
+                let context = [hyps, std::slice::from_ref(&witness)].concat();
+                let axiom = self.push(Sequent::new(context, vec![witness]), SequentRule::Axiom);
+                let case = self.modus_ponens(instance, axiom)?;
+                let lemma = self.push(
+                    Sequent::new([hyps, &[existential]].concat(), vec![self.proved(case)]),
+                    SequentRule::ExistsLeft(case, z),
+                );
+                self.eliminate(exists, lemma)
+            }
+            Jusitification::Hyp
+            | Jusitification::IImpl
+            | Jusitification::IForall
+            | Jusitification::INec
+            | Jusitification::INot
+            | Jusitification::ENec(_)
+            | Jusitification::RPos(_)
+            | Jusitification::Gap
+            | Jusitification::Named(_, _) => return None,
+        };
+        Some(Ok(i))
+    }
+
+    /// Looks for a cut-free derivation of a propositional sequent, decomposing its connectives.
+    /// Quantified and modal formulas are left as they are. Nothing is added when the search
+    /// fails.
+    fn prove(&mut self, sequent: Sequent) -> Option<usize> {
+        let mark = self.records.len();
+        let derivation = self.prove_step(sequent);
+        if derivation.is_none() {
+            self.records.truncate(mark);
+        }
+        derivation
+    }
+
+    fn prove_step(&mut self, s: Sequent) -> Option<usize> {
+        if s.antecedent.contains(&Formula::Bottom) {
+            return Some(self.push(s, SequentRule::BottomLeft));
+        }
+        if s.succedent.contains(&Formula::Top) {
+            return Some(self.push(s, SequentRule::TopRight));
+        }
+        if s.antecedent
+            .iter()
+            .any(|f| s.succedent.iter().any(|g| f.alpha_eq(g)))
+        {
+            return Some(self.push(s, SequentRule::Axiom));
+        }
+        let left = s.antecedent.iter().position(|f| {
+            matches!(
+                f,
+                Formula::Top
+                    | Formula::Not(_)
+                    | Formula::And(_, _)
+                    | Formula::Or(_, _)
+                    | Formula::Implies(_, _)
+                    | Formula::RLImplies(_, _)
+                    | Formula::Equiv(_, _)
+            )
+        });
+        if let Some(i) = left {
+            let f = s.antecedent[i].clone();
+            let gamma = s.replace(Side::Left, i, &[], &[]);
+            let rule = match &f {
+                Formula::Not(a) => {
+                    SequentRule::NotLeft(self.prove(gamma.extended(&[], &[(**a).clone()]))?)
+                }
+                Formula::And(a, b) => {
+                    let p = self.prove(gamma.extended(&[(**a).clone(), (**b).clone()], &[]))?;
+                    let p = self.push(
+                        gamma.extended(&[(**a).clone(), f.clone()], &[]),
+                        SequentRule::AndLeft2(p),
+                    );
+                    let p = self.push(
+                        gamma.extended(&[f.clone(), f.clone()], &[]),
+                        SequentRule::AndLeft1(p),
+                    );
+                    SequentRule::ContractionLeft(p)
+                }
+                Formula::Or(a, b) => SequentRule::OrLeft(
+                    self.prove(gamma.extended(&[(**a).clone()], &[]))?,
+                    self.prove(gamma.extended(&[(**b).clone()], &[]))?,
+                ),
+                Formula::Implies(a, b) | Formula::RLImplies(b, a) => {
+                    let p1 = self.prove(gamma.extended(&[], &[(**a).clone()]))?;
+                    let p2 = self.prove(gamma.extended(&[(**b).clone()], &[]))?;
+                    let rule = match &f {
+                        Formula::Implies(_, _) => SequentRule::ImpliesLeft(p1, p2),
+                        _ => SequentRule::RLImpliesLeft(p1, p2),
+                    };
+                    let both = Sequent::new(
+                        [
+                            gamma.antecedent.as_slice(),
+                            &gamma.antecedent,
+                            std::slice::from_ref(&f),
+                        ]
+                        .concat(),
+                        [gamma.succedent.as_slice(), &gamma.succedent].concat(),
+                    );
+                    let p = self.push(both, rule);
+                    return Some(self.contract(p, &gamma.antecedent, &gamma.succedent));
+                }
+                Formula::Equiv(a, b) => SequentRule::EquivLeft(
+                    self.prove(gamma.extended(&[implies(a, b), implies(b, a)], &[]))?,
+                ),
+                _ => SequentRule::WeakeningLeft(self.prove(gamma)?),
+            };
+            return Some(self.push(s, rule));
+        }
+        let right = s.succedent.iter().position(|f| {
+            matches!(
+                f,
+                Formula::Bottom
+                    | Formula::Not(_)
+                    | Formula::And(_, _)
+                    | Formula::Or(_, _)
+                    | Formula::Implies(_, _)
+                    | Formula::RLImplies(_, _)
+                    | Formula::Equiv(_, _)
+            )
+        });
+        let i = right?;
+        let f = s.succedent[i].clone();
+        let delta = s.replace(Side::Right, i, &[], &[]);
+        let rule = match &f {
+            Formula::Not(a) => {
+                SequentRule::NotRight(self.prove(delta.extended(&[(**a).clone()], &[]))?)
+            }
+            Formula::And(a, b) => SequentRule::AndRight(
+                self.prove(delta.extended(&[], &[(**a).clone()]))?,
+                self.prove(delta.extended(&[], &[(**b).clone()]))?,
+            ),
+            Formula::Or(a, b) => {
+                let p = self.prove(delta.extended(&[], &[(**a).clone(), (**b).clone()]))?;
+                let p = self.push(
+                    delta.extended(&[], &[(**a).clone(), f.clone()]),
+                    SequentRule::OrRight2(p),
+                );
+                let p = self.push(
+                    delta.extended(&[], &[f.clone(), f.clone()]),
+                    SequentRule::OrRight1(p),
+                );
+                SequentRule::ContractionRight(p)
+            }
+            Formula::Implies(a, b) => SequentRule::ImpliesRight(
+                self.prove(delta.extended(&[(**a).clone()], &[(**b).clone()]))?,
+            ),
+            Formula::RLImplies(b, a) => SequentRule::RLImpliesRight(
+                self.prove(delta.extended(&[(**a).clone()], &[(**b).clone()]))?,
+            ),
+            Formula::Equiv(a, b) => SequentRule::EquivRight(
+                self.prove(delta.extended(&[], &[implies(a, b)]))?,
+                self.prove(delta.extended(&[], &[implies(b, a)]))?,
+            ),
+            _ => SequentRule::WeakeningRight(self.prove(delta)?),
+        };
+        Some(self.push(s, rule))
+    }
+}
+
+impl Proof {
+    /// Translates a checked, valid proof into an LK derivation of `⊢ φ`, `φ` being the formula
+    /// of the last record.
+    ///
+    /// Each record becomes a derivation of the sequent `Γ ⊢ φ` where `Γ` are its open
+    /// hypotheses. Eliminations become cuts against small lemmas, and `Rwrt` a cut against a
+    /// derivation found by proof search. Modal rules have no counterpart in LK.
+    pub fn to_sequent_calculus(&self) -> Result<SequentProof, TranslationError> {
+        if !matches!(self.state(), CheckUpResult::Valid) {
+            return Err(TranslationError::NotValid);
+        }
+        let records = self.records();
+        let mut translation = Translation {
+            proof: records,
+            records: Vec::new(),
+            translated: vec![None; records.len()],
+        };
+        translation.translate(records.len() - 1)?;
+        Ok(SequentProof {
+            records: translation.records,
+            calculus: Calculus::Lk,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof(input: &str) -> SequentProof {
+        SequentProof::read_proof(input).unwrap()
+    }
+
+    #[test]
+    fn read_and_display() {
+        let sequent = Sequent::read("P(x,y), A∧B ⊢ ").unwrap();
+        assert_eq!(sequent.antecedent.len(), 2);
+        assert!(sequent.succedent.is_empty());
+        assert_eq!(sequent.to_string(), "P(x,y), A∧B ⊢");
+        assert_eq!(Sequent::read("⊢A,B").unwrap().to_string(), "⊢ A, B");
+        assert!(matches!(
+            Sequent::read("A, B"),
+            Err(RecordError::MissingTurnstile)
+        ));
+        for input in [
+            "0;A ⊢ A;Ax",
+            "3;⊢ ∀xP(x);ForallR 2 y",
+            "4;∀xP(x) ⊢ P(f(a));ForallL 1 f(a)",
+        ] {
+            assert_eq!(
+                SequentRecord::read_record(input).unwrap().to_string(),
+                input
+            );
+        }
+        assert!(matches!(
+            SequentRecord::read_record("0;A⊢A;Cut 1"),
+            Err(RecordError::MissingReference)
+        ));
+        assert!(matches!(
+            SequentRecord::read_record("0;A⊢A;Ax 1"),
+            Err(RecordError::TooManyArguments)
+        ));
+    }
+
+    #[test]
+    fn excluded_middle() {
+        let input = "0;A⊢A;Ax\n1;⊢A,¬A;NotR 0\n2;⊢A,A∨¬A;OrR2 1\n3;⊢A∨¬A,A∨¬A;OrR1 2\n4;⊢A∨¬A;CR 3";
+        let mut p = proof(input);
+        assert_eq!(p.check(), Ok(()));
+        p.set_calculus(Calculus::Lj);
+        let errors = p.check().unwrap_err();
+        assert_eq!(errors[0], (1, SequentError::NotIntuitionistic));
+    }
+
+    #[test]
+    fn wrong_steps() {
+        let p = proof("0;A⊢B;Ax\n1;A⊢A;Ax\n2;⊢A∧B;AndR 1 1\n3;A⊢A;WL 4");
+        let errors = p.check().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                (0, SequentError::NotAnAxiom("Ax")),
+                (2, SequentError::DoesNotFollow("AndR")),
+                (3, SequentError::PremiseNotBefore(4)),
+            ]
+        );
+        // y is free in the conclusion.
+        let p = proof("0;P(y)⊢P(y);Ax\n1;P(y)⊢∀xP(x);ForallR 0 y");
+        assert_eq!(
+            p.check(),
+            Err(vec![(1, SequentError::EigenvariableNotFresh('y'))])
+        );
+        // Cut and ImpL gather the contexts of their premises.
+        let p =
+            proof("0;A⊢A;Ax\n1;B⊢B;Ax\n2;A,A⇒B⊢B;ImpL 0 1\n3;A∧C⊢A;AndL1 0\n4;A∧C,A⇒B⊢B;Cut 3 2");
+        assert_eq!(p.check(), Ok(()));
+    }
+
+    #[test]
+    fn translation() {
+        let inputs = [
+            include_str!("../proofs/first.proof"),
+            include_str!("../proofs/third.proof"),
+            include_str!("../proofs/fourth.proof"),
+            include_str!("../proofs/sixth.proof"),
+            // EEquivL, EEquivR, IEquiv and ITop
+            "0;0;Supposons a⇔b;Hyp\n1;0;a⇒b;EEquivL 0\n2;0;b⇒a;EEquivR 0\n3;0;b⇔a;IEquiv 2 1\n\
+             4;0;⊤;ITop\n5;0;(b⇔a)∧⊤;IAnd 3 4\n6;;Donc (a⇔b)⇒(b⇔a)∧⊤;IImpl",
+            // EOr, Rwrt and Raa
+            "0;0;Supposons a∨b;Hyp\n1;0,1;Supposons a;Hyp\n2;0,1;b∨a;IOrL 1 b\n3;0;Donc a⇒b∨a;IImpl\n\
+             4;0,4;Supposons b;Hyp\n5;0,4;b∨a;IOrR 4 a\n6;0;Donc b⇒b∨a;IImpl\n7;0;b∨a;EOr 3 6 0\n\
+             8;0,8;Supposons ¬(b∨a);Hyp\n9;0,8;b∨a⇒⊥;Rwrt 8\n10;0,8;⊥;EImpl 7 9\n\
+             11;0;Donc ¬(b∨a)⇒⊥;IImpl\n12;0;¬¬(b∨a);Rwrt 11\n13;0;b∨a;Raa 12\n14;;Donc a∨b⇒b∨a;IImpl",
+            // EExists
+            "0;0;Supposons ∃xP(x);Hyp\n1;0,1;Soit y;Hyp\n2;0,1,2;Supposons P(y);Hyp\n\
+             3;0,1,2;∃zP(z);IExists 2 y\n4;0,1;Donc P(y)⇒∃zP(z);IImpl\n5;0;Donc ∀y(P(y)⇒∃zP(z));IForall\n\
+             6;0;∃zP(z);EExists 0 5\n7;;Donc ∃xP(x)⇒∃zP(z);IImpl",
+        ];
+        for input in inputs {
+            let mut nd = Proof::read_proof(input).unwrap();
+            nd.check();
+            let lk = nd.to_sequent_calculus().unwrap();
+            assert_eq!(lk.check(), Ok(()), "{}", lk);
+            let conclusion = lk.conclusion().unwrap();
+            assert!(conclusion.antecedent.is_empty());
+            assert_eq!(conclusion.succedent.len(), 1);
+        }
+        let mut modal = Proof::read_proof(include_str!("../proofs/fifth.proof")).unwrap();
+        modal.check();
+        assert!(matches!(
+            modal.to_sequent_calculus(),
+            Err(TranslationError::Unsupported(_))
+        ));
+        let mut invalid = Proof::read_proof(include_str!("../proofs/second.proof")).unwrap();
+        invalid.check();
+        assert_eq!(
+            invalid.to_sequent_calculus().err(),
+            Some(TranslationError::NotValid)
+        );
+    }
+}
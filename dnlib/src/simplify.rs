@@ -0,0 +1,331 @@
+use std::fmt;
+
+use crate::formula::Formula;
+
+/// A Boolean law used by the simplifier, always oriented from left to right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Law {
+    /// ¬¬A ≡ A
+    DoubleNegation,
+    /// ¬⊤ ≡ ⊥, ¬⊥ ≡ ⊤
+    NegatedConstant,
+    /// A∧A ≡ A, A∨A ≡ A
+    Idempotence,
+    /// A∧⊤ ≡ A, A∨⊥ ≡ A
+    Identity,
+    /// A∧⊥ ≡ ⊥, A∨⊤ ≡ ⊤
+    Annihilation,
+    /// A∧¬A ≡ ⊥, A∨¬A ≡ ⊤
+    Complement,
+    /// A∧(A∨B) ≡ A, A∨(A∧B) ≡ A
+    Absorption,
+    /// ⊤⇒A ≡ A, A⇒⊤ ≡ ⊤, ⊥⇒A ≡ ⊤, A⇒⊥ ≡ ¬A, A⇒A ≡ ⊤
+    ImplicationUnit,
+    /// A⇔⊤ ≡ A, A⇔⊥ ≡ ¬A, A⇔A ≡ ⊤
+    EquivalenceUnit,
+    /// ∀xA ≡ A, ∃xA ≡ A when x is not free in A
+    VacuousQuantifier,
+    /// □⊤ ≡ ⊤, ◇⊥ ≡ ⊥
+    ModalConstant,
+}
+
+impl fmt::Display for Law {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Law::DoubleNegation => "double negation",
+            Law::NegatedConstant => "negated constant",
+            Law::Idempotence => "idempotence",
+            Law::Identity => "identity",
+            Law::Annihilation => "annihilation",
+            Law::Complement => "complement",
+            Law::Absorption => "absorption",
+            Law::ImplicationUnit => "implication with a constant or itself",
+            Law::EquivalenceUnit => "equivalence with a constant or itself",
+            Law::VacuousQuantifier => "vacuous quantifier",
+            Law::ModalConstant => "modality of a constant",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single rewrite performed by the simplifier.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewriteStep {
+    /// The law that was applied
+    pub law: Law,
+    /// The subformula the law was applied to
+    pub redex: Formula,
+    /// What the subformula was rewritten into
+    pub contractum: Formula,
+    /// The whole formula after the rewrite
+    pub result: Formula,
+}
+
+impl fmt::Display for RewriteStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "≡ {}    ({}: {} ≡ {})",
+            self.result, self.law, self.redex, self.contractum
+        )
+    }
+}
+
+/// The explained simplification of a formula.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simplification {
+    pub original: Formula,
+    pub steps: Vec<RewriteStep>,
+    pub result: Formula,
+}
+
+impl fmt::Display for Simplification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  {}", self.original)?;
+        for step in self.steps.iter() {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl Formula {
+    /// Simplifies the formula with the laws of [`Law`], one rewrite at a time, outermost
+    /// subformulas first.
+    ///
+    /// Every law makes the formula smaller, so this always terminates.
+    pub fn simplify(&self) -> Simplification {
+        let mut steps = Vec::new();
+        let mut current = self.clone();
+        while let Some((law, redex, contractum, result)) = rewrite_once(&current) {
+            steps.push(RewriteStep {
+                law,
+                redex,
+                contractum,
+                result: result.clone(),
+            });
+            current = result;
+        }
+        Simplification {
+            original: self.clone(),
+            steps,
+            result: current,
+        }
+    }
+}
+
+/// Performs the leftmost outermost rewrite of `f`, if any. Returns the law, the redex, the
+/// contractum and the rewritten formula.
+fn rewrite_once(f: &Formula) -> Option<(Law, Formula, Formula, Formula)> {
+    if let Some((law, contractum)) = rewrite_root(f) {
+        return Some((law, f.clone(), contractum.clone(), contractum));
+    }
+    let rebuild_binary = |a: &Formula,
+                          b: &Formula,
+                          build: fn(Box<Formula>, Box<Formula>) -> Formula|
+     -> Option<(Law, Formula, Formula, Formula)> {
+        if let Some((law, redex, contractum, a)) = rewrite_once(a) {
+            Some((law, redex, contractum, build(Box::new(a), Box::new(b.clone()))))
+        } else {
+            rewrite_once(b).map(|(law, redex, contractum, b)| {
+                (law, redex, contractum, build(Box::new(a.clone()), Box::new(b)))
+            })
+        }
+    };
+    match f {
+        Formula::Top
+        | Formula::Bottom
+        | Formula::Variable(_)
+        | Formula::Predicate(_, _)
+        | Formula::Equal(_, _) => None,
+        Formula::Not(g) => rewrite_once(g).map(|(law, redex, contractum, g)| {
+            (law, redex, contractum, Formula::Not(Box::new(g)))
+        }),
+        Formula::Necessarily(g) => rewrite_once(g).map(|(law, redex, contractum, g)| {
+            (law, redex, contractum, Formula::Necessarily(Box::new(g)))
+        }),
+        Formula::Possibly(g) => rewrite_once(g).map(|(law, redex, contractum, g)| {
+            (law, redex, contractum, Formula::Possibly(Box::new(g)))
+        }),
+        Formula::Forall(x, g) => rewrite_once(g).map(|(law, redex, contractum, g)| {
+            (law, redex, contractum, Formula::Forall(*x, Box::new(g)))
+        }),
+        Formula::Exists(x, g) => rewrite_once(g).map(|(law, redex, contractum, g)| {
+            (law, redex, contractum, Formula::Exists(*x, Box::new(g)))
+        }),
+        Formula::And(a, b) => rebuild_binary(a, b, Formula::And),
+        Formula::Or(a, b) => rebuild_binary(a, b, Formula::Or),
+        Formula::Implies(a, b) => rebuild_binary(a, b, Formula::Implies),
+        Formula::RLImplies(a, b) => rebuild_binary(a, b, Formula::RLImplies),
+        Formula::Equiv(a, b) => rebuild_binary(a, b, Formula::Equiv),
+    }
+}
+
+fn is_negation_of(f: &Formula, g: &Formula) -> bool {
+    matches!(f, Formula::Not(h) if **h == *g) || matches!(g, Formula::Not(h) if **h == *f)
+}
+
+/// Applies a law at the root of `f`, if one applies.
+fn rewrite_root(f: &Formula) -> Option<(Law, Formula)> {
+    match f {
+        Formula::Not(g) => match g.as_ref() {
+            Formula::Not(h) => Some((Law::DoubleNegation, *h.clone())),
+            Formula::Top => Some((Law::NegatedConstant, Formula::Bottom)),
+            Formula::Bottom => Some((Law::NegatedConstant, Formula::Top)),
+            _ => None,
+        },
+        Formula::And(a, b) => {
+            if a == b {
+                Some((Law::Idempotence, *a.clone()))
+            } else if **b == Formula::Top {
+                Some((Law::Identity, *a.clone()))
+            } else if **a == Formula::Top {
+                Some((Law::Identity, *b.clone()))
+            } else if **a == Formula::Bottom || **b == Formula::Bottom {
+                Some((Law::Annihilation, Formula::Bottom))
+            } else if is_negation_of(a, b) {
+                Some((Law::Complement, Formula::Bottom))
+            } else if matches!(b.as_ref(), Formula::Or(l, r) if l == a || r == a) {
+                Some((Law::Absorption, *a.clone()))
+            } else if matches!(a.as_ref(), Formula::Or(l, r) if l == b || r == b) {
+                Some((Law::Absorption, *b.clone()))
+            } else {
+                None
+            }
+        }
+        Formula::Or(a, b) => {
+            if a == b {
+                Some((Law::Idempotence, *a.clone()))
+            } else if **b == Formula::Bottom {
+                Some((Law::Identity, *a.clone()))
+            } else if **a == Formula::Bottom {
+                Some((Law::Identity, *b.clone()))
+            } else if **a == Formula::Top || **b == Formula::Top {
+                Some((Law::Annihilation, Formula::Top))
+            } else if is_negation_of(a, b) {
+                Some((Law::Complement, Formula::Top))
+            } else if matches!(b.as_ref(), Formula::And(l, r) if l == a || r == a) {
+                Some((Law::Absorption, *a.clone()))
+            } else if matches!(a.as_ref(), Formula::And(l, r) if l == b || r == b) {
+                Some((Law::Absorption, *b.clone()))
+            } else {
+                None
+            }
+        }
+        Formula::Implies(hyp, cons) | Formula::RLImplies(cons, hyp) => {
+            if hyp == cons {
+                Some((Law::ImplicationUnit, Formula::Top))
+            } else if **hyp == Formula::Top {
+                Some((Law::ImplicationUnit, *cons.clone()))
+            } else if **hyp == Formula::Bottom || **cons == Formula::Top {
+                Some((Law::ImplicationUnit, Formula::Top))
+            } else if **cons == Formula::Bottom {
+                Some((Law::ImplicationUnit, Formula::Not(hyp.clone())))
+            } else {
+                None
+            }
+        }
+        Formula::Equiv(a, b) => {
+            if a == b {
+                Some((Law::EquivalenceUnit, Formula::Top))
+            } else if **b == Formula::Top {
+                Some((Law::EquivalenceUnit, *a.clone()))
+            } else if **a == Formula::Top {
+                Some((Law::EquivalenceUnit, *b.clone()))
+            } else if **b == Formula::Bottom {
+                Some((Law::EquivalenceUnit, Formula::Not(a.clone())))
+            } else if **a == Formula::Bottom {
+                Some((Law::EquivalenceUnit, Formula::Not(b.clone())))
+            } else {
+                None
+            }
+        }
+        Formula::Necessarily(g) => match g.as_ref() {
+            Formula::Top => Some((Law::ModalConstant, Formula::Top)),
+            _ => None,
+        },
+        Formula::Possibly(g) => match g.as_ref() {
+            Formula::Bottom => Some((Law::ModalConstant, Formula::Bottom)),
+            _ => None,
+        },
+        Formula::Forall(x, g) | Formula::Exists(x, g) => {
+            if g.has_free(*x) {
+                None
+            } else {
+                Some((Law::VacuousQuantifier, *g.clone()))
+            }
+        }
+        Formula::Top
+        | Formula::Bottom
+        | Formula::Variable(_)
+        | Formula::Predicate(_, _)
+        | Formula::Equal(_, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    #[test]
+    fn absorption_and_identity() {
+        let s = f("(a∧(a∨b))∨⊥").simplify();
+        assert_eq!(s.result, f("a"));
+        assert_eq!(
+            s.steps.iter().map(|step| step.law).collect::<Vec<_>>(),
+            vec![Law::Identity, Law::Absorption]
+        );
+    }
+
+    #[test]
+    fn negations() {
+        let s = f("¬¬¬⊤⇒b").simplify();
+        assert_eq!(s.result, f("⊤"));
+        assert_eq!(s.steps[0].law, Law::DoubleNegation);
+        assert_eq!(s.steps[0].result, f("¬⊤⇒b"));
+        assert_eq!(s.steps[1].law, Law::NegatedConstant);
+    }
+
+    #[test]
+    fn implication_to_bottom() {
+        let s = f("(a∨a)⇒⊥").simplify();
+        assert_eq!(s.result, f("¬a"));
+    }
+
+    #[test]
+    fn quantifiers() {
+        let s = f("∀x(P(x)∧∃yQ(x)∨⊥)").simplify();
+        assert_eq!(s.result, f("∀x(P(x)∧Q(x))"));
+        assert_eq!(
+            s.steps.iter().map(|step| step.law).collect::<Vec<_>>(),
+            vec![Law::Identity, Law::VacuousQuantifier]
+        );
+    }
+
+    #[test]
+    fn nothing_to_do() {
+        let s = f("a∧b⇒c").simplify();
+        assert!(s.steps.is_empty());
+        assert_eq!(s.result, s.original);
+    }
+
+    #[test]
+    fn steps_preserve_truth() {
+        let original = f("(p∨¬p)∧(q⇔⊥)∨(r∧(r∨q))");
+        let s = original.simplify();
+        for row in 0..8u8 {
+            let valuation = |v: char| match v {
+                'p' => row & 1 != 0,
+                'q' => row & 2 != 0,
+                _ => row & 4 != 0,
+            };
+            for step in s.steps.iter() {
+                assert_eq!(original.eval(&valuation), step.result.eval(&valuation));
+            }
+        }
+    }
+}
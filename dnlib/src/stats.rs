@@ -0,0 +1,115 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::proof::Proof;
+use crate::record::Statement;
+
+/// Figures describing the size and the difficulty of a proof.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    /// The number of records
+    pub records: usize,
+    /// The number of boxes the deepest record is in, the length of its `ctxt`
+    pub depth: usize,
+    /// How many records each rule justifies
+    pub rules: BTreeMap<String, usize>,
+    /// The number of records justified by a classical rule, such as `Raa`
+    pub classical_steps: usize,
+    /// The size of the largest formula, see [`Formula::size`](crate::formula::Formula::size)
+    pub formula_size: usize,
+    /// The number of distinct propositional variables
+    pub variables: usize,
+    /// The height of the conclusion in the proof tree, where every record is copied for each
+    /// record citing it: the number of records on the longest chain of premises
+    pub height: usize,
+}
+
+impl Proof {
+    /// Measures the proof, which is meant to be checked.
+    pub fn statistics(&self) -> Statistics {
+        let mut stats = Statistics {
+            records: self.records().len(),
+            ..Statistics::default()
+        };
+        let mut variables = BTreeSet::new();
+        let mut heights: Vec<usize> = Vec::with_capacity(self.records().len());
+        for (id, rec) in self.records().iter().enumerate() {
+            stats.depth = stats.depth.max(rec.ctxt.len());
+            *stats
+                .rules
+                .entry(rec.justif.name().to_string())
+                .or_default() += 1;
+            if self.is_classical_step(rec) {
+                stats.classical_steps += 1;
+            }
+            if let Statement::Supposons(f)
+            | Statement::Simple(f)
+            | Statement::Donc(f)
+            | Statement::Premise(f) = &rec.stmt
+            {
+                stats.formula_size = stats.formula_size.max(f.size());
+                variables.extend(f.variables());
+            }
+            let height = self
+                .premises(id)
+                .iter()
+                .map(|p| heights[*p])
+                .max()
+                .unwrap_or(0)
+                + 1;
+            heights.push(height);
+        }
+        stats.variables = variables.len();
+        stats.height = heights.last().copied().unwrap_or(0);
+        stats
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Records: {}", self.records)?;
+        writeln!(f, "Hypothesis depth: {}", self.depth)?;
+        writeln!(f, "Proof-tree height: {}", self.height)?;
+        writeln!(f, "Largest formula size: {}", self.formula_size)?;
+        writeln!(f, "Distinct variables: {}", self.variables)?;
+        writeln!(f, "Classical steps: {}", self.classical_steps)?;
+        writeln!(f, "Rules:")?;
+        for (rule, count) in &self.rules {
+            writeln!(f, "  {}: {}", rule, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_of_third() {
+        let proof = Proof::read_proof(include_str!("../proofs/third.proof")).unwrap();
+        let stats = proof.statistics();
+        let rules = [
+            ("EImpl", 3),
+            ("EOr", 1),
+            ("Hyp", 5),
+            ("IImpl", 5),
+            ("IOrL", 2),
+            ("IOrR", 2),
+            ("Raa", 1),
+            ("Rwrt", 2),
+        ];
+        assert_eq!(
+            stats,
+            Statistics {
+                records: 21,
+                depth: 3,
+                rules: rules.iter().map(|(r, n)| (r.to_string(), *n)).collect(),
+                classical_steps: 1,
+                formula_size: 8,
+                variables: 2,
+                height: 11,
+            }
+        );
+    }
+}
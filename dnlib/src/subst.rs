@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use thiserror::Error;
+
+use crate::formula::Formula;
+use crate::term::{fresh_variable, Term};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum UnificationError {
+    /// The two formulas have different main connectives
+    #[error("The two formulas do not have the same shape")]
+    Clash,
+    /// A variable would have to be replaced by a formula containing it
+    #[error("Variable {0} occurs in the formula it should be replaced with")]
+    OccursCheck(char),
+}
+
+/// A simultaneous substitution of formulas for propositional variables.
+///
+/// Variables without a binding are left untouched.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Substitution {
+    bindings: BTreeMap<char, Formula>,
+}
+
+impl Substitution {
+    /// Creates the empty substitution.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `v` to `formula`, returning the previous binding if any.
+    pub fn insert(&mut self, v: char, formula: Formula) -> Option<Formula> {
+        self.bindings.insert(v, formula)
+    }
+
+    /// Returns the formula bound to `v`.
+    pub fn get(&self, v: char) -> Option<&Formula> {
+        self.bindings.get(&v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Iterates over the bindings, ordered by variable.
+    pub fn iter(&self) -> impl Iterator<Item = (char, &Formula)> {
+        self.bindings.iter().map(|(v, f)| (*v, f))
+    }
+
+    /// Applies the substitution to a formula.
+    pub fn apply(&self, formula: &Formula) -> Formula {
+        match formula {
+            Formula::Top => Formula::Top,
+            Formula::Bottom => Formula::Bottom,
+            Formula::Variable(v) => match self.bindings.get(v) {
+                Some(f) => f.clone(),
+                None => Formula::Variable(*v),
+            },
+            Formula::Not(f) => Formula::Not(Box::new(self.apply(f))),
+            Formula::Necessarily(f) => Formula::Necessarily(Box::new(self.apply(f))),
+            Formula::Possibly(f) => Formula::Possibly(Box::new(self.apply(f))),
+            Formula::Or(a, b) => Formula::Or(Box::new(self.apply(a)), Box::new(self.apply(b))),
+            Formula::And(a, b) => Formula::And(Box::new(self.apply(a)), Box::new(self.apply(b))),
+            Formula::Implies(a, b) => {
+                Formula::Implies(Box::new(self.apply(a)), Box::new(self.apply(b)))
+            }
+            Formula::RLImplies(a, b) => {
+                Formula::RLImplies(Box::new(self.apply(a)), Box::new(self.apply(b)))
+            }
+            Formula::Equiv(a, b) => {
+                Formula::Equiv(Box::new(self.apply(a)), Box::new(self.apply(b)))
+            }
+            Formula::Predicate(_, _) | Formula::Equal(_, _) => formula.clone(),
+            Formula::Forall(x, f) | Formula::Exists(x, f) => {
+                // The bound variable is renamed when it would capture a free variable of a
+                // replacement.
+                let mut free = BTreeSet::new();
+                for v in f.variables() {
+                    if let Some(g) = self.bindings.get(&v) {
+                        free.extend(g.free_term_variables());
+                    }
+                }
+                let (x, f) = if free.contains(x) {
+                    free.extend(f.term_variables());
+                    let y = fresh_variable(&free);
+                    (y, f.substitute_term(*x, &Term::Variable(y)))
+                } else {
+                    (*x, *f.clone())
+                };
+                match formula {
+                    Formula::Forall(_, _) => Formula::Forall(x, Box::new(self.apply(&f))),
+                    _ => Formula::Exists(x, Box::new(self.apply(&f))),
+                }
+            }
+        }
+    }
+
+    /// Composes two substitutions: applying the result is the same as applying `self`, then
+    /// `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        let mut bindings: BTreeMap<char, Formula> = self
+            .bindings
+            .iter()
+            .map(|(v, f)| (*v, other.apply(f)))
+            .collect();
+        for (v, f) in other.bindings.iter() {
+            bindings.entry(*v).or_insert_with(|| f.clone());
+        }
+        Self { bindings }
+    }
+}
+
+impl FromIterator<(char, Formula)> for Substitution {
+    fn from_iter<T: IntoIterator<Item = (char, Formula)>>(iter: T) -> Self {
+        Self {
+            bindings: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Formula {
+    /// Replaces the variables of the formula according to `subst`.
+    pub fn substitute(&self, subst: &Substitution) -> Formula {
+        subst.apply(self)
+    }
+
+    /// Matches `self`, seen as a pattern whose variables are metavariables, against the
+    /// concrete formula `target`.
+    ///
+    /// Returns the substitution `σ` such that `σ(self) == target`, if there is one.
+    pub fn match_pattern(&self, target: &Formula) -> Option<Substitution> {
+        let mut subst = Substitution::new();
+        if self.match_pattern_into(target, &mut subst) {
+            Some(subst)
+        } else {
+            None
+        }
+    }
+
+    /// Same as [`Formula::match_pattern`], but extends an existing substitution. This is
+    /// how a schema with several premises is matched: every premise is matched against the
+    /// same substitution.
+    ///
+    /// A metavariable under a quantifier of the pattern only stands for formulas in which the
+    /// variable it binds is not free: `∀xA` matches `∀xP(y)`, not `∀xP(x)`, as `A` does not
+    /// depend on `x`.
+    ///
+    /// On failure, `subst` may have been partially extended.
+    pub fn match_pattern_into(&self, target: &Formula, subst: &mut Substitution) -> bool {
+        self.match_under(target, subst, &mut Vec::new())
+    }
+
+    /// Matches the pattern under the quantifiers binding `bound`.
+    fn match_under(
+        &self,
+        target: &Formula,
+        subst: &mut Substitution,
+        bound: &mut Vec<char>,
+    ) -> bool {
+        match (self, target) {
+            (Formula::Variable(v), _) => {
+                if bound.iter().any(|x| target.has_free(*x)) {
+                    return false;
+                }
+                match subst.get(*v) {
+                    Some(bound) => bound == target,
+                    None => {
+                        subst.insert(*v, target.clone());
+                        true
+                    }
+                }
+            }
+            (Formula::Top, Formula::Top) | (Formula::Bottom, Formula::Bottom) => true,
+            (Formula::Predicate(_, _), _) | (Formula::Equal(_, _), _) => self == target,
+            (Formula::Not(p), Formula::Not(t))
+            | (Formula::Necessarily(p), Formula::Necessarily(t))
+            | (Formula::Possibly(p), Formula::Possibly(t)) => p.match_under(t, subst, bound),
+            (Formula::Forall(x, p), Formula::Forall(y, t))
+            | (Formula::Exists(x, p), Formula::Exists(y, t)) => {
+                if x != y {
+                    return false;
+                }
+                bound.push(*x);
+                let matched = p.match_under(t, subst, bound);
+                bound.pop();
+                matched
+            }
+            (Formula::Or(pa, pb), Formula::Or(ta, tb))
+            | (Formula::And(pa, pb), Formula::And(ta, tb))
+            | (Formula::Implies(pa, pb), Formula::Implies(ta, tb))
+            | (Formula::RLImplies(pa, pb), Formula::RLImplies(ta, tb))
+            | (Formula::Equiv(pa, pb), Formula::Equiv(ta, tb)) => {
+                pa.match_under(ta, subst, bound) && pb.match_under(tb, subst, bound)
+            }
+            _ => false,
+        }
+    }
+
+    /// Unifies two patterns, every variable of which is considered a metavariable.
+    ///
+    /// Returns the most general unifier `σ`, such that `σ(self) == σ(other)`.
+    pub fn unify(&self, other: &Formula) -> Result<Substitution, UnificationError> {
+        let mut subst = Substitution::new();
+        unify_into(self, other, &mut subst)?;
+        Ok(subst)
+    }
+}
+
+/// Extends `subst` so that it unifies `left` and `right`. `subst` is kept idempotent: no bound
+/// variable appears in the formulas it is bound to.
+fn unify_into(
+    left: &Formula,
+    right: &Formula,
+    subst: &mut Substitution,
+) -> Result<(), UnificationError> {
+    let left = subst.apply(left);
+    let right = subst.apply(right);
+    match (&left, &right) {
+        (Formula::Variable(v), Formula::Variable(w)) if v == w => Ok(()),
+        (Formula::Variable(v), other) | (other, Formula::Variable(v)) => {
+            if other.occurs(*v) {
+                return Err(UnificationError::OccursCheck(*v));
+            }
+            let binding: Substitution = [(*v, other.clone())].into_iter().collect();
+            *subst = subst.then(&binding);
+            Ok(())
+        }
+        (Formula::Top, Formula::Top) | (Formula::Bottom, Formula::Bottom) => Ok(()),
+        (Formula::Predicate(_, _), Formula::Predicate(_, _))
+        | (Formula::Equal(_, _), Formula::Equal(_, _))
+            if left == right =>
+        {
+            Ok(())
+        }
+        (Formula::Not(l), Formula::Not(r))
+        | (Formula::Necessarily(l), Formula::Necessarily(r))
+        | (Formula::Possibly(l), Formula::Possibly(r)) => unify_into(l, r, subst),
+        (Formula::Forall(x, l), Formula::Forall(y, r))
+        | (Formula::Exists(x, l), Formula::Exists(y, r))
+            if x == y =>
+        {
+            unify_into(l, r, subst)
+        }
+        (Formula::Or(la, lb), Formula::Or(ra, rb))
+        | (Formula::And(la, lb), Formula::And(ra, rb))
+        | (Formula::Implies(la, lb), Formula::Implies(ra, rb))
+        | (Formula::RLImplies(la, lb), Formula::RLImplies(ra, rb))
+        | (Formula::Equiv(la, lb), Formula::Equiv(ra, rb)) => {
+            unify_into(la, ra, subst)?;
+            unify_into(lb, rb, subst)
+        }
+        _ => Err(UnificationError::Clash),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    #[test]
+    fn substitute_is_simultaneous() {
+        let subst: Substitution = [('a', f("b")), ('b', f("a∧c"))].into_iter().collect();
+        assert_eq!(f("a⇒b").substitute(&subst), f("b⇒a∧c"));
+    }
+
+    #[test]
+    fn composition() {
+        let first: Substitution = [('a', f("b∨c"))].into_iter().collect();
+        let second: Substitution = [('b', f("⊥")), ('d', f("e"))].into_iter().collect();
+        let both = first.then(&second);
+        let g = f("a⇒d");
+        assert_eq!(
+            g.substitute(&both),
+            g.substitute(&first).substitute(&second)
+        );
+    }
+
+    #[test]
+    fn match_modus_ponens() {
+        let pattern = f("A⇒B");
+        let subst = pattern.match_pattern(&f("¬x⇒(y∨z)")).unwrap();
+        assert_eq!(subst.get('A'), Some(&f("¬x")));
+        assert_eq!(subst.get('B'), Some(&f("y∨z")));
+    }
+
+    #[test]
+    fn match_is_consistent() {
+        assert!(f("A∧A").match_pattern(&f("x∧x")).is_some());
+        assert!(f("A∧A").match_pattern(&f("x∧y")).is_none());
+        assert!(f("A∧B").match_pattern(&f("x∨y")).is_none());
+    }
+
+    #[test]
+    fn match_several_premises() {
+        let mut subst = Substitution::new();
+        assert!(f("A⇒B").match_pattern_into(&f("p⇒q"), &mut subst));
+        assert!(!f("¬B").match_pattern_into(&f("¬p"), &mut subst));
+    }
+
+    #[test]
+    fn unify_patterns() {
+        let left = f("A⇒(B∧C)");
+        let right = f("(D∨E)⇒F");
+        let subst = left.unify(&right).unwrap();
+        assert_eq!(left.substitute(&subst), right.substitute(&subst));
+        assert_eq!(subst.get('A'), Some(&f("D∨E")));
+        assert_eq!(subst.get('F'), Some(&f("B∧C")));
+    }
+
+    #[test]
+    fn unify_chains_bindings() {
+        let left = f("A∧A");
+        let right = f("B∧(C⇒D)");
+        let subst = left.unify(&right).unwrap();
+        assert_eq!(left.substitute(&subst), right.substitute(&subst));
+        assert_eq!(subst.get('A'), Some(&f("C⇒D")));
+        assert_eq!(subst.get('B'), Some(&f("C⇒D")));
+    }
+
+    #[test]
+    fn unify_failures() {
+        assert_eq!(f("A∧B").unify(&f("A∨B")), Err(UnificationError::Clash));
+        assert_eq!(
+            f("A").unify(&f("¬A")),
+            Err(UnificationError::OccursCheck('A'))
+        );
+    }
+
+    #[test]
+    fn substitution_under_quantifier() {
+        let subst: Substitution = [('A', f("P(x)"))].into_iter().collect();
+        let g = f("∀x(A∧Q(x))").substitute(&subst);
+        assert!(g.alpha_eq(&f("∀y(P(x)∧Q(y))")));
+        assert!(f("∀xA").match_pattern(&f("∀xP(y)")).is_some());
+        assert!(f("∀xA").match_pattern(&f("∃xP(x)")).is_none());
+    }
+
+    #[test]
+    fn match_avoids_capture() {
+        assert!(f("∀xA").match_pattern(&f("∀xP(x)")).is_none());
+        assert!(f("A⇒∀yA").match_pattern(&f("P(y)⇒∀yP(y)")).is_none());
+        assert!(f("∀yA⇒A").match_pattern(&f("∀yP(x)⇒P(x)")).is_some());
+    }
+}
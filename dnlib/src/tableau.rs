@@ -0,0 +1,428 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::formula::{Formula, NotPropositional};
+
+/// A formula signed `T` (it holds) or `F` (it does not).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedFormula {
+    pub sign: bool,
+    pub formula: Formula,
+}
+
+impl SignedFormula {
+    pub fn new(sign: bool, formula: Formula) -> Self {
+        Self { sign, formula }
+    }
+
+    /// Returns the formulas the signed formula expands into. Formulas of an inner vector are
+    /// added to the same branch, and the branch splits when there are several vectors (a β
+    /// formula). Literals, ⊤ and ⊥ do not expand.
+    fn expansion(&self) -> Option<Vec<Vec<SignedFormula>>> {
+        let t = |f: &Formula| SignedFormula::new(true, f.clone());
+        let f = |f: &Formula| SignedFormula::new(false, f.clone());
+        Some(match (&self.formula, self.sign) {
+            (Formula::Not(a), true) => vec![vec![f(a)]],
+            (Formula::Not(a), false) => vec![vec![t(a)]],
+            (Formula::And(a, b), true) => vec![vec![t(a), t(b)]],
+            (Formula::And(a, b), false) => vec![vec![f(a)], vec![f(b)]],
+            (Formula::Or(a, b), true) => vec![vec![t(a)], vec![t(b)]],
+            (Formula::Or(a, b), false) => vec![vec![f(a), f(b)]],
+            (Formula::Implies(a, b), true) | (Formula::RLImplies(b, a), true) => {
+                vec![vec![f(a)], vec![t(b)]]
+            }
+            (Formula::Implies(a, b), false) | (Formula::RLImplies(b, a), false) => {
+                vec![vec![t(a), f(b)]]
+            }
+            (Formula::Equiv(a, b), true) => vec![vec![t(a), t(b)], vec![f(a), f(b)]],
+            (Formula::Equiv(a, b), false) => vec![vec![t(a), f(b)], vec![f(a), t(b)]],
+            _ => return None,
+        })
+    }
+
+    /// Tells whether the signed formula closes a branch on its own: `T ⊥` or `F ⊤`.
+    fn is_absurd(&self) -> bool {
+        matches!(
+            (&self.formula, self.sign),
+            (Formula::Bottom, true) | (Formula::Top, false)
+        )
+    }
+}
+
+impl fmt::Display for SignedFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.sign { 'T' } else { 'F' };
+        write!(f, "{} {}", sign, self.formula)
+    }
+}
+
+/// A signed formula of the tableau, numbered in the order formulas are added.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub id: usize,
+    pub signed: SignedFormula,
+    /// The entry this one was expanded from
+    pub from: Option<usize>,
+}
+
+/// A node of the tableau: formulas added one below the other, followed either by the
+/// branches it splits into, by the closure of the branch, or by nothing if the branch is
+/// open.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableauNode {
+    pub entries: Vec<Entry>,
+    pub children: Vec<TableauNode>,
+    /// The contradictory entries closing the branch. An absurd entry such as `T ⊥`
+    /// contradicts itself.
+    pub closure: Option<(usize, usize)>,
+}
+
+impl TableauNode {
+    pub fn is_closed(&self) -> bool {
+        self.closure.is_some()
+            || (!self.children.is_empty() && self.children.iter().all(Self::is_closed))
+    }
+
+    fn open_branches<'a>(&'a self, path: &mut Vec<&'a Entry>, branches: &mut Vec<BTreeSet<char>>) {
+        let len = path.len();
+        path.extend(self.entries.iter());
+        if self.children.is_empty() && self.closure.is_none() {
+            branches.push(true_variables(path.iter().map(|e| &e.signed)));
+        }
+        for child in self.children.iter() {
+            child.open_branches(path, branches);
+        }
+        path.truncate(len);
+    }
+}
+
+/// Returns the variables signed `T` among `signed`.
+fn true_variables<'a>(signed: impl Iterator<Item = &'a SignedFormula>) -> BTreeSet<char> {
+    signed
+        .filter_map(|s| match (&s.formula, s.sign) {
+            (Formula::Variable(v), true) => Some(*v),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A fully expanded analytic tableau. Formulas are expanded at most once on each branch, α
+/// formulas before β ones so that the tableau branches as late as possible.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tableau {
+    pub root: TableauNode,
+}
+
+impl Tableau {
+    /// Expands the tableau starting with `signed`. Every formula has to be propositional.
+    pub fn new(signed: Vec<SignedFormula>) -> Result<Self, NotPropositional> {
+        if !signed.iter().all(|s| s.formula.is_propositional()) {
+            return Err(NotPropositional);
+        }
+        let mut next = 0;
+        let entries = signed
+            .into_iter()
+            .map(|signed| {
+                next += 1;
+                Entry {
+                    id: next,
+                    signed,
+                    from: None,
+                }
+            })
+            .collect();
+        let root = grow(&mut next, Vec::new(), BTreeSet::new(), entries);
+        Ok(Self { root })
+    }
+
+    /// Tells whether every branch is closed, the initial signed formulas being contradictory.
+    pub fn is_closed(&self) -> bool {
+        self.root.is_closed()
+    }
+
+    /// Returns, for each open branch, the variables that are true in the valuation it
+    /// describes. The other variables are false. Such a valuation satisfies every signed
+    /// formula of the branch, the initial ones included.
+    pub fn countermodels(&self) -> Vec<BTreeSet<char>> {
+        let mut branches = Vec::new();
+        self.root.open_branches(&mut Vec::new(), &mut branches);
+        branches
+    }
+
+    /// Renders the tableau for the LaTeX `forest` package.
+    pub fn to_latex(&self) -> String {
+        let mut latex = String::from("\\begin{forest}\nfor tree={align=center}\n");
+        latex_node(&self.root, 0, &mut latex);
+        latex.push_str("\\end{forest}\n");
+        latex
+    }
+}
+
+/// Adds `entries` to the branch made of `path`, then expands the branch until it closes,
+/// splits or has nothing left to expand.
+fn grow(
+    next: &mut usize,
+    mut path: Vec<Entry>,
+    mut expanded: BTreeSet<usize>,
+    entries: Vec<Entry>,
+) -> TableauNode {
+    let mut node = TableauNode {
+        entries: Vec::new(),
+        children: Vec::new(),
+        closure: None,
+    };
+    let mut queue = entries;
+    loop {
+        for entry in queue.drain(..) {
+            let closure = if entry.signed.is_absurd() {
+                Some((entry.id, entry.id))
+            } else {
+                path.iter()
+                    .find(|e| {
+                        e.signed.formula == entry.signed.formula
+                            && e.signed.sign != entry.signed.sign
+                    })
+                    .map(|e| (e.id, entry.id))
+            };
+            path.push(entry.clone());
+            node.entries.push(entry);
+            if closure.is_some() {
+                node.closure = closure;
+                return node;
+            }
+        }
+        let pending: Vec<(usize, Vec<Vec<SignedFormula>>)> = path
+            .iter()
+            .filter(|e| !expanded.contains(&e.id))
+            .filter_map(|e| e.signed.expansion().map(|x| (e.id, x)))
+            .collect();
+        let Some((id, expansion)) = pending
+            .iter()
+            .find(|(_, x)| x.len() == 1)
+            .or(pending.first())
+            .cloned()
+        else {
+            return node;
+        };
+        expanded.insert(id);
+        let mut number = |branch: Vec<SignedFormula>| -> Vec<Entry> {
+            branch
+                .into_iter()
+                .map(|signed| {
+                    *next += 1;
+                    Entry {
+                        id: *next,
+                        signed,
+                        from: Some(id),
+                    }
+                })
+                .collect()
+        };
+        if expansion.len() == 1 {
+            queue = expansion.into_iter().flat_map(&mut number).collect();
+        } else {
+            let branches: Vec<Vec<Entry>> = expansion.into_iter().map(number).collect();
+            node.children = branches
+                .into_iter()
+                .map(|branch| grow(next, path.clone(), expanded.clone(), branch))
+                .collect();
+            return node;
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.from {
+            Some(from) => write!(f, "{}. {}  ({})", self.id, self.signed, from),
+            None => write!(f, "{}. {}", self.id, self.signed),
+        }
+    }
+}
+
+/// Returns the line ending a branch, if it does not split.
+fn branch_end(node: &TableauNode, path: &[&Entry]) -> Option<String> {
+    match (node.closure, node.children.is_empty()) {
+        (Some((a, b)), _) if a == b => Some(format!("x closed by {}", a)),
+        (Some((a, b)), _) => Some(format!("x closed by {} and {}", a, b)),
+        (None, true) => {
+            let valuation = true_variables(path.iter().map(|e| &e.signed));
+            let valuation: Vec<String> = valuation.iter().map(|v| v.to_string()).collect();
+            match valuation.is_empty() {
+                true => Some("o open, every variable false".to_string()),
+                false => Some(format!("o open, true: {}", valuation.join(", "))),
+            }
+        }
+        (None, false) => None,
+    }
+}
+
+fn write_node<'a>(
+    f: &mut fmt::Formatter<'_>,
+    node: &'a TableauNode,
+    path: &mut Vec<&'a Entry>,
+    first: &str,
+    rest: &str,
+) -> fmt::Result {
+    let len = path.len();
+    path.extend(node.entries.iter());
+    let mut lines: Vec<String> = node.entries.iter().map(|e| e.to_string()).collect();
+    lines.extend(branch_end(node, path));
+    for (i, line) in lines.iter().enumerate() {
+        writeln!(f, "{}{}", if i == 0 { first } else { rest }, line)?;
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        if i + 1 < node.children.len() {
+            write_node(
+                f,
+                child,
+                path,
+                &format!("{}+-- ", rest),
+                &format!("{}|   ", rest),
+            )?;
+        } else {
+            write_node(
+                f,
+                child,
+                path,
+                &format!("{}`-- ", rest),
+                &format!("{}    ", rest),
+            )?;
+        }
+    }
+    path.truncate(len);
+    Ok(())
+}
+
+impl fmt::Display for Tableau {
+    /// Prints the tableau as an ASCII tree, each formula being followed by the number of the
+    /// one it was expanded from.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_node(f, &self.root, &mut Vec::new(), "", "")
+    }
+}
+
+/// Returns the formula in LaTeX math mode.
+fn latex_formula(formula: &Formula) -> String {
+    formula
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            '¬' => "\\lnot ".to_string(),
+            '∧' => "\\land ".to_string(),
+            '∨' => "\\lor ".to_string(),
+            '⇒' => "\\Rightarrow ".to_string(),
+            '⇐' => "\\Leftarrow ".to_string(),
+            '⇔' => "\\Leftrightarrow ".to_string(),
+            '⊤' => "\\top ".to_string(),
+            '⊥' => "\\bot ".to_string(),
+            c => c.to_string(),
+        })
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+fn latex_node(node: &TableauNode, depth: usize, latex: &mut String) {
+    let indent = "  ".repeat(depth);
+    let mut lines: Vec<String> = node
+        .entries
+        .iter()
+        .map(|e| {
+            let sign = if e.signed.sign { "T" } else { "F" };
+            let from = e
+                .from
+                .map(|from| format!(" \\quad ({})", from))
+                .unwrap_or_default();
+            format!(
+                "${}.\\ \\mathsf{{{}}}\\ {}{}$",
+                e.id,
+                sign,
+                latex_formula(&e.signed.formula),
+                from
+            )
+        })
+        .collect();
+    match (node.closure, node.children.is_empty()) {
+        (Some((a, b)), _) if a == b => lines.push(format!("$\\times$ ({})", a)),
+        (Some((a, b)), _) => lines.push(format!("$\\times$ ({}, {})", a, b)),
+        (None, true) => lines.push("$\\circ$".to_string()),
+        (None, false) => (),
+    }
+    latex.push_str(&format!("{}[{{{}}}", indent, lines.join(" \\\\ ")));
+    if node.children.is_empty() {
+        latex.push_str("]\n");
+    } else {
+        latex.push('\n');
+        for child in node.children.iter() {
+            latex_node(child, depth + 1, latex);
+        }
+        latex.push_str(&format!("{}]\n", indent));
+    }
+}
+
+impl Formula {
+    /// Builds the tableau of `F self`, which closes if and only if the formula is valid.
+    pub fn tableau(&self) -> Result<Tableau, NotPropositional> {
+        Tableau::new(vec![SignedFormula::new(false, self.clone())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    #[test]
+    fn valid_formulas_close() {
+        for formula in ["(a⇒b)⇒¬b⇒¬a", "a∨¬a", "(a⇔b)⇔(b⇔a)", "((a⇒b)⇒a)⇒a", "⊥⇒a"]
+        {
+            let tableau = f(formula).tableau().unwrap();
+            assert!(tableau.is_closed(), "{}\n{}", formula, tableau);
+            assert!(tableau.countermodels().is_empty());
+        }
+    }
+
+    #[test]
+    fn open_branches_give_countermodels() {
+        for formula in ["(a⇒b)⇒b⇒a", "a∧b⇔a∨b", "a⇐b∧c"] {
+            let formula = f(formula);
+            let tableau = formula.tableau().unwrap();
+            assert!(!tableau.is_closed());
+            let countermodels = tableau.countermodels();
+            assert!(!countermodels.is_empty());
+            for valuation in countermodels {
+                assert!(
+                    formula.eval(&|v| valuation.contains(&v)) == Ok(false),
+                    "{:?}",
+                    valuation
+                );
+            }
+        }
+        assert_eq!(f("∀xP(x)").tableau(), Err(NotPropositional));
+    }
+
+    #[test]
+    fn rendering() {
+        let tableau = f("(a⇒b)∧a⇒b").tableau().unwrap();
+        assert_eq!(
+            tableau.to_string(),
+            "1. F (a⇒b)∧a⇒b\n\
+             2. T (a⇒b)∧a  (1)\n\
+             3. F b  (1)\n\
+             4. T a⇒b  (2)\n\
+             5. T a  (2)\n\
+             +-- 6. F a  (4)\n\
+             |   x closed by 5 and 6\n\
+             `-- 7. T b  (4)\n    \
+                 x closed by 3 and 7\n"
+        );
+        let latex = tableau.to_latex();
+        assert!(latex.starts_with("\\begin{forest}"));
+        assert!(latex.contains("$6.\\ \\mathsf{F}\\ a \\quad (4)$ \\\\ $\\times$ (5, 6)"));
+        assert!(latex.contains("\\land a\\Rightarrow b"));
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::{Enumerate, Peekable};
+
+use crate::formula::{Formula, TokenizationError};
+
+pub(crate) type Chars<'a> = Peekable<Enumerate<std::str::Chars<'a>>>;
+
+/// A first-order term.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+    Variable(char),
+    /// Function symbol applied to terms. Constants are functions without arguments, as `c()`.
+    Function(char, Vec<Term>),
+}
+
+impl Term {
+    /// Reads a term from a string, such as `f(x,g(y))`.
+    pub fn read(input: &str) -> Result<Self, TokenizationError> {
+        let mut chars = input.chars().enumerate().peekable();
+        let term = Self::read_from(&mut chars)?;
+        match chars.next() {
+            Some((pos, c)) => Err(TokenizationError::InvalidCharacter(pos, c)),
+            None => Ok(term),
+        }
+    }
+
+    pub(crate) fn read_from(chars: &mut Chars) -> Result<Self, TokenizationError> {
+        match chars.next() {
+            Some((_, c)) if c.is_ascii_alphabetic() => match chars.peek() {
+                Some((_, '(')) => {
+                    chars.next();
+                    Ok(Term::Function(c, Self::read_arguments(chars)?))
+                }
+                _ => Ok(Term::Variable(c)),
+            },
+            Some((pos, _)) => Err(TokenizationError::InvalidTerm(pos)),
+            None => Err(TokenizationError::MissingTerm),
+        }
+    }
+
+    /// Reads `t1,…,tn)`, the opening parenthesis being already read.
+    pub(crate) fn read_arguments(chars: &mut Chars) -> Result<Vec<Self>, TokenizationError> {
+        let mut args = Vec::new();
+        if let Some((_, ')')) = chars.peek() {
+            chars.next();
+            return Ok(args);
+        }
+        loop {
+            args.push(Self::read_from(chars)?);
+            match chars.next() {
+                Some((_, ',')) => (),
+                Some((_, ')')) => return Ok(args),
+                Some((pos, _)) => return Err(TokenizationError::InvalidTerm(pos)),
+                None => return Err(TokenizationError::UnmatchedOpeningParenthesis),
+            }
+        }
+    }
+
+    /// Returns the variables of the term.
+    pub fn variables(&self) -> BTreeSet<char> {
+        let mut vars = BTreeSet::new();
+        self.collect_variables(&mut vars);
+        vars
+    }
+
+    fn collect_variables(&self, vars: &mut BTreeSet<char>) {
+        match self {
+            Term::Variable(x) => {
+                vars.insert(*x);
+            }
+            Term::Function(_, args) => args.iter().for_each(|t| t.collect_variables(vars)),
+        }
+    }
+
+    /// Tells whether the variable `x` appears in the term.
+    pub fn contains(&self, x: char) -> bool {
+        match self {
+            Term::Variable(y) => *y == x,
+            Term::Function(_, args) => args.iter().any(|t| t.contains(x)),
+        }
+    }
+
+    /// Replaces the variable `x` by `t`.
+    pub fn substitute(&self, x: char, t: &Term) -> Term {
+        match self {
+            Term::Variable(y) if *y == x => t.clone(),
+            Term::Variable(y) => Term::Variable(*y),
+            Term::Function(f, args) => {
+                Term::Function(*f, args.iter().map(|a| a.substitute(x, t)).collect())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Variable(x) => write!(f, "{}", x),
+            Term::Function(g, args) => {
+                let args: Vec<String> = args.iter().map(|t| t.to_string()).collect();
+                write!(f, "{}({})", g, args.join(","))
+            }
+        }
+    }
+}
+
+/// Returns a variable name that is not in `avoid`, lowercase ones first.
+pub fn fresh_variable(avoid: &BTreeSet<char>) -> char {
+    ('a'..='z')
+        .chain('A'..='Z')
+        .find(|c| !avoid.contains(c))
+        .expect("every variable name is already used")
+}
+
+impl Formula {
+    /// Returns the term variables having a free occurrence in the formula.
+    pub fn free_term_variables(&self) -> BTreeSet<char> {
+        let mut free = BTreeSet::new();
+        self.collect_term_variables(&mut Vec::new(), &mut free, false);
+        free
+    }
+
+    /// Returns every term variable of the formula, bound or free.
+    pub fn term_variables(&self) -> BTreeSet<char> {
+        let mut vars = BTreeSet::new();
+        self.collect_term_variables(&mut Vec::new(), &mut vars, true);
+        vars
+    }
+
+    fn collect_term_variables(&self, bound: &mut Vec<char>, vars: &mut BTreeSet<char>, all: bool) {
+        match self {
+            Formula::Top | Formula::Bottom | Formula::Variable(_) => (),
+            Formula::Predicate(_, args) => {
+                for t in args.iter() {
+                    vars.extend(t.variables().into_iter().filter(|x| all || !bound.contains(x)));
+                }
+            }
+            Formula::Equal(l, r) => {
+                for t in [l, r] {
+                    vars.extend(t.variables().into_iter().filter(|x| all || !bound.contains(x)));
+                }
+            }
+            Formula::Not(f) | Formula::Necessarily(f) | Formula::Possibly(f) => {
+                f.collect_term_variables(bound, vars, all)
+            }
+            Formula::Or(a, b)
+            | Formula::And(a, b)
+            | Formula::Implies(a, b)
+            | Formula::RLImplies(a, b)
+            | Formula::Equiv(a, b) => {
+                a.collect_term_variables(bound, vars, all);
+                b.collect_term_variables(bound, vars, all);
+            }
+            Formula::Forall(x, f) | Formula::Exists(x, f) => {
+                if all {
+                    vars.insert(*x);
+                }
+                bound.push(*x);
+                f.collect_term_variables(bound, vars, all);
+                bound.pop();
+            }
+        }
+    }
+
+    /// Tells whether the term variable `x` has a free occurrence in the formula.
+    pub fn has_free(&self, x: char) -> bool {
+        self.free_term_variables().contains(&x)
+    }
+
+    /// Replaces the free occurrences of the term variable `x` by `t`, renaming bound variables
+    /// when they would capture a variable of `t`.
+    pub fn substitute_term(&self, x: char, t: &Term) -> Formula {
+        let sub = |f: &Formula| Box::new(f.substitute_term(x, t));
+        match self {
+            Formula::Top | Formula::Bottom | Formula::Variable(_) => self.clone(),
+            Formula::Predicate(p, args) => {
+                Formula::Predicate(*p, args.iter().map(|a| a.substitute(x, t)).collect())
+            }
+            Formula::Equal(l, r) => Formula::Equal(l.substitute(x, t), r.substitute(x, t)),
+            Formula::Not(f) => Formula::Not(sub(f)),
+            Formula::Necessarily(f) => Formula::Necessarily(sub(f)),
+            Formula::Possibly(f) => Formula::Possibly(sub(f)),
+            Formula::Or(a, b) => Formula::Or(sub(a), sub(b)),
+            Formula::And(a, b) => Formula::And(sub(a), sub(b)),
+            Formula::Implies(a, b) => Formula::Implies(sub(a), sub(b)),
+            Formula::RLImplies(a, b) => Formula::RLImplies(sub(a), sub(b)),
+            Formula::Equiv(a, b) => Formula::Equiv(sub(a), sub(b)),
+            Formula::Forall(y, f) | Formula::Exists(y, f) => {
+                let (y, body) = if *y == x || !f.has_free(x) {
+                    return self.clone();
+                } else if t.contains(*y) {
+                    let mut avoid = f.term_variables();
+                    avoid.extend(t.variables());
+                    avoid.insert(x);
+                    let z = fresh_variable(&avoid);
+                    (z, f.substitute_term(*y, &Term::Variable(z)))
+                } else {
+                    (*y, *f.clone())
+                };
+                let body = Box::new(body.substitute_term(x, t));
+                match self {
+                    Formula::Forall(_, _) => Formula::Forall(y, body),
+                    _ => Formula::Exists(y, body),
+                }
+            }
+        }
+    }
+
+    /// Tells whether two formulas are equal up to the renaming of bound variables.
+    pub fn alpha_eq(&self, other: &Formula) -> bool {
+        alpha_eq_in(self, other, &mut Vec::new())
+    }
+}
+
+/// `bound` pairs the variables bound on the left with those bound on the right, innermost
+/// last.
+fn alpha_eq_in(left: &Formula, right: &Formula, bound: &mut Vec<(char, char)>) -> bool {
+    match (left, right) {
+        (Formula::Top, Formula::Top) | (Formula::Bottom, Formula::Bottom) => true,
+        (Formula::Variable(v), Formula::Variable(w)) => v == w,
+        (Formula::Predicate(p, pargs), Formula::Predicate(q, qargs)) => {
+            p == q
+                && pargs.len() == qargs.len()
+                && pargs
+                    .iter()
+                    .zip(qargs.iter())
+                    .all(|(s, t)| term_alpha_eq(s, t, bound))
+        }
+        (Formula::Equal(l1, r1), Formula::Equal(l2, r2)) => {
+            term_alpha_eq(l1, l2, bound) && term_alpha_eq(r1, r2, bound)
+        }
+        (Formula::Not(f), Formula::Not(g))
+        | (Formula::Necessarily(f), Formula::Necessarily(g))
+        | (Formula::Possibly(f), Formula::Possibly(g)) => alpha_eq_in(f, g, bound),
+        (Formula::Or(a1, b1), Formula::Or(a2, b2))
+        | (Formula::And(a1, b1), Formula::And(a2, b2))
+        | (Formula::Implies(a1, b1), Formula::Implies(a2, b2))
+        | (Formula::RLImplies(a1, b1), Formula::RLImplies(a2, b2))
+        | (Formula::Equiv(a1, b1), Formula::Equiv(a2, b2)) => {
+            alpha_eq_in(a1, a2, bound) && alpha_eq_in(b1, b2, bound)
+        }
+        (Formula::Forall(x, f), Formula::Forall(y, g))
+        | (Formula::Exists(x, f), Formula::Exists(y, g)) => {
+            bound.push((*x, *y));
+            let eq = alpha_eq_in(f, g, bound);
+            bound.pop();
+            eq
+        }
+        _ => false,
+    }
+}
+
+fn term_alpha_eq(left: &Term, right: &Term, bound: &[(char, char)]) -> bool {
+    match (left, right) {
+        (Term::Variable(x), Term::Variable(y)) => {
+            match bound.iter().rev().find(|(bx, by)| bx == x || by == y) {
+                Some((bx, by)) => bx == x && by == y,
+                None => x == y,
+            }
+        }
+        (Term::Function(f, fargs), Term::Function(g, gargs)) => {
+            f == g
+                && fargs.len() == gargs.len()
+                && fargs
+                    .iter()
+                    .zip(gargs.iter())
+                    .all(|(s, t)| term_alpha_eq(s, t, bound))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(input: &str) -> Formula {
+        Formula::read(input).unwrap()
+    }
+
+    fn t(input: &str) -> Term {
+        Term::read(input).unwrap()
+    }
+
+    #[test]
+    fn read_first_order() {
+        assert_eq!(
+            f("∀x(P(x)⇒x=f(y))"),
+            Formula::Forall(
+                'x',
+                Box::new(Formula::Implies(
+                    Box::new(Formula::Predicate('P', vec![t("x")])),
+                    Box::new(Formula::Equal(
+                        t("x"),
+                        Term::Function('f', vec![Term::Variable('y')])
+                    )),
+                ))
+            )
+        );
+        // Quantifiers bind as tightly as negation.
+        assert_eq!(
+            f("∀xP(x)∧Q"),
+            Formula::And(
+                Box::new(Formula::Forall('x', Box::new(f("P(x)")))),
+                Box::new(Formula::Variable('Q'))
+            )
+        );
+        assert_eq!(Formula::read("∀(P(x))"), Err(TokenizationError::MissingQuantifiedVariable(0)));
+        assert_eq!(Formula::read("P(x"), Err(TokenizationError::UnmatchedOpeningParenthesis));
+    }
+
+    #[test]
+    fn display_first_order() {
+        for input in ["∀x∃yR(x,f(y,c()))", "¬∀x(P(x)⇒x=y)", "∃xP(x)∨Q"] {
+            assert_eq!(f(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn free_variables() {
+        let g = f("∀x(P(x,y)∧∃yQ(y))∨R(x)");
+        assert_eq!(g.free_term_variables(), BTreeSet::from(['x', 'y']));
+        assert!(!f("∀xP(x)").has_free('x'));
+    }
+
+    #[test]
+    fn substitution_avoids_capture() {
+        let g = f("∀yR(x,y)");
+        let h = g.substitute_term('x', &t("f(y)"));
+        assert!(h.alpha_eq(&f("∀zR(f(y),z)")));
+        assert!(!h.alpha_eq(&f("∀yR(f(y),y)")));
+        // Bound occurrences are left alone.
+        assert_eq!(f("∀xP(x)").substitute_term('x', &t("c()")), f("∀xP(x)"));
+    }
+
+    #[test]
+    fn alpha_equivalence() {
+        assert!(f("∀xP(x)").alpha_eq(&f("∀yP(y)")));
+        assert!(f("∀x∃yR(x,y)").alpha_eq(&f("∀y∃xR(y,x)")));
+        assert!(!f("∀x∃yR(x,y)").alpha_eq(&f("∀x∃yR(y,x)")));
+        assert!(!f("∀xP(y)").alpha_eq(&f("∀yP(y)")));
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+
+use crate::formula::Formula;
+use crate::proof::{CheckUpResult, Proof, SemanticError};
+use crate::record::{RecordError, Statement};
+
+/// What a proof proves: the goal follows from the premises.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theorem {
+    pub premises: Vec<Formula>,
+    pub goal: Formula,
+}
+
+impl Theorem {
+    /// Reads a theorem, as in `a⇒b, a ⊢ b`: the premises are separated by commas out of
+    /// parentheses, and may be left out as in `⊢ a⇒a`.
+    pub fn read(input: &str) -> Result<Self, RecordError> {
+        let (premises, goal) = input.split_once('⊢').ok_or(RecordError::InvalidTheorem)?;
+        let goal = Formula::read(goal.trim()).map_err(RecordError::InvalidFormula)?;
+        let mut formulas = Vec::new();
+        let mut depth = 0;
+        let mut start = 0;
+        for (pos, c) in premises.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    formulas.push(&premises[start..pos]);
+                    start = pos + 1;
+                }
+                _ => (),
+            }
+        }
+        formulas.push(&premises[start..]);
+        if formulas.len() == 1 && formulas[0].trim().is_empty() {
+            formulas.clear();
+        }
+        let premises = formulas
+            .into_iter()
+            .map(|f| Formula::read(f.trim()).map_err(RecordError::InvalidFormula))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { premises, goal })
+    }
+
+    /// Tells whether `formula` is one of the premises, up to the rewritings of `Rwrt`.
+    pub fn has_premise(&self, formula: &Formula) -> bool {
+        let formula = formula.clone().normalize();
+        self.premises
+            .iter()
+            .any(|premise| premise.clone().normalize() == formula)
+    }
+}
+
+impl fmt::Display for Theorem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let premises: Vec<String> = self.premises.iter().map(|p| p.to_string()).collect();
+        if premises.is_empty() {
+            write!(f, "⊢ {}", self.goal)
+        } else {
+            write!(f, "{} ⊢ {}", premises.join(", "), self.goal)
+        }
+    }
+}
+
+impl Proof {
+    /// Returns the theorem a valid proof proves: the declared one, or else its last formula
+    /// following from the premises and the hypotheses left open. A proof ending inside a
+    /// `Soit` or a `Monde` box proves no theorem.
+    pub fn proven_theorem(&self) -> Option<Theorem> {
+        if !matches!(self.state(), CheckUpResult::Valid) {
+            return None;
+        }
+        if let Some(theorem) = self.theorem() {
+            return Some(theorem.clone());
+        }
+        let last = self.records().last()?;
+        let mut premises: Vec<Formula> = self
+            .records()
+            .iter()
+            .filter_map(|rec| match &rec.stmt {
+                Statement::Premise(f) => Some(f.clone()),
+                _ => None,
+            })
+            .collect();
+        for hyp in &last.ctxt {
+            match &self.records()[*hyp].stmt {
+                Statement::Supposons(f) => premises.push(f.clone()),
+                _ => return None,
+            }
+        }
+        Some(Theorem {
+            premises,
+            goal: last.stmt.get_formula().clone(),
+        })
+    }
+
+    /// Checks the last record proves the declared goal, out of every box.
+    pub(crate) fn check_goal(&self) -> Result<(), SemanticError> {
+        let (Some(theorem), Some(last)) = (self.theorem(), self.records().last()) else {
+            return Ok(());
+        };
+        if !last.ctxt.is_empty() {
+            return Err(SemanticError::GoalInBox);
+        }
+        let formula = last.stmt.get_formula();
+        if formula.clone().normalize() != theorem.goal.clone().normalize() {
+            return Err(SemanticError::GoalNotProved(Box::new(formula.clone())));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(input: &str) -> Proof {
+        let mut proof = Proof::read_proof(input).unwrap();
+        proof.check();
+        proof
+    }
+
+    #[test]
+    fn read_theorem() {
+        let theorem = Theorem::read("a⇒b, P(x,y) ⊢ b").unwrap();
+        assert_eq!(theorem.premises.len(), 2);
+        assert_eq!(theorem.to_string(), "a⇒b, P(x,y) ⊢ b");
+        assert_eq!(Theorem::read("⊢ a⇒a").unwrap().to_string(), "⊢ a⇒a");
+        assert!(matches!(
+            Theorem::read("a⇒a"),
+            Err(RecordError::InvalidTheorem)
+        ));
+    }
+
+    #[test]
+    fn premises() {
+        let input = "Théorème a⇒b, a ⊢ b\n\
+                     0;;Prémisse a⇒b;Hyp\n\
+                     1;;Prémisse a;Hyp\n\
+                     2;;b;EImpl 1 0\n";
+        let proof = check(input);
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        assert_eq!(proof.proven_theorem().unwrap().to_string(), "a⇒b, a ⊢ b");
+        assert_eq!(proof.to_string(), input);
+        // A premise holds in the world of the goal only, like a hypothesis.
+        let proof = check(
+            "Théorème a ⊢ □a\n0;;Prémisse a;Hyp\n1;1;Monde;Hyp\n2;1;a;Rwrt 0\n3;;Donc □a;INec\n",
+        );
+        match proof.state() {
+            CheckUpResult::SemanticErrors { errors, .. } => assert!(matches!(
+                errors[..],
+                [(2, SemanticError::RwrtIncompatibleCtxt), ..]
+            )),
+            _ => panic!("a premise cannot be cited from a Monde box"),
+        }
+        let proof = check(
+            "Théorème □a ⊢ □(a∨b)\n0;;Prémisse □a;Hyp\n1;1;Monde;Hyp\n2;1;a;ENec 0\n\
+             3;1;a∨b;IOrR 2 b\n4;;Donc □(a∨b);INec\n",
+        );
+        assert!(matches!(proof.state(), CheckUpResult::Valid));
+        let proof = check("0;0;Supposons a;Hyp\n1;0;Prémisse a;Hyp\n");
+        match proof.state() {
+            CheckUpResult::SemanticErrors { errors, .. } => {
+                assert!(matches!(errors[..], [(1, SemanticError::PremiseInBox)]))
+            }
+            _ => panic!("a premise is out of every box"),
+        }
+    }
+
+    #[test]
+    fn wrong_goal() {
+        let proof = check("Théorème a ⊢ a∧a\n0;;Prémisse a;Hyp\n1;;a;Rwrt 0\n");
+        match proof.state() {
+            CheckUpResult::SemanticErrors { errors, .. } => {
+                assert!(matches!(errors[..], [(1, SemanticError::GoalNotProved(_))]))
+            }
+            _ => panic!("the goal is not proved"),
+        }
+        let proof = check("Théorème a ⊢ a\n0;;Prémisse b;Hyp\n1;;b;Rwrt 0\n");
+        match proof.state() {
+            CheckUpResult::SemanticErrors { errors, .. } => assert!(matches!(
+                errors[..],
+                [
+                    (0, SemanticError::PremiseNotDeclared(_)),
+                    (1, SemanticError::GoalNotProved(_))
+                ]
+            )),
+            _ => panic!("b is not a premise"),
+        }
+    }
+
+    #[test]
+    fn undeclared_theorem() {
+        let proof = check("0;0;Supposons a;Hyp\n1;0;a;Rwrt 0\n");
+        assert_eq!(proof.proven_theorem().unwrap().to_string(), "a ⊢ a");
+    }
+}
@@ -1,37 +1,341 @@
 use std::process::ExitCode;
 
-use clap::Parser;
-use dnlib::proof::CheckUpResult;
+use clap::{Parser, Subcommand, ValueEnum};
+use dnlib::derived::{DerivedRule, DerivedRuleError};
+use dnlib::diff::ProofDiff;
+use dnlib::formula::{Formula, NotPropositional, TokenizationError};
+use dnlib::hilbert::{self, DeductionError, HilbertError, HilbertProof};
+use dnlib::kripke::{KripkeModel, Verdict};
+use dnlib::lint::{Level, LintError, LintLevels, Warning};
+use dnlib::lambda::{self, Extraction, ExtractionError, Lambda, TypeError};
+use dnlib::minimize::{Minimization, TruthTable, TruthTableError};
+use dnlib::modal::ModalSystem;
+use dnlib::normal::{Normalization, NormalizationError};
+use dnlib::proof::{CheckUpResult, Logic};
 use dnlib::proof;
+use dnlib::hint::Hint;
+use dnlib::prover::{self, GapError, ProverError};
+use dnlib::resolution::{self, CnfError, ResolutionError, ResolutionProof};
+use dnlib::rule::RuleRegistry;
+use dnlib::ruleset;
+use dnlib::sequent::{self, Calculus, SequentError, SequentProof, TranslationError};
+use dnlib::simplify::Simplification;
+use dnlib::stats::Statistics;
+use dnlib::tableau::Tableau;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
-    filename: std::path::PathBuf,
+    /// Proof file to check
+    filename: Option<std::path::PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks a proof file
+    Check {
+        filename: std::path::PathBuf,
+        /// Logic the proof is checked in
+        #[arg(long, value_enum, default_value_t = LogicArg::Classical)]
+        logic: LogicArg,
+        /// Lists the records that depend on a classical step
+        #[arg(long)]
+        classical: bool,
+        /// Lists the records the conclusion does not need, and the unused hypotheses
+        #[arg(long)]
+        unused: bool,
+        /// Modal system the necessity rules follow
+        #[arg(long, value_enum, default_value_t = ModalArg::K)]
+        modal: ModalArg,
+        /// File declaring a derived rule the proof may cite, the option can be repeated
+        #[arg(long)]
+        rule: Vec<std::path::PathBuf>,
+        /// File declaring the rules the proof is checked with, instead of the INF402 ones
+        #[arg(long)]
+        rule_set: Option<std::path::PathBuf>,
+    },
+    /// Simplifies a formula, explaining every step
+    Simplify { formula: String },
+    /// Computes a minimal DNF (or CNF) of a formula
+    Minimize {
+        formula: String,
+        /// Computes a minimal CNF instead of a DNF
+        #[arg(long)]
+        cnf: bool,
+    },
+    /// Synthesizes a minimal formula from a truth table, such as `abc 00010111`
+    Synth {
+        /// Variables, the first one being the most significant
+        variables: String,
+        /// Values of the function, from the row where every variable is false
+        values: String,
+        /// Computes a minimal CNF instead of a DNF
+        #[arg(long)]
+        cnf: bool,
+    },
+    /// Decides whether a formula is intuitionistically valid, giving a Kripke countermodel
+    /// when it is not
+    Intuitionistic { formula: String },
+    /// Looks for a small Kripke countermodel of a modal formula
+    Modal {
+        formula: String,
+        /// Modal system whose frames are searched
+        #[arg(long, value_enum, default_value_t = ModalArg::K)]
+        system: ModalArg,
+        /// Maximal number of worlds of the countermodel
+        #[arg(long, default_value_t = 3)]
+        worlds: usize,
+    },
+    /// Checks a sequent calculus proof file
+    Sequent {
+        filename: std::path::PathBuf,
+        /// Checks the proof in LJ instead of LK
+        #[arg(long)]
+        lj: bool,
+    },
+    /// Translates a natural-deduction proof file into an LK derivation
+    ToSequent { filename: std::path::PathBuf },
+    /// Reports the flaws of a valid proof file, which may be allowed or denied by comment lines
+    /// such as `(* allow: unused-record *)`
+    Lint {
+        filename: std::path::PathBuf,
+        /// Logic the proof is checked in
+        #[arg(long, value_enum, default_value_t = LogicArg::Classical)]
+        logic: LogicArg,
+        /// Lint not to report, the option can be repeated
+        #[arg(long)]
+        allow: Vec<String>,
+        /// Lint to report as a warning, the option can be repeated
+        #[arg(long)]
+        warn: Vec<String>,
+        /// Lint to report as an error, the option can be repeated
+        #[arg(long)]
+        deny: Vec<String>,
+    },
+    /// Measures a valid proof file: records, depth, rules used, formula size…
+    Stats {
+        filename: std::path::PathBuf,
+        /// Logic the proof is checked in
+        #[arg(long, value_enum, default_value_t = LogicArg::Classical)]
+        logic: LogicArg,
+    },
+    /// Prints the dependency graph of a proof file in the DOT language of Graphviz
+    Graph { filename: std::path::PathBuf },
+    /// Compares two versions of a proof file record by record, following their renumbering
+    Diff {
+        old: std::path::PathBuf,
+        new: std::path::PathBuf,
+    },
+    /// Translates an intuitionistic natural-deduction proof file into a λ-term
+    ToLambda { filename: std::path::PathBuf },
+    /// Type-checks a λ-term against a formula, printing the proof it stands for
+    FromLambda {
+        term: String,
+        formula: String,
+        /// Type of a free variable, as `x:a∧b`, the option can be repeated
+        #[arg(long)]
+        var: Vec<String>,
+    },
+    /// Checks a Hilbert-style proof file
+    Hilbert {
+        filename: std::path::PathBuf,
+        /// Prints the proof without this hypothesis given by the deduction theorem
+        #[arg(long)]
+        discharge: Option<String>,
+    },
+    /// Looks for a resolution refutation of the CNF of a formula
+    Refute { formula: String },
+    /// Checks a resolution refutation file
+    Resolution {
+        filename: std::path::PathBuf,
+        /// Formula whose CNF the Hyp clauses have to come from
+        #[arg(long)]
+        premises: Option<String>,
+    },
+    /// Expands the analytic tableau of a formula, which closes if the formula is valid
+    Tableau {
+        formula: String,
+        /// Prints the tableau for the LaTeX forest package
+        #[arg(long)]
+        latex: bool,
+    },
+    /// Looks for a natural-deduction proof of a formula
+    Prove {
+        formula: String,
+        /// Hypothesis the proof may use, the option can be repeated
+        #[arg(long)]
+        premise: Vec<String>,
+        /// Time given to the search, in seconds
+        #[arg(long, default_value_t = 5)]
+        timeout: u64,
+    },
+    /// Lists the rules that apply at the end of a proof file
+    Hint {
+        filename: std::path::PathBuf,
+        /// Formula the proof is meant to prove, used to rank the hints
+        #[arg(long)]
+        goal: Option<String>,
+        /// Logic whose rules are suggested
+        #[arg(long, value_enum, default_value_t = LogicArg::Classical)]
+        logic: LogicArg,
+        /// File declaring the rules suggested, instead of the INF402 ones
+        #[arg(long)]
+        rule_set: Option<std::path::PathBuf>,
+    },
+    /// Replaces the `?` justifications of a proof file with derivations
+    Fill {
+        filename: std::path::PathBuf,
+        /// Logic the derivations have to hold in
+        #[arg(long, value_enum, default_value_t = LogicArg::Classical)]
+        logic: LogicArg,
+        /// Time given to the search, in seconds
+        #[arg(long, default_value_t = 5)]
+        timeout: u64,
+    },
+    /// Eliminates the detours of a proof file, printing its normal form
+    Normalize {
+        filename: std::path::PathBuf,
+        /// Logic the proof is checked in
+        #[arg(long, value_enum, default_value_t = LogicArg::Classical)]
+        logic: LogicArg,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogicArg {
+    Classical,
+    Intuitionistic,
+    Minimal,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ModalArg {
+    K,
+    T,
+    S4,
+    S5,
+}
+
+impl From<ModalArg> for ModalSystem {
+    fn from(system: ModalArg) -> Self {
+        match system {
+            ModalArg::K => ModalSystem::K,
+            ModalArg::T => ModalSystem::T,
+            ModalArg::S4 => ModalSystem::S4,
+            ModalArg::S5 => ModalSystem::S5,
+        }
+    }
+}
+
+impl From<LogicArg> for Logic {
+    fn from(logic: LogicArg) -> Self {
+        match logic {
+            LogicArg::Classical => Logic::Classical,
+            LogicArg::Intuitionistic => Logic::Intuitionistic,
+            LogicArg::Minimal => Logic::Minimal,
+        }
+    }
 }
 
 //#[derive(Debug)]
 enum Answer {
     ErrorReadingTheFile(std::io::Error),
     ErrorReadingTheProof(proof::ReadError),
+    InvalidDerivedRule(std::path::PathBuf, DerivedRuleError),
+    InvalidRuleSet(std::path::PathBuf, ruleset::ReadError),
+    ErrorReadingTheSequentProof(sequent::ReadError),
+    ErrorReadingTheHilbertProof(hilbert::ReadError),
+    ErrorReadingTheResolutionProof(resolution::ReadError),
+    ErrorReadingTheFormula(TokenizationError),
+    NotPropositional(NotPropositional),
+    ErrorConvertingToCnf(CnfError),
+    ErrorInTheTruthTable(TruthTableError),
     ErrorsInTheProof{
         first_error: usize,
         errors: Vec<(usize, proof::SemanticError)>,
     },
+    ErrorsInTheSequentProof(Vec<(usize, SequentError)>),
+    NotTranslated(TranslationError),
+    ErrorReadingTheTerm(lambda::ReadError),
+    Extracted(Extraction),
+    NotExtracted(ExtractionError),
+    IllTyped(TypeError),
+    ErrorsInTheHilbertProof(Vec<(usize, HilbertError)>),
+    NotDischarged(DeductionError),
+    ErrorsInTheResolutionProof(Vec<(usize, ResolutionError)>),
     InternalError(usize),
-    AllRight,
+    AllRight { notes: Vec<String> },
+    Simplified(Simplification),
+    Minimized(TruthTable, Minimization),
+    Decided(Formula, Verdict),
+    ModalSearch(Formula, ModalSystem, Option<KripkeModel>),
+    Translated(SequentProof),
+    Discharged(HilbertProof),
+    Refuted(Formula, Option<ResolutionProof>),
+    Expanded { formula: Formula, tableau: Tableau, latex: bool },
+    Proved(proof::Proof),
+    NotProved(ProverError),
+    NotFilled(proof::Proof, Vec<(usize, GapError)>),
+    Hints(Vec<Hint>),
+    Graph(String),
+    Compared(ProofDiff),
+    Measured(Statistics),
+    InvalidLint(LintError),
+    Linted(Vec<Warning>),
+    Normalized(Normalization),
+    NotNormalized(NormalizationError),
+    NoCommand,
 }
 
 impl std::process::Termination for Answer {
     fn report(self) -> std::process::ExitCode {
         match self {
             Answer::ErrorReadingTheFile(e) => {
-                println!("Error reading the file: {}", e.to_string());
+                println!("Error reading the file: {}", e);
                 ExitCode::FAILURE
             },
             Answer::ErrorReadingTheProof(e) => {
                 println!("Error reading the proof: {}", e);
                 ExitCode::FAILURE
             },
+            Answer::InvalidDerivedRule(filename, e) => {
+                println!("Error in the derived rule of {}: {}", filename.display(), e);
+                ExitCode::FAILURE
+            },
+            Answer::InvalidRuleSet(filename, e) => {
+                println!("Error in the rule set of {}: {}", filename.display(), e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorReadingTheSequentProof(e) => {
+                println!("Error reading the sequent proof: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorReadingTheHilbertProof(e) => {
+                println!("Error reading the Hilbert proof: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorReadingTheResolutionProof(e) => {
+                println!("Error reading the resolution proof: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorReadingTheFormula(e) => {
+                println!("Error reading the formula: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::NotPropositional(e) => {
+                println!("{}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorConvertingToCnf(e) => {
+                println!("{}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorInTheTruthTable(e) => {
+                println!("Error in the truth table: {}", e);
+                ExitCode::FAILURE
+            },
             Answer::ErrorsInTheProof { first_error, errors } => {
                 println!("The first wrong record is: {}", first_error);
                 println!("The following errors occured:");
@@ -40,33 +344,682 @@ impl std::process::Termination for Answer {
                 }
                 ExitCode::FAILURE
             },
+            Answer::ErrorsInTheSequentProof(errors) => {
+                println!("The following errors occured:");
+                for error in errors {
+                    println!("  At record {}: {}", error.0, error.1)
+                }
+                ExitCode::FAILURE
+            },
+            Answer::NotTranslated(e) => {
+                println!("The proof cannot be translated: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorReadingTheTerm(e) => {
+                println!("Error reading the term: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::NotExtracted(e) => {
+                println!("The proof has no λ-term: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::IllTyped(e) => {
+                println!("The term is not well typed: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorsInTheHilbertProof(errors) => {
+                println!("The following errors occured:");
+                for error in errors {
+                    println!("  At record {}: {}", error.0, error.1)
+                }
+                ExitCode::FAILURE
+            },
+            Answer::NotDischarged(e) => {
+                println!("The hypothesis cannot be discharged: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::ErrorsInTheResolutionProof(errors) => {
+                println!("The following errors occured:");
+                for error in errors {
+                    println!("  At record {}: {}", error.0, error.1)
+                }
+                ExitCode::FAILURE
+            },
             Answer::InternalError(no) => {
                 println!("An internal error occured (#{}).", no);
                 ExitCode::FAILURE
             }
-            Answer::AllRight => {
+            Answer::AllRight { notes } => {
                 println!("Proof is valid");
+                for note in notes {
+                    println!("{}", note)
+                }
+                ExitCode::SUCCESS
+            },
+            Answer::Simplified(simplification) => {
+                print!("{}", simplification);
+                println!("Simplified: {}", simplification.result);
                 ExitCode::SUCCESS
             },
+            Answer::Minimized(table, minimization) => {
+                if let Some(map) = table.karnaugh() {
+                    println!("Karnaugh map:");
+                    println!("{}", map);
+                }
+                print!("{}", minimization);
+                ExitCode::SUCCESS
+            },
+            Answer::Decided(formula, Verdict::Valid) => {
+                println!("{} is intuitionistically valid", formula);
+                ExitCode::SUCCESS
+            },
+            Answer::Decided(formula, Verdict::Refuted(model)) => {
+                println!("{} is not intuitionistically valid, w0 does not force it in:", formula);
+                print!("{}", model);
+                ExitCode::FAILURE
+            },
+            Answer::ModalSearch(formula, system, None) => {
+                println!("No small countermodel of {} was found in {}", formula, system);
+                ExitCode::SUCCESS
+            },
+            Answer::ModalSearch(formula, system, Some(model)) => {
+                println!("{} is not valid in {}, it is false at w0 in:", formula, system);
+                print!("{}", model.display_modal());
+                ExitCode::FAILURE
+            },
+            Answer::Translated(derivation) => {
+                print!("{}", derivation);
+                ExitCode::SUCCESS
+            },
+            Answer::Extracted(extraction) => {
+                println!("{}", extraction);
+                ExitCode::SUCCESS
+            },
+            Answer::Discharged(proof) => {
+                print!("{}", proof);
+                ExitCode::SUCCESS
+            },
+            Answer::Refuted(formula, Some(refutation)) => {
+                println!("{} is unsatisfiable:", formula);
+                print!("{}", refutation);
+                ExitCode::SUCCESS
+            },
+            Answer::Refuted(formula, None) => {
+                println!("{} is satisfiable, its CNF has no refutation", formula);
+                ExitCode::FAILURE
+            },
+            Answer::Expanded { formula, tableau, latex } => {
+                if latex {
+                    print!("{}", tableau.to_latex());
+                } else {
+                    print!("{}", tableau);
+                }
+                if tableau.is_closed() {
+                    println!("The tableau is closed, {} is valid", formula);
+                    return ExitCode::SUCCESS;
+                }
+                println!("The tableau is open, {} is false when:", formula);
+                let countermodels: std::collections::BTreeSet<_> = tableau.countermodels().into_iter().collect();
+                for valuation in countermodels {
+                    let vars: Vec<String> = valuation.iter().map(|v| v.to_string()).collect();
+                    match vars.is_empty() {
+                        true => println!("  every variable is false"),
+                        false => println!("  {} true, every other variable false", vars.join(", ")),
+                    }
+                }
+                ExitCode::FAILURE
+            },
+            Answer::Proved(proof) => {
+                print!("{}", proof);
+                ExitCode::SUCCESS
+            },
+            Answer::NotProved(e) => {
+                println!("No proof was found: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::Hints(hints) => {
+                if hints.is_empty() {
+                    println!("No rule applies");
+                }
+                for (rank, hint) in hints.iter().enumerate() {
+                    println!("{}. {}", rank + 1, hint);
+                }
+                ExitCode::SUCCESS
+            },
+            Answer::InvalidLint(e) => {
+                println!("Error in the lint levels: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::Linted(warnings) => {
+                if warnings.is_empty() {
+                    println!("No warning");
+                }
+                for warning in &warnings {
+                    println!("{}", warning);
+                }
+                if warnings.iter().any(|w| w.level == Level::Deny) {
+                    ExitCode::FAILURE
+                } else {
+                    ExitCode::SUCCESS
+                }
+            },
+            Answer::Measured(stats) => {
+                print!("{}", stats);
+                ExitCode::SUCCESS
+            },
+            Answer::Graph(dot) => {
+                print!("{}", dot);
+                ExitCode::SUCCESS
+            },
+            Answer::Compared(diff) => {
+                if diff.is_empty() {
+                    println!("The proofs have the same records.");
+                } else {
+                    print!("{}", diff);
+                }
+                ExitCode::SUCCESS
+            },
+            Answer::NotFilled(proof, errors) => {
+                print!("{}", proof);
+                println!("The following placeholders are left:");
+                for error in errors {
+                    println!("  At record {}: {}", error.0, error.1)
+                }
+                ExitCode::FAILURE
+            },
+            Answer::Normalized(normal) => {
+                for detour in &normal.detours {
+                    println!("Eliminated {}", detour);
+                }
+                print!("{}", normal.proof);
+                if normal.has_subformula_property() {
+                    println!("The proof has the subformula property");
+                } else {
+                    let outside: Vec<String> = normal.outside.iter().map(|id| id.to_string()).collect();
+                    println!("The formulas of the records {} break the subformula property", outside.join(", "));
+                }
+                if !normal.remaining.is_empty() {
+                    println!("The following detours are left:");
+                    for (id, detour) in &normal.remaining {
+                        println!("  At record {}: {}", id, detour);
+                    }
+                }
+                ExitCode::SUCCESS
+            },
+            Answer::NotNormalized(e) => {
+                println!("The proof was not normalized: {}", e);
+                ExitCode::FAILURE
+            },
+            Answer::NoCommand => {
+                println!("Nothing to do, give a proof file or a command (see --help).");
+                ExitCode::FAILURE
+            },
         }
     }
 }
 
-fn main() -> Answer {
-    let args = Cli::parse();
-    let content = match std::fs::read_to_string(&args.filename) {
+fn check(
+    filename: &std::path::Path,
+    logic: Logic,
+    modal_system: ModalSystem,
+    classical: bool,
+    unused: bool,
+    rule_files: &[std::path::PathBuf],
+    rule_set: Option<&std::path::Path>,
+) -> Answer {
+    let rule_set = match rule_set {
+        Some(rule_set_file) => {
+            let content = match std::fs::read_to_string(rule_set_file) {
+                Ok(o) => o,
+                Err(e) => return Answer::ErrorReadingTheFile(e),
+            };
+            match RuleRegistry::read_rule_set(&content) {
+                Ok(rules) => rules,
+                Err(e) => return Answer::InvalidRuleSet(rule_set_file.to_path_buf(), e),
+            }
+        }
+        None => RuleRegistry::standard().clone(),
+    };
+    let mut rules: Vec<DerivedRule> = Vec::new();
+    for rule_file in rule_files {
+        let content = match std::fs::read_to_string(rule_file) {
+            Ok(o) => o,
+            Err(e) => return Answer::ErrorReadingTheFile(e),
+        };
+        match DerivedRule::read(&content, &rules) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => return Answer::InvalidDerivedRule(rule_file.clone(), e),
+        }
+    }
+    let content = match std::fs::read_to_string(filename) {
         Ok(o) => o,
         Err(e) => return Answer::ErrorReadingTheFile(e),
     };
-    let mut proof = match proof::Proof::read_proof(&content) {
+    let mut proof = match proof::Proof::read_proof_with(&content, rule_set) {
         Ok(o) => o,
         Err(e) => return Answer::ErrorReadingTheProof(e),
     };
+    for rule in rules {
+        proof.add_rule(rule);
+    }
+    proof.set_logic(logic);
+    proof.set_modal_system(modal_system);
     proof.check();
+    let mut notes = Vec::new();
+    if let Some(theorem) = proof.proven_theorem() {
+        notes.push(format!("Proved: {}", theorem));
+    }
+    if classical {
+        let deps = proof.classical_dependencies();
+        if deps.iter().all(|d| d.is_empty()) {
+            notes.push("No record depends on a classical step.".to_string());
+        }
+        for (id, dep) in deps.iter().enumerate().filter(|(_, d)| !d.is_empty()) {
+            let steps: Vec<String> = dep.iter().map(|d| d.to_string()).collect();
+            notes.push(format!("  Record {} depends on classical step(s) {}", id, steps.join(", ")));
+        }
+    }
+    let valid = !matches!(proof.state(), CheckUpResult::SemanticErrors { .. });
+    if unused && valid {
+        let dead = proof.dead_records();
+        if dead.is_empty() {
+            notes.push("Every record is used.".to_string());
+        }
+        for id in dead.steps {
+            notes.push(format!("  Record {} is not used to reach the conclusion", id));
+        }
+        for id in dead.hypotheses {
+            notes.push(format!("  Hypothesis {} is not used before the end of its box", id));
+        }
+    }
     match proof.into_state() {
         CheckUpResult::NotChecked => Answer::InternalError(0),
         CheckUpResult::ValidUntil(_) => Answer::InternalError(1),
         CheckUpResult::SemanticErrors { first_error, errors } => Answer::ErrorsInTheProof { first_error , errors },
-        CheckUpResult::Valid => Answer::AllRight,
+        CheckUpResult::Valid => Answer::AllRight { notes },
+    }
+}
+
+fn check_sequent(filename: &std::path::Path, calculus: Calculus) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match SequentProof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheSequentProof(e),
+    };
+    proof.set_calculus(calculus);
+    match (proof.check(), proof.conclusion()) {
+        (Ok(()), Some(conclusion)) => Answer::AllRight {
+            notes: vec![format!("It derives {}", conclusion)],
+        },
+        (Ok(()), None) => Answer::AllRight { notes: Vec::new() },
+        (Err(errors), _) => Answer::ErrorsInTheSequentProof(errors),
+    }
+}
+
+fn to_sequent(filename: &std::path::Path) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match proof::Proof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    proof.check();
+    match proof.to_sequent_calculus() {
+        Ok(derivation) => Answer::Translated(derivation),
+        Err(e) => Answer::NotTranslated(e),
+    }
+}
+
+fn lint(filename: &std::path::Path, logic: Logic, levels: [(&[String], Level); 3]) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match proof::Proof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    let mut lint_levels = LintLevels::default();
+    if let Err(e) = lint_levels.read_directives(&content) {
+        return Answer::InvalidLint(e);
+    }
+    for (codes, level) in levels {
+        for code in codes {
+            if let Err(e) = lint_levels.set_code(code, level) {
+                return Answer::InvalidLint(e);
+            }
+        }
+    }
+    proof.set_logic(logic);
+    proof.check();
+    // The lints assume a well-formed proof, errors are reported instead
+    let valid = !matches!(proof.state(), CheckUpResult::SemanticErrors { .. });
+    let warnings = if valid { proof.lint(&lint_levels) } else { Vec::new() };
+    match proof.into_state() {
+        CheckUpResult::SemanticErrors { first_error, errors } => Answer::ErrorsInTheProof { first_error, errors },
+        _ => Answer::Linted(warnings),
+    }
+}
+
+fn stats(filename: &std::path::Path, logic: Logic) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match proof::Proof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    proof.set_logic(logic);
+    proof.check();
+    let stats = proof.statistics();
+    match proof.into_state() {
+        CheckUpResult::SemanticErrors { first_error, errors } => Answer::ErrorsInTheProof { first_error, errors },
+        _ => Answer::Measured(stats),
+    }
+}
+
+fn graph(filename: &std::path::Path) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    match proof::Proof::read_proof(&content) {
+        Ok(proof) => Answer::Graph(proof.to_dot()),
+        Err(e) => Answer::ErrorReadingTheProof(e),
+    }
+}
+
+fn diff(old: &std::path::Path, new: &std::path::Path) -> Answer {
+    let mut proofs = Vec::new();
+    for filename in [old, new] {
+        let content = match std::fs::read_to_string(filename) {
+            Ok(o) => o,
+            Err(e) => return Answer::ErrorReadingTheFile(e),
+        };
+        match proof::Proof::read_proof(&content) {
+            Ok(proof) => proofs.push(proof),
+            Err(e) => return Answer::ErrorReadingTheProof(e),
+        }
+    }
+    Answer::Compared(proofs[0].diff(&proofs[1]))
+}
+
+fn to_lambda(filename: &std::path::Path) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let proof = match proof::Proof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    match proof.lambda_term() {
+        Ok(extraction) => Answer::Extracted(extraction),
+        Err(e) => Answer::NotExtracted(e),
+    }
+}
+
+fn from_lambda(term: &str, formula: &str, vars: &[String]) -> Answer {
+    let term = match Lambda::read(term) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheTerm(e),
+    };
+    let formula = match Formula::read(formula) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFormula(e),
+    };
+    let mut context = Vec::new();
+    for var in vars {
+        let (name, type_) = var.split_once(':').unwrap_or((var, ""));
+        match Formula::read(type_.trim()) {
+            Ok(o) => context.push((name.trim().to_string(), o)),
+            Err(e) => return Answer::ErrorReadingTheFormula(e),
+        }
+    }
+    match term.proof(&context, &formula) {
+        Ok(proof) => Answer::Proved(proof),
+        Err(e) => Answer::IllTyped(e),
+    }
+}
+
+fn check_hilbert(filename: &std::path::Path, discharge: Option<String>) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let proof = match HilbertProof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheHilbertProof(e),
+    };
+    if let Err(errors) = proof.check() {
+        return Answer::ErrorsInTheHilbertProof(errors);
+    }
+    match discharge {
+        Some(h) => match Formula::read(&h) {
+            Ok(h) => match proof.deduction(&h) {
+                Ok(discharged) => Answer::Discharged(discharged),
+                Err(e) => Answer::NotDischarged(e),
+            },
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        None => {
+            let hypotheses: Vec<String> = proof.hypotheses().iter().map(|h| h.to_string()).collect();
+            let notes = match hypotheses.is_empty() {
+                true => vec!["It assumes no hypothesis".to_string()],
+                false => vec![format!("It assumes {}", hypotheses.join(", "))],
+            };
+            Answer::AllRight { notes }
+        }
+    }
+}
+
+fn check_resolution(filename: &std::path::Path, premises: Option<String>) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match ResolutionProof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheResolutionProof(e),
+    };
+    if let Some(premises) = premises {
+        match Formula::read(&premises).map(|f| f.clauses()) {
+            Ok(Ok(clauses)) => proof.set_premises(clauses),
+            Ok(Err(e)) => return Answer::ErrorConvertingToCnf(e),
+            Err(e) => return Answer::ErrorReadingTheFormula(e),
+        }
+    }
+    match proof.check() {
+        Ok(()) => Answer::AllRight { notes: Vec::new() },
+        Err(errors) => Answer::ErrorsInTheResolutionProof(errors),
+    }
+}
+
+fn prove(formula: &str, premises: &[String], timeout: u64) -> Answer {
+    let goal = match Formula::read(formula) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFormula(e),
+    };
+    let mut hypotheses = Vec::new();
+    for premise in premises {
+        match Formula::read(premise) {
+            Ok(o) => hypotheses.push(o),
+            Err(e) => return Answer::ErrorReadingTheFormula(e),
+        }
+    }
+    match prover::prove(&hypotheses, &goal, std::time::Duration::from_secs(timeout)) {
+        Ok(proof) => Answer::Proved(proof),
+        Err(e) => Answer::NotProved(e),
+    }
+}
+
+fn hint(
+    filename: &std::path::Path,
+    goal: Option<String>,
+    logic: Logic,
+    rule_set: Option<&std::path::Path>,
+) -> Answer {
+    let rule_set = match rule_set {
+        Some(rule_set_file) => {
+            let content = match std::fs::read_to_string(rule_set_file) {
+                Ok(o) => o,
+                Err(e) => return Answer::ErrorReadingTheFile(e),
+            };
+            match RuleRegistry::read_rule_set(&content) {
+                Ok(rules) => rules,
+                Err(e) => return Answer::InvalidRuleSet(rule_set_file.to_path_buf(), e),
+            }
+        }
+        None => RuleRegistry::standard().clone(),
+    };
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match proof::Proof::read_proof_with(&content, rule_set) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    proof.set_logic(logic);
+    let goal = match goal.map(|g| Formula::read(&g)).transpose() {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFormula(e),
+    };
+    Answer::Hints(proof.hints(goal.as_ref()))
+}
+
+fn fill(filename: &std::path::Path, logic: Logic, timeout: u64) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match proof::Proof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    proof.set_logic(logic);
+    if let Err(errors) = proof.fill_gaps(std::time::Duration::from_secs(timeout)) {
+        return Answer::NotFilled(proof, errors);
+    }
+    // The filled proof may still be wrong elsewhere
+    proof.check();
+    if !matches!(proof.state(), CheckUpResult::SemanticErrors { .. }) {
+        return Answer::Proved(proof);
+    }
+    match proof.into_state() {
+        CheckUpResult::SemanticErrors { first_error, errors } => Answer::ErrorsInTheProof { first_error, errors },
+        _ => unreachable!(),
+    }
+}
+
+fn normalize(filename: &std::path::Path, logic: Logic) -> Answer {
+    let content = match std::fs::read_to_string(filename) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheFile(e),
+    };
+    let mut proof = match proof::Proof::read_proof(&content) {
+        Ok(o) => o,
+        Err(e) => return Answer::ErrorReadingTheProof(e),
+    };
+    proof.set_logic(logic);
+    match proof.normal_form() {
+        Ok(normal) => Answer::Normalized(normal),
+        Err(e) => Answer::NotNormalized(e),
+    }
+}
+
+fn minimize(table: TruthTable, cnf: bool) -> Answer {
+    let minimization = if cnf {
+        table.minimal_cnf()
+    } else {
+        table.minimal_dnf()
+    };
+    Answer::Minimized(table, minimization)
+}
+
+fn main() -> Answer {
+    let args = Cli::parse();
+    match (args.command, args.filename) {
+        (Some(Command::Check { filename, logic, classical, unused, modal, rule, rule_set }), _) => {
+            check(&filename, logic.into(), modal.into(), classical, unused, &rule, rule_set.as_deref())
+        }
+        (None, Some(filename)) => {
+            check(&filename, Logic::Classical, ModalSystem::K, false, false, &[], None)
+        }
+        (Some(Command::Simplify { formula }), _) => match Formula::read(&formula) {
+            Ok(f) => Answer::Simplified(f.simplify()),
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        (Some(Command::Minimize { formula, cnf }), _) => match Formula::read(&formula) {
+            Ok(f) => match f.truth_table() {
+                Ok(table) => minimize(table, cnf),
+                Err(e) => Answer::ErrorInTheTruthTable(e),
+            },
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        (Some(Command::Synth { variables, values, cnf }), _) => {
+            match TruthTable::read(&variables, &values) {
+                Ok(table) => minimize(table, cnf),
+                Err(e) => Answer::ErrorInTheTruthTable(e),
+            }
+        }
+        (Some(Command::Intuitionistic { formula }), _) => match Formula::read(&formula) {
+            Ok(f) => match f.intuitionistic_validity() {
+                Ok(verdict) => Answer::Decided(f, verdict),
+                Err(e) => Answer::NotPropositional(e),
+            },
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        (Some(Command::Modal { formula, system, worlds }), _) => match Formula::read(&formula) {
+            Ok(f) => match f.modal_countermodel(system.into(), worlds) {
+                Ok(model) => Answer::ModalSearch(f, system.into(), model),
+                Err(e) => Answer::NotPropositional(e),
+            },
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        (Some(Command::Sequent { filename, lj }), _) => {
+            let calculus = if lj { Calculus::Lj } else { Calculus::Lk };
+            check_sequent(&filename, calculus)
+        }
+        (Some(Command::ToSequent { filename }), _) => to_sequent(&filename),
+        (Some(Command::Lint { filename, logic, allow, warn, deny }), _) => lint(
+            &filename,
+            logic.into(),
+            [(&allow, Level::Allow), (&warn, Level::Warn), (&deny, Level::Deny)],
+        ),
+        (Some(Command::Stats { filename, logic }), _) => stats(&filename, logic.into()),
+        (Some(Command::Graph { filename }), _) => graph(&filename),
+        (Some(Command::Diff { old, new }), _) => diff(&old, &new),
+        (Some(Command::ToLambda { filename }), _) => to_lambda(&filename),
+        (Some(Command::FromLambda { term, formula, var }), _) => from_lambda(&term, &formula, &var),
+        (Some(Command::Hilbert { filename, discharge }), _) => check_hilbert(&filename, discharge),
+        (Some(Command::Refute { formula }), _) => match Formula::read(&formula) {
+            Ok(f) => match f.refutation() {
+                Ok(refutation) => Answer::Refuted(f, refutation),
+                Err(e) => Answer::ErrorConvertingToCnf(e),
+            },
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        (Some(Command::Resolution { filename, premises }), _) => check_resolution(&filename, premises),
+        (Some(Command::Tableau { formula, latex }), _) => match Formula::read(&formula) {
+            Ok(f) => match f.tableau() {
+                Ok(tableau) => Answer::Expanded { formula: f, tableau, latex },
+                Err(e) => Answer::NotPropositional(e),
+            },
+            Err(e) => Answer::ErrorReadingTheFormula(e),
+        },
+        (Some(Command::Prove { formula, premise, timeout }), _) => prove(&formula, &premise, timeout),
+        (Some(Command::Hint { filename, goal, logic, rule_set }), _) => {
+            hint(&filename, goal, logic.into(), rule_set.as_deref())
+        }
+        (Some(Command::Fill { filename, logic, timeout }), _) => fill(&filename, logic.into(), timeout),
+        (Some(Command::Normalize { filename, logic }), _) => normalize(&filename, logic.into()),
+        (None, None) => Answer::NoCommand,
     }
 }